@@ -0,0 +1,388 @@
+use super::houses::House;
+use super::map_document::{read_flag, read_num, read_text, write_flag, write_num, write_text, MapDocument};
+use super::triggers::{letters_to_waypoint, TriggerSet};
+use super::waypoints::Waypoint;
+
+/// `[TaskForces]` entry: which units a team is made of.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskForce {
+    pub id: String,
+    pub name: String,
+    pub group: i32,
+    /// `(count, type)` per `N=count,TYPE` line.
+    pub members: Vec<(u32, String)>,
+}
+
+/// `[ScriptTypes]` entry: the orders a team follows, one `N=action,param` line each.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptType {
+    pub id: String,
+    pub name: String,
+    pub actions: Vec<(u32, String)>,
+}
+
+/// `[TeamTypes]` entry. Only the keys below are typed; anything else in the
+/// section is left as it was.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TeamType {
+    pub id: String,
+    pub name: String,
+    pub house: String,
+    pub script: String,
+    pub task_force: String,
+    /// Waypoint letters (`A`, `B`, ...), like trigger actions.
+    pub waypoint: String,
+    pub tag: Option<String>,
+    pub priority: i32,
+    pub max: i32,
+    pub tech_level: i32,
+    pub veteran_level: i32,
+    pub group: i32,
+    pub autocreate: bool,
+    pub prebuild: bool,
+    pub reinforce: bool,
+    pub recruiter: bool,
+    pub full: bool,
+    pub loadable: bool,
+    pub aggressive: bool,
+    pub suicide: bool,
+    pub whiner: bool,
+    pub annoyance: bool,
+    pub guard_slower: bool,
+    pub loose_recruit: bool,
+    pub droppod: bool,
+    pub use_transport_origin: bool,
+    pub on_trans_only: bool,
+    pub is_base_defense: bool,
+    pub only_target_house_enemy: bool,
+    pub are_team_members_recruitable: bool,
+    pub transports_return_on_unload: bool,
+}
+
+impl TaskForce {
+    pub fn new(id: &str) -> Self {
+        Self { id: id.to_string(), name: "New task force".to_string(), group: -1, members: Vec::new() }
+    }
+}
+
+impl ScriptType {
+    pub fn new(id: &str) -> Self {
+        Self { id: id.to_string(), name: "New script".to_string(), actions: Vec::new() }
+    }
+}
+
+impl TeamType {
+    /// FinalAlert's defaults for a new team.
+    pub fn new(id: &str, house: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            name: "New team".to_string(),
+            house: house.to_string(),
+            script: String::new(),
+            task_force: String::new(),
+            waypoint: "A".to_string(),
+            tag: None,
+            priority: 5,
+            max: 5,
+            tech_level: 0,
+            veteran_level: 1,
+            group: -1,
+            autocreate: false,
+            prebuild: false,
+            reinforce: false,
+            recruiter: false,
+            full: false,
+            loadable: false,
+            aggressive: false,
+            suicide: false,
+            whiner: false,
+            annoyance: false,
+            guard_slower: false,
+            loose_recruit: false,
+            droppod: false,
+            use_transport_origin: false,
+            on_trans_only: false,
+            is_base_defense: false,
+            only_target_house_enemy: false,
+            are_team_members_recruitable: false,
+            transports_return_on_unload: false,
+        }
+    }
+
+    /// Yes/no keys with their INI names (drives I/O and the panel).
+    pub fn flags_mut(&mut self) -> [(&'static str, &mut bool); 19] {
+        [
+            ("Autocreate", &mut self.autocreate),
+            ("Prebuild", &mut self.prebuild),
+            ("Reinforce", &mut self.reinforce),
+            ("Recruiter", &mut self.recruiter),
+            ("Full", &mut self.full),
+            ("Loadable", &mut self.loadable),
+            ("Aggressive", &mut self.aggressive),
+            ("Suicide", &mut self.suicide),
+            ("Whiner", &mut self.whiner),
+            ("Annoyance", &mut self.annoyance),
+            ("GuardSlower", &mut self.guard_slower),
+            ("LooseRecruit", &mut self.loose_recruit),
+            ("Droppod", &mut self.droppod),
+            ("UseTransportOrigin", &mut self.use_transport_origin),
+            ("OnTransOnly", &mut self.on_trans_only),
+            ("IsBaseDefense", &mut self.is_base_defense),
+            ("OnlyTargetHouseEnemy", &mut self.only_target_house_enemy),
+            ("AreTeamMembersRecruitable", &mut self.are_team_members_recruitable),
+            ("TransportsReturnOnUnload", &mut self.transports_return_on_unload),
+        ]
+    }
+}
+
+/// All AI scripting objects of a map.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AiScripts {
+    pub task_forces: Vec<TaskForce>,
+    pub scripts: Vec<ScriptType>,
+    pub teams: Vec<TeamType>,
+}
+
+/// IDs listed in a `[TaskForces]`-style index section, in index order.
+fn listed_ids(doc: &MapDocument, list: &str) -> Vec<String> {
+    let Some(section) = doc.section(list) else {
+        return Vec::new();
+    };
+    let mut ids: Vec<(i32, String)> = section
+        .entries()
+        .filter_map(|e| Some((e.key().parse().ok()?, e.value().to_string())))
+        .collect();
+    ids.sort_by_key(|(i, _)| *i);
+    ids.into_iter().map(|(_, id)| id).collect()
+}
+
+/// `N=` entries of an object section, in numeric order.
+fn numbered(doc: &MapDocument, id: &str) -> Vec<String> {
+    let Some(section) = doc.section(id) else {
+        return Vec::new();
+    };
+    let mut lines: Vec<(u32, String)> = section
+        .entries()
+        .filter_map(|e| Some((e.key().parse().ok()?, e.value().to_string())))
+        .collect();
+    lines.sort_by_key(|(i, _)| *i);
+    lines.into_iter().map(|(_, v)| v).collect()
+}
+
+/// `a,b` → `(a, b)` with `a` numeric.
+fn pair(v: &str) -> Option<(u32, String)> {
+    let (a, b) = v.split_once(',')?;
+    Some((a.trim().parse().ok()?, b.trim().to_string()))
+}
+
+/// Rewrite an index section (`0=ID`, `1=ID`, ...) and drop sections of IDs no
+/// longer listed.
+fn write_list(doc: &mut MapDocument, list: &str, ids: &[&str]) {
+    for old in listed_ids(doc, list) {
+        if !ids.iter().any(|id| id.eq_ignore_ascii_case(&old)) {
+            doc.remove_section(&old);
+        }
+    }
+    if ids.is_empty() && !doc.has_section(list) {
+        return;
+    }
+    let section = doc.ensure_section(list);
+    section.clear_entries();
+    for (i, id) in ids.iter().enumerate() {
+        section.push(&i.to_string(), id);
+    }
+}
+
+impl AiScripts {
+    pub fn from_document(doc: &MapDocument) -> Self {
+        let mut ai = Self::default();
+
+        for id in listed_ids(doc, "TaskForces") {
+            let mut tf = TaskForce::new(&id);
+            if let Some(s) = doc.section(&id) {
+                read_text(s, "Name", &mut tf.name);
+                read_num(s, "Group", &mut tf.group);
+            }
+            tf.members = numbered(doc, &id).iter().filter_map(|v| pair(v)).collect();
+            ai.task_forces.push(tf);
+        }
+
+        for id in listed_ids(doc, "ScriptTypes") {
+            let mut st = ScriptType::new(&id);
+            if let Some(s) = doc.section(&id) {
+                read_text(s, "Name", &mut st.name);
+            }
+            st.actions = numbered(doc, &id).iter().filter_map(|v| pair(v)).collect();
+            ai.scripts.push(st);
+        }
+
+        for id in listed_ids(doc, "TeamTypes") {
+            let mut t = TeamType::new(&id, "");
+            if let Some(s) = doc.section(&id) {
+                read_text(s, "Name", &mut t.name);
+                read_text(s, "House", &mut t.house);
+                read_text(s, "Script", &mut t.script);
+                read_text(s, "TaskForce", &mut t.task_force);
+                read_text(s, "Waypoint", &mut t.waypoint);
+                t.tag = s.get("Tag").map(String::from);
+                read_num(s, "Priority", &mut t.priority);
+                read_num(s, "Max", &mut t.max);
+                read_num(s, "TechLevel", &mut t.tech_level);
+                read_num(s, "VeteranLevel", &mut t.veteran_level);
+                read_num(s, "Group", &mut t.group);
+                for (key, value) in t.flags_mut() {
+                    read_flag(s, key, value);
+                }
+            }
+            ai.teams.push(t);
+        }
+        ai
+    }
+
+    pub fn apply(&self, doc: &mut MapDocument) {
+        let ids: Vec<&str> = self.task_forces.iter().map(|t| t.id.as_str()).collect();
+        write_list(doc, "TaskForces", &ids);
+        for tf in &self.task_forces {
+            let s = doc.ensure_section(&tf.id);
+            s.clear_entries();
+            s.push("Name", &tf.name);
+            for (i, (count, kind)) in tf.members.iter().enumerate() {
+                s.push(&i.to_string(), &format!("{count},{kind}"));
+            }
+            s.push("Group", &tf.group.to_string());
+        }
+
+        let ids: Vec<&str> = self.scripts.iter().map(|t| t.id.as_str()).collect();
+        write_list(doc, "ScriptTypes", &ids);
+        for st in &self.scripts {
+            let s = doc.ensure_section(&st.id);
+            s.clear_entries();
+            s.push("Name", &st.name);
+            for (i, (action, param)) in st.actions.iter().enumerate() {
+                s.push(&i.to_string(), &format!("{action},{param}"));
+            }
+        }
+
+        let ids: Vec<&str> = self.teams.iter().map(|t| t.id.as_str()).collect();
+        write_list(doc, "TeamTypes", &ids);
+        for t in &self.teams {
+            let s = doc.ensure_section(&t.id);
+            write_text(s, "Name", &t.name);
+            write_text(s, "House", &t.house);
+            write_text(s, "Script", &t.script);
+            write_text(s, "TaskForce", &t.task_force);
+            write_text(s, "Waypoint", &t.waypoint);
+            match &t.tag {
+                Some(tag) => write_text(s, "Tag", tag),
+                None => {
+                    s.remove("Tag");
+                }
+            }
+            write_num(s, "Priority", t.priority);
+            write_num(s, "Max", t.max);
+            write_num(s, "TechLevel", t.tech_level);
+            write_num(s, "VeteranLevel", t.veteran_level);
+            write_num(s, "Group", t.group);
+            for (key, value) in t.clone().flags_mut() {
+                write_flag(s, key, *value);
+            }
+        }
+    }
+
+    pub fn task_force(&self, id: &str) -> Option<&TaskForce> {
+        self.task_forces.iter().find(|t| t.id.eq_ignore_ascii_case(id))
+    }
+
+    pub fn script(&self, id: &str) -> Option<&ScriptType> {
+        self.scripts.iter().find(|t| t.id.eq_ignore_ascii_case(id))
+    }
+
+    pub fn team(&self, id: &str) -> Option<&TeamType> {
+        self.teams.iter().find(|t| t.id.eq_ignore_ascii_case(id))
+    }
+
+    pub fn contains_id(&self, id: &str) -> bool {
+        self.task_force(id).is_some() || self.script(id).is_some() || self.team(id).is_some()
+    }
+
+    pub fn team_ids(&self) -> Vec<String> {
+        self.teams.iter().map(|t| t.id.clone()).collect()
+    }
+
+    /// Dangling references, one message each. `known_type` says whether a
+    /// TechnoType ID (taskforce member) exists in the game or the map.
+    pub fn validate(
+        &self,
+        houses: &[House],
+        waypoints: &[Waypoint],
+        triggers: &TriggerSet,
+        known_type: impl Fn(&str) -> bool,
+    ) -> Vec<String> {
+        let mut errors = Vec::new();
+        for t in &self.teams {
+            let who = format!("Team {} ({})", t.id, t.name);
+            if !houses.iter().any(|h| h.name.eq_ignore_ascii_case(&t.house)) {
+                errors.push(format!("{who}: unknown house {}", t.house));
+            }
+            if self.script(&t.script).is_none() {
+                errors.push(format!("{who}: script {} does not exist", t.script));
+            }
+            if self.task_force(&t.task_force).is_none() {
+                errors.push(format!("{who}: task force {} does not exist", t.task_force));
+            }
+            match letters_to_waypoint(&t.waypoint) {
+                Some(n) if !waypoints.iter().any(|w| w.number == n) => {
+                    errors.push(format!("{who}: waypoint {n} does not exist"));
+                }
+                None => errors.push(format!("{who}: bad waypoint {}", t.waypoint)),
+                _ => {}
+            }
+            if let Some(tag) = &t.tag
+                && triggers.tag(tag).is_none()
+            {
+                errors.push(format!("{who}: tag {tag} does not exist"));
+            }
+        }
+        for tf in &self.task_forces {
+            for (_, kind) in &tf.members {
+                if !known_type(kind) {
+                    errors.push(format!("Task force {} ({}): unknown unit type {kind}", tf.id, tf.name));
+                }
+            }
+            if tf.members.is_empty() {
+                errors.push(format!("Task force {} ({}) is empty", tf.id, tf.name));
+            }
+        }
+        errors
+    }
+}
+
+/// Object types a map adds on top of the game's (`[InfantryTypes]`, `[VehicleTypes]`, ...).
+fn map_defined_types(doc: &MapDocument) -> Vec<String> {
+    ["InfantryTypes", "VehicleTypes", "AircraftTypes", "BuildingTypes"]
+        .iter()
+        .filter_map(|list| doc.section(list))
+        .flat_map(|s| s.entries().map(|e| e.value().to_string()))
+        .collect()
+}
+
+/// Stock RA2/YR TechnoTypes a taskforce may list. Only used without a game
+/// directory; with one, the rules catalog is the sole authority.
+const STOCK_TYPES: &[&str] = &[
+    // infantry
+    "E1", "E2", "SHK", "ENGINEER", "JUMPJET", "GHOST", "TANY", "SPY", "DOG", "ADOG", "CLEG", "YURI", "YURIPR",
+    "BRUTE", "VIRUS", "INIT", "DESO", "IVAN", "TERROR", "SNIPE", "CCOMAND", "PTROOP", "LUNR", "SLAV", "YENGINEER",
+    "SENGINEER",
+    // vehicles and ships
+    "AMCV", "SMCV", "PCV", "CMIN", "HARV", "SMIN", "MTNK", "HTNK", "APOC", "FV", "SREF", "TNKD", "ROBO", "MGTK",
+    "BFRT", "V3", "DRON", "TTNK", "DTRUK", "HTK", "LTNK", "YTNK", "TELE", "CAOS", "MIND", "SAPC", "LCRF", "DEST",
+    "AEGIS", "CARRIER", "DLPH", "SUB", "HYD", "SQD", "DRED", "BSUB", "YHVR", "SCHD",
+    // aircraft
+    "ORCA", "BEAG", "SHAD", "ZEP", "DISK", "SPYP", "PDPLANE",
+];
+
+/// Whether `id` is a stock TechnoType or one the map itself defines.
+pub fn is_known_type(doc: &MapDocument, id: &str) -> bool {
+    STOCK_TYPES.iter().any(|t| t.eq_ignore_ascii_case(id))
+        || map_defined_types(doc).iter().any(|t| t.eq_ignore_ascii_case(id))
+}
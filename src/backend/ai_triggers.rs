@@ -0,0 +1,241 @@
+use anyhow::Result;
+use bevy::prelude::*;
+use std::path::{Path, PathBuf};
+
+use super::loader::find_file;
+use super::map_document::{parse_bool, MapDocument};
+use super::mix::Vfs;
+
+/// One global AI trigger from `ai.ini`'s `[AITriggerTypes]`
+/// (`ID=Name,Team1,House,TechLevel,...`). Only what the enable panel shows is kept.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AiTriggerInfo {
+    pub id: String,
+    pub name: String,
+    pub house: String,
+    /// `IsForSkirmish` (field 10): the game runs it on multiplayer maps.
+    pub skirmish: bool,
+    /// Enabled in Easy, Normal and Hard (fields 15-17).
+    pub difficulties: [bool; 3],
+}
+
+impl AiTriggerInfo {
+    /// Whether the game runs this trigger when the map says nothing about it:
+    /// skirmish triggers with at least one difficulty on multiplayer maps, none
+    /// on campaign maps.
+    pub fn default_enabled(&self, multiplayer: bool) -> bool {
+        multiplayer && self.skirmish && self.difficulties.contains(&true)
+    }
+}
+
+/// The game's AI triggers, read from `ai.ini` and then `aimd.ini` (Yuri's
+/// Revenge entries replace RA2's ones with the same ID).
+#[derive(Resource, Debug, Clone, Default)]
+pub struct AiTriggerCatalog {
+    pub entries: Vec<AiTriggerInfo>,
+    /// Files the entries were read from, in load order.
+    pub sources: Vec<PathBuf>,
+}
+
+impl AiTriggerCatalog {
+    /// Read `path`. Picking `aimd.ini` also reads the `ai.ini` next to it first.
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut catalog = Self::default();
+        let is_md = path.file_name().is_some_and(|n| n.eq_ignore_ascii_case("aimd.ini"));
+        if is_md && let Some(base) = path.parent().and_then(|dir| find_file(dir, "ai.ini")) {
+            catalog.merge(&base)?;
+        }
+        catalog.merge(path)?;
+        Ok(catalog)
+    }
+
+    /// `ai.ini`/`aimd.ini` in the top level of `dir`, if there are any.
+    pub fn from_folder(dir: &Path) -> Option<Result<Self>> {
+        let path = find_file(dir, "aimd.ini").or_else(|| find_file(dir, "ai.ini"))?;
+        Some(Self::load(&path))
+    }
+
+    /// `ai.ini` then `aimd.ini` from the game files (loose or in archives),
+    /// if there are any.
+    pub fn from_vfs(vfs: &Vfs) -> Option<Result<Self>> {
+        let files: Vec<_> = ["ai.ini", "aimd.ini"].iter().filter_map(|name| vfs.read(name)).collect();
+        if files.is_empty() {
+            return None;
+        }
+        let mut catalog = Self::default();
+        for file in files {
+            match file {
+                Ok((source, bytes)) => catalog.merge_document(&MapDocument::from_bytes(&bytes), source),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        Some(Ok(catalog))
+    }
+
+    fn merge(&mut self, path: &Path) -> Result<()> {
+        let doc = MapDocument::load(path)?;
+        self.merge_document(&doc, path.to_path_buf());
+        Ok(())
+    }
+
+    fn merge_document(&mut self, doc: &MapDocument, source: PathBuf) {
+        if let Some(section) = doc.section("AITriggerTypes") {
+            for e in section.entries() {
+                let fields: Vec<&str> = e.value().split(',').map(str::trim).collect();
+                let field = |i: usize| fields.get(i).copied().unwrap_or_default();
+                // Entries written before the difficulty fields existed run on all of them.
+                let difficulty = |i: usize| fields.get(i).is_none_or(|v| parse_bool(v));
+                let info = AiTriggerInfo {
+                    id: e.key().to_string(),
+                    name: field(0).to_string(),
+                    house: field(2).to_string(),
+                    skirmish: parse_bool(field(10)),
+                    difficulties: [difficulty(15), difficulty(16), difficulty(17)],
+                };
+                match self.entries.iter_mut().find(|i| i.id.eq_ignore_ascii_case(&info.id)) {
+                    Some(old) => *old = info,
+                    None => self.entries.push(info),
+                }
+            }
+        }
+        self.sources.push(source);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, id: &str) -> Option<&AiTriggerInfo> {
+        self.entries.iter().find(|i| i.id.eq_ignore_ascii_case(id))
+    }
+
+    /// The game's own setting for `id`; unknown IDs are off.
+    pub fn default_for(&self, id: &str, multiplayer: bool) -> bool {
+        self.get(id).is_some_and(|i| i.default_enabled(multiplayer))
+    }
+
+    /// A setting the game would not apply on its own. IDs missing from the
+    /// catalog (map-local triggers, or no catalog loaded) always count.
+    pub fn is_override(&self, id: &str, on: bool, multiplayer: bool) -> bool {
+        self.get(id).is_none_or(|i| on != i.default_enabled(multiplayer))
+    }
+}
+
+/// Whether the game treats the map as multiplayer, which decides the AI
+/// trigger defaults.
+pub fn is_multiplayer(doc: &MapDocument) -> bool {
+    doc.get("Basic", "MultiplayerOnly").is_some_and(parse_bool)
+}
+
+/// Typed `[AITriggerTypesEnable]` (`ID=yes`/`ID=no`), in file order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AiTriggerEnables {
+    pub entries: Vec<(String, bool)>,
+    /// Drop entries that only restate the game default on save (needs a
+    /// catalog). Off unless asked for, so saving keeps the map's list.
+    pub save_overrides_only: bool,
+}
+
+impl AiTriggerEnables {
+    pub fn from_document(doc: &MapDocument) -> Self {
+        let mut enables = Self::default();
+        if let Some(section) = doc.section("AITriggerTypesEnable") {
+            for e in section.entries() {
+                enables.set(e.key(), parse_bool(e.value()));
+            }
+        }
+        enables
+    }
+
+    /// The map's own setting for `id`, if it has one.
+    pub fn explicit(&self, id: &str) -> Option<bool> {
+        self.entries.iter().find(|(k, _)| k.eq_ignore_ascii_case(id)).map(|(_, on)| *on)
+    }
+
+    pub fn is_enabled(&self, id: &str, default: bool) -> bool {
+        self.explicit(id).unwrap_or(default)
+    }
+
+    pub fn set(&mut self, id: &str, on: bool) {
+        match self.entries.iter_mut().find(|(k, _)| k.eq_ignore_ascii_case(id)) {
+            Some((_, v)) => *v = on,
+            None => self.entries.push((id.to_string(), on)),
+        }
+    }
+
+    /// Forget the map's setting so the game default applies.
+    pub fn reset(&mut self, id: &str) {
+        self.entries.retain(|(k, _)| !k.eq_ignore_ascii_case(id));
+    }
+
+    /// Entries that would be written for this catalog on a multiplayer or campaign map.
+    pub fn saved_entries<'a>(&'a self, catalog: &AiTriggerCatalog, multiplayer: bool) -> Vec<&'a (String, bool)> {
+        self.entries
+            .iter()
+            .filter(|(id, on)| !self.save_overrides_only || catalog.is_override(id, *on, multiplayer))
+            .collect()
+    }
+
+    /// Write the section back, keeping the map's own spelling (`1`, `true`, ...)
+    /// of values that did not change.
+    pub fn apply(&self, doc: &mut MapDocument, catalog: &AiTriggerCatalog) {
+        let entries: Vec<(String, String)> = self
+            .saved_entries(catalog, is_multiplayer(doc))
+            .into_iter()
+            .map(|(id, on)| {
+                let value = match doc.get("AITriggerTypesEnable", id) {
+                    Some(v) if parse_bool(v) == *on => v.to_string(),
+                    _ => if *on { "yes" } else { "no" }.to_string(),
+                };
+                (id.clone(), value)
+            })
+            .collect();
+        doc.replace_entries_if_changed("AITriggerTypesEnable", &entries);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn catalog() -> AiTriggerCatalog {
+        let ini = "[AITriggerTypes]\n\
+                   SKIRM=Skirmish,T1,<all>,1,0,<none>,0000,50,30,50,1,0,1,0,<none>,1,1,1\n\
+                   CAMPAIGN=Campaign,T1,Americans,1,0,<none>,0000,50,30,50,0,0,1,0,<none>,1,1,1\n\
+                   HARDONLY=Hard only,T1,<all>,1,0,<none>,0000,50,30,50,1,0,1,0,<none>,0,0,1\n";
+        let mut catalog = AiTriggerCatalog::default();
+        catalog.merge_document(&MapDocument::parse(ini), PathBuf::from("ai.ini"));
+        catalog
+    }
+
+    #[test]
+    fn defaults_follow_the_skirmish_flag() {
+        let catalog = catalog();
+        assert_eq!(catalog.get("campaign").map(|i| i.house.as_str()), Some("Americans"));
+        assert!(catalog.default_for("SKIRM", true));
+        assert!(catalog.default_for("HARDONLY", true));
+        assert!(!catalog.default_for("CAMPAIGN", true));
+        assert!(!catalog.default_for("SKIRM", false));
+        assert!(!catalog.is_override("SKIRM", true, true));
+        assert!(catalog.is_override("CAMPAIGN", true, true));
+        assert!(catalog.is_override("LOCAL", true, true));
+    }
+
+    #[test]
+    fn saving_an_untouched_map_keeps_its_bytes() {
+        let bytes = std::fs::read("src/samplemap/sample.map").unwrap();
+        let mut doc = MapDocument::from_bytes(&bytes);
+        AiTriggerEnables::from_document(&doc).apply(&mut doc, &catalog());
+        assert_eq!(doc.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn pruning_drops_entries_that_match_the_default() {
+        let mut doc = MapDocument::parse("[Basic]\nMultiplayerOnly=1\n[AITriggerTypesEnable]\nSKIRM=yes\nCAMPAIGN=yes\n");
+        let mut enables = AiTriggerEnables::from_document(&doc);
+        enables.save_overrides_only = true;
+        enables.apply(&mut doc, &catalog());
+        assert_eq!(doc.get("AITriggerTypesEnable", "SKIRM"), None);
+        assert_eq!(doc.get("AITriggerTypesEnable", "CAMPAIGN"), Some("yes"));
+    }
+}
@@ -0,0 +1,183 @@
+use super::map_document::{read_flag, read_num, read_text, write_flag, write_num, write_text, MapDocument};
+
+/// Typed `[Basic]` map properties, edited through the Map Properties dialog.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BasicProperties {
+    pub name: String,
+    /// Comma-separated game modes (`standard`, `meatgrind`, `teamgame`, ...).
+    pub game_mode: String,
+    pub min_player: i32,
+    pub max_player: i32,
+    pub new_ini_format: i32,
+    pub multiplayer_only: bool,
+    pub official: bool,
+    pub percent: i32,
+    pub init_time: i32,
+    pub home_cell: i32,
+    pub alt_home_cell: i32,
+    pub next_scenario: String,
+    pub alt_next_scenario: String,
+    pub carry_over_cap: i32,
+    pub carry_over_money: f32,
+    pub end_of_game: bool,
+    pub one_time_only: bool,
+    pub skip_score: bool,
+    pub skip_map_select: bool,
+    pub free_radar: bool,
+    pub train_crate: bool,
+    pub truck_crate: bool,
+    pub tiberium_growth: bool,
+    pub vein_growth: bool,
+    pub ice_growth: bool,
+    pub tiberium_death_to_visceroid: bool,
+    pub ignore_global_ai_triggers: bool,
+}
+
+impl Default for BasicProperties {
+    /// FinalAlert's values for a new multiplayer map.
+    fn default() -> Self {
+        Self {
+            name: "No name".to_string(),
+            game_mode: "standard".to_string(),
+            min_player: 2,
+            max_player: 8,
+            new_ini_format: 4,
+            multiplayer_only: true,
+            official: false,
+            percent: 0,
+            init_time: 10000,
+            home_cell: 98,
+            alt_home_cell: 99,
+            next_scenario: String::new(),
+            alt_next_scenario: String::new(),
+            carry_over_cap: 0,
+            carry_over_money: 0.0,
+            end_of_game: false,
+            one_time_only: false,
+            skip_score: false,
+            skip_map_select: false,
+            free_radar: false,
+            train_crate: false,
+            truck_crate: false,
+            tiberium_growth: true,
+            vein_growth: true,
+            ice_growth: true,
+            tiberium_death_to_visceroid: false,
+            ignore_global_ai_triggers: false,
+        }
+    }
+}
+
+impl BasicProperties {
+    /// Read `[Basic]`; keys the map does not set keep FinalAlert's defaults.
+    pub fn from_document(doc: &MapDocument) -> Self {
+        let mut p = Self::default();
+        let Some(s) = doc.section("Basic") else {
+            return p;
+        };
+        read_text(s, "Name", &mut p.name);
+        read_text(s, "GameMode", &mut p.game_mode);
+        read_num(s, "MinPlayer", &mut p.min_player);
+        read_num(s, "MaxPlayer", &mut p.max_player);
+        read_num(s, "NewINIFormat", &mut p.new_ini_format);
+        read_flag(s, "MultiplayerOnly", &mut p.multiplayer_only);
+        read_flag(s, "Official", &mut p.official);
+        read_num(s, "Percent", &mut p.percent);
+        read_num(s, "InitTime", &mut p.init_time);
+        read_num(s, "HomeCell", &mut p.home_cell);
+        read_num(s, "AltHomeCell", &mut p.alt_home_cell);
+        read_text(s, "NextScenario", &mut p.next_scenario);
+        read_text(s, "AltNextScenario", &mut p.alt_next_scenario);
+        read_num(s, "CarryOverCap", &mut p.carry_over_cap);
+        read_num(s, "CarryOverMoney", &mut p.carry_over_money);
+        read_flag(s, "EndOfGame", &mut p.end_of_game);
+        read_flag(s, "OneTimeOnly", &mut p.one_time_only);
+        read_flag(s, "SkipScore", &mut p.skip_score);
+        read_flag(s, "SkipMapSelect", &mut p.skip_map_select);
+        read_flag(s, "FreeRadar", &mut p.free_radar);
+        read_flag(s, "TrainCrate", &mut p.train_crate);
+        read_flag(s, "TruckCrate", &mut p.truck_crate);
+        read_flag(s, "TiberiumGrowthEnabled", &mut p.tiberium_growth);
+        read_flag(s, "VeinGrowthEnabled", &mut p.vein_growth);
+        read_flag(s, "IceGrowthEnabled", &mut p.ice_growth);
+        read_flag(s, "TiberiumDeathToVisceroid", &mut p.tiberium_death_to_visceroid);
+        read_flag(s, "IgnoreGlobalAITriggers", &mut p.ignore_global_ai_triggers);
+        p
+    }
+
+    /// Write the fields back into `[Basic]`. Values that did not change keep their
+    /// original spelling (`MultiplayerOnly=1`, `CarryOverMoney=0.000000`, ...).
+    pub fn apply(&self, doc: &mut MapDocument) {
+        let s = doc.ensure_section("Basic");
+        write_text(s, "Name", &self.name);
+        write_text(s, "GameMode", &self.game_mode);
+        write_num(s, "MinPlayer", self.min_player);
+        write_num(s, "MaxPlayer", self.max_player);
+        write_num(s, "NewINIFormat", self.new_ini_format);
+        write_flag(s, "MultiplayerOnly", self.multiplayer_only);
+        write_flag(s, "Official", self.official);
+        write_num(s, "Percent", self.percent);
+        write_num(s, "InitTime", self.init_time);
+        write_num(s, "HomeCell", self.home_cell);
+        write_num(s, "AltHomeCell", self.alt_home_cell);
+        write_text(s, "NextScenario", &self.next_scenario);
+        write_text(s, "AltNextScenario", &self.alt_next_scenario);
+        write_num(s, "CarryOverCap", self.carry_over_cap);
+        write_num(s, "CarryOverMoney", self.carry_over_money);
+        write_flag(s, "EndOfGame", self.end_of_game);
+        write_flag(s, "OneTimeOnly", self.one_time_only);
+        write_flag(s, "SkipScore", self.skip_score);
+        write_flag(s, "SkipMapSelect", self.skip_map_select);
+        write_flag(s, "FreeRadar", self.free_radar);
+        write_flag(s, "TrainCrate", self.train_crate);
+        write_flag(s, "TruckCrate", self.truck_crate);
+        write_flag(s, "TiberiumGrowthEnabled", self.tiberium_growth);
+        write_flag(s, "VeinGrowthEnabled", self.vein_growth);
+        write_flag(s, "IceGrowthEnabled", self.ice_growth);
+        write_flag(s, "TiberiumDeathToVisceroid", self.tiberium_death_to_visceroid);
+        write_flag(s, "IgnoreGlobalAITriggers", self.ignore_global_ai_triggers);
+    }
+
+    /// Human-readable problems; empty when the properties can be applied.
+    /// Start locations only matter on multiplayer maps.
+    pub fn validate(&self, starting_points: usize) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.name.trim().is_empty() {
+            errors.push("Name must not be empty".to_string());
+        }
+        if self.game_mode.split(',').all(|m| m.trim().is_empty()) {
+            errors.push("At least one game mode is required".to_string());
+        }
+        if !(1..=8).contains(&self.min_player) || !(1..=8).contains(&self.max_player) {
+            errors.push("Player counts must be between 1 and 8".to_string());
+        }
+        if self.min_player > self.max_player {
+            errors.push(format!("MinPlayer ({}) is larger than MaxPlayer ({})", self.min_player, self.max_player));
+        }
+        if self.multiplayer_only && self.max_player as usize > starting_points {
+            errors.push(format!(
+                "MaxPlayer ({}) is larger than the number of start locations ({starting_points})",
+                self.max_player
+            ));
+        }
+        if !(0..=100).contains(&self.percent) {
+            errors.push("Percent must be between 0 and 100".to_string());
+        }
+        errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn start_locations_only_limit_multiplayer_maps() {
+        let multiplayer = BasicProperties::default();
+        assert_eq!(multiplayer.validate(8), Vec::<String>::new());
+        assert_eq!(multiplayer.validate(4).len(), 1);
+
+        let campaign = BasicProperties { multiplayer_only: false, ..BasicProperties::default() };
+        assert_eq!(campaign.validate(0), Vec::<String>::new());
+    }
+}
@@ -0,0 +1,503 @@
+//! Decoders for the packed binary sections of RA2/YR maps.
+//!
+//! Packs such as `[IsoMapPack5]` and `[PreviewPack]` are stored as numbered
+//! base64 lines (`1=...`, `2=...`). The decoded bytes are a series of chunks:
+//!
+//! ```text
+//! u16 compressed_len | u16 uncompressed_len | compressed_len bytes
+//! ```
+//!
+//! "Format 5" chunks are LZO1X compressed; "format 80" chunks (`[OverlayPack]`,
+//! `[OverlayDataPack]`) use Westwood's LCW scheme.
+//!
+//! The encoders mirror FinalAlert's output: 8 KiB chunks and 70-character lines.
+
+use anyhow::{anyhow, bail, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+use super::map_document::Section;
+
+/// Concatenate the numbered lines `1=`, `2=`, ... of a pack section and base64-decode
+/// them. Reading stops at the first missing number, like the game does.
+pub fn decode_base64_section(section: &Section) -> Result<Vec<u8>> {
+    let mut text = String::new();
+    for n in 1.. {
+        match section.get(&n.to_string()) {
+            Some(line) => text.push_str(line),
+            None => break,
+        }
+    }
+    STANDARD
+        .decode(text.as_bytes())
+        .map_err(|e| anyhow!("[{}] is not valid base64: {e}", section.name()))
+}
+
+/// FinalAlert's base64 line width for pack sections.
+pub const PACK_LINE_LEN: usize = 70;
+/// Uncompressed bytes per chunk when encoding (what FinalAlert and the game use).
+const CHUNK_LEN: usize = 8192;
+
+/// Replace a pack section's entries with `data` as numbered 70-character base64 lines.
+pub fn encode_base64_section(section: &mut Section, data: &[u8]) {
+    let text = STANDARD.encode(data);
+    section.clear_entries();
+    for (i, line) in text.as_bytes().chunks(PACK_LINE_LEN).enumerate() {
+        // base64 output is ASCII, so every chunk is valid UTF-8.
+        section.push(&(i + 1).to_string(), std::str::from_utf8(line).unwrap_or_default());
+    }
+}
+
+/// Split `data` into chunks and compress each with `compress`, adding the
+/// `u16 packed | u16 unpacked` chunk headers.
+fn encode_chunks(data: &[u8], compress: fn(&[u8]) -> Vec<u8>) -> Vec<u8> {
+    let mut out = Vec::new();
+    for chunk in data.chunks(CHUNK_LEN) {
+        let packed = compress(chunk);
+        out.extend_from_slice(&(packed.len() as u16).to_le_bytes());
+        out.extend_from_slice(&(chunk.len() as u16).to_le_bytes());
+        out.extend_from_slice(&packed);
+    }
+    out
+}
+
+/// Encode a buffer as format-5 (LZO1X) chunks.
+pub fn encode_format5(data: &[u8]) -> Vec<u8> {
+    encode_chunks(data, lzo1x_compress)
+}
+
+/// Encode a buffer as format-80 (LCW) chunks.
+pub fn encode_format80(data: &[u8]) -> Vec<u8> {
+    encode_chunks(data, lcw_compress)
+}
+
+/// Split a packed buffer into its `(uncompressed_len, payload)` chunks.
+fn chunks(data: &[u8]) -> Result<Vec<(usize, &[u8])>> {
+    let mut out = Vec::new();
+    let mut pos = 0;
+    while pos < data.len() {
+        if pos + 4 > data.len() {
+            bail!("truncated chunk header at byte {pos}");
+        }
+        let packed = u16::from_le_bytes([data[pos], data[pos + 1]]) as usize;
+        let unpacked = u16::from_le_bytes([data[pos + 2], data[pos + 3]]) as usize;
+        pos += 4;
+        let payload = data
+            .get(pos..pos + packed)
+            .ok_or_else(|| anyhow!("chunk at byte {} overruns the pack", pos - 4))?;
+        out.push((unpacked, payload));
+        pos += packed;
+    }
+    Ok(out)
+}
+
+/// Decode a chunked format-5 (LZO1X) buffer.
+pub fn decode_format5(data: &[u8]) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    for (unpacked, payload) in chunks(data)? {
+        out.extend(lzo1x_decompress(payload, unpacked)?);
+    }
+    Ok(out)
+}
+
+/// Decode a chunked format-80 (LCW) buffer.
+pub fn decode_format80(data: &[u8]) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    for (unpacked, payload) in chunks(data)? {
+        out.extend(lcw_decompress(payload, unpacked)?);
+    }
+    Ok(out)
+}
+
+// ---------- LCW (format 80) ----------
+
+/// Westwood LCW decompression with absolute copy offsets (as used by TS/RA2).
+pub fn lcw_decompress(src: &[u8], out_len: usize) -> Result<Vec<u8>> {
+    let mut out: Vec<u8> = Vec::with_capacity(out_len);
+    let mut r = Input { src, ip: 0 };
+
+    while out.len() < out_len {
+        let cmd = r.byte()?;
+        match cmd {
+            0x80 => break, // end of stream
+            0x00..=0x7F => {
+                // 0cccpppp pppppppp: short copy from `p` bytes back.
+                let len = ((cmd & 0x70) >> 4) + 3;
+                let dist = ((cmd & 0x0F) << 8) | r.byte()?;
+                copy_match(&mut out, dist, len)?;
+            }
+            0x81..=0xBF => r.literals(&mut out, cmd & 0x3F)?,
+            0xFE => {
+                let len = r.le16()?;
+                let value = r.byte()? as u8;
+                out.resize(out.len() + len, value);
+            }
+            0xFF => {
+                let len = r.le16()?;
+                let from = r.le16()?;
+                copy_absolute(&mut out, from, len)?;
+            }
+            _ => {
+                // 11cccccc pppppppp pppppppp: medium absolute copy.
+                let len = (cmd & 0x3F) + 3;
+                let from = r.le16()?;
+                copy_absolute(&mut out, from, len)?;
+            }
+        }
+    }
+
+    out.truncate(out_len);
+    if out.len() != out_len {
+        bail!("LCW chunk decoded to {} bytes, expected {out_len}", out.len());
+    }
+    Ok(out)
+}
+
+/// Greedy LCW compressor emitting fills (`0xFE`), absolute copies (`0xC0..=0xFD`,
+/// `0xFF`) and literal runs (`0x81..=0xBF`), terminated by `0x80`.
+pub fn lcw_compress(src: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut table = vec![usize::MAX; 1 << 12];
+    let mut lit_start = 0;
+    let mut ip = 0;
+
+    while ip < src.len() {
+        let run = src[ip..].iter().take(0xFFFF).take_while(|&&b| b == src[ip]).count();
+        let (from, copy) = find_match(src, ip, &mut table, 0xFFFF, 0xFFFF);
+
+        if run >= 3 && run >= copy {
+            flush_lcw_literals(&mut out, &src[lit_start..ip]);
+            out.push(0xFE);
+            out.extend_from_slice(&(run as u16).to_le_bytes());
+            out.push(src[ip]);
+            ip += run;
+            lit_start = ip;
+        } else if copy >= 3 {
+            flush_lcw_literals(&mut out, &src[lit_start..ip]);
+            if copy <= 64 {
+                out.push(0xC0 | (copy - 3) as u8);
+            } else {
+                out.push(0xFF);
+                out.extend_from_slice(&(copy as u16).to_le_bytes());
+            }
+            out.extend_from_slice(&(from as u16).to_le_bytes());
+            ip += copy;
+            lit_start = ip;
+        } else {
+            ip += 1;
+        }
+    }
+    flush_lcw_literals(&mut out, &src[lit_start..]);
+    out.push(0x80);
+    out
+}
+
+fn flush_lcw_literals(out: &mut Vec<u8>, lits: &[u8]) {
+    for part in lits.chunks(0x3F) {
+        out.push(0x80 | part.len() as u8);
+        out.extend_from_slice(part);
+    }
+}
+
+/// Hash-chain-free match finder shared by both compressors: remembers the last
+/// position of each 3-byte prefix and extends the candidate as far as it goes.
+/// Returns `(match_start, match_len)`; a length below 3 means "no match".
+fn find_match(src: &[u8], ip: usize, table: &mut [usize], max_dist: usize, max_len: usize) -> (usize, usize) {
+    if ip + 3 > src.len() {
+        return (0, 0);
+    }
+    let key = (src[ip] as usize) << 16 | (src[ip + 1] as usize) << 8 | src[ip + 2] as usize;
+    let h = (key.wrapping_mul(0x9E37_79B1) >> 8) & (table.len() - 1);
+    let cand = std::mem::replace(&mut table[h], ip);
+    if cand == usize::MAX || ip - cand > max_dist || src[cand..cand + 3] != src[ip..ip + 3] {
+        return (0, 0);
+    }
+    let len = 3 + src[ip + 3..]
+        .iter()
+        .zip(&src[cand + 3..])
+        .take(max_len - 3)
+        .take_while(|(a, b)| a == b)
+        .count();
+    (cand, len)
+}
+
+/// Absolute copies index into what has been written so far.
+fn copy_absolute(out: &mut Vec<u8>, from: usize, len: usize) -> Result<()> {
+    if from >= out.len() {
+        bail!("LCW copy source {from} beyond output ({} bytes)", out.len());
+    }
+    for i in 0..len {
+        out.push(out[from + i]);
+    }
+    Ok(())
+}
+
+// ---------- LZO1X ----------
+
+struct Input<'a> {
+    src: &'a [u8],
+    ip: usize,
+}
+
+impl Input<'_> {
+    fn byte(&mut self) -> Result<usize> {
+        let b = *self.src.get(self.ip).ok_or_else(|| anyhow!("LZO input overrun"))?;
+        self.ip += 1;
+        Ok(b as usize)
+    }
+
+    fn le16(&mut self) -> Result<usize> {
+        Ok(self.byte()? | (self.byte()? << 8))
+    }
+
+    /// Extended length: each zero byte adds 255, the first non-zero byte ends the run.
+    fn run_len(&mut self, base: usize) -> Result<usize> {
+        let mut n = base;
+        while *self.src.get(self.ip).ok_or_else(|| anyhow!("LZO input overrun"))? == 0 {
+            n += 255;
+            self.ip += 1;
+        }
+        Ok(n + self.byte()?)
+    }
+
+    fn literals(&mut self, out: &mut Vec<u8>, n: usize) -> Result<()> {
+        let lit = self.src.get(self.ip..self.ip + n).ok_or_else(|| anyhow!("LZO literal overrun"))?;
+        out.extend_from_slice(lit);
+        self.ip += n;
+        Ok(())
+    }
+}
+
+fn copy_match(out: &mut Vec<u8>, dist: usize, len: usize) -> Result<()> {
+    if dist == 0 || dist > out.len() {
+        bail!("LZO match distance {dist} out of range");
+    }
+    let start = out.len() - dist;
+    // Byte by byte: matches may overlap the bytes they produce.
+    for i in 0..len {
+        out.push(out[start + i]);
+    }
+    Ok(())
+}
+
+enum Step {
+    Literal,
+    FirstLiteralRun,
+    Match(usize),
+    MatchNext(usize),
+}
+
+/// LZO1X decompression (the `lzo1x_decompress_safe` state machine from minilzo).
+pub fn lzo1x_decompress(src: &[u8], out_len: usize) -> Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(out_len);
+    let mut r = Input { src, ip: 0 };
+
+    let mut step = match src.first() {
+        Some(&b) if b > 17 => {
+            r.ip = 1;
+            let t = b as usize - 17;
+            if t < 4 {
+                Step::MatchNext(t)
+            } else {
+                r.literals(&mut out, t)?;
+                Step::FirstLiteralRun
+            }
+        }
+        Some(_) => Step::Literal,
+        None => bail!("empty LZO stream"),
+    };
+
+    loop {
+        step = match step {
+            Step::Literal => {
+                let mut t = r.byte()?;
+                if t >= 16 {
+                    Step::Match(t)
+                } else {
+                    if t == 0 {
+                        t = r.run_len(15)?;
+                    }
+                    r.literals(&mut out, t + 3)?;
+                    Step::FirstLiteralRun
+                }
+            }
+            Step::FirstLiteralRun => {
+                let t = r.byte()?;
+                if t >= 16 {
+                    Step::Match(t)
+                } else {
+                    // M1 right after a literal run: 3 bytes from 2..3 KiB back.
+                    let dist = 1 + 0x0800 + (t >> 2) + (r.byte()? << 2);
+                    copy_match(&mut out, dist, 3)?;
+                    after_match(&r)
+                }
+            }
+            Step::Match(t) => {
+                if t >= 64 {
+                    // M2
+                    let dist = 1 + ((t >> 2) & 7) + (r.byte()? << 3);
+                    copy_match(&mut out, dist, (t >> 5) + 1)?;
+                } else if t >= 32 {
+                    // M3
+                    let mut len = t & 31;
+                    if len == 0 {
+                        len = r.run_len(31)?;
+                    }
+                    let dist = 1 + (r.le16()? >> 2);
+                    copy_match(&mut out, dist, len + 2)?;
+                } else if t >= 16 {
+                    // M4 (or end of stream)
+                    let mut len = t & 7;
+                    if len == 0 {
+                        len = r.run_len(7)?;
+                    }
+                    let dist = ((t & 8) << 11) + (r.le16()? >> 2);
+                    if dist == 0 {
+                        break;
+                    }
+                    copy_match(&mut out, dist + 0x4000, len + 2)?;
+                } else {
+                    // M1
+                    let dist = 1 + (t >> 2) + (r.byte()? << 2);
+                    copy_match(&mut out, dist, 2)?;
+                }
+                after_match(&r)
+            }
+            Step::MatchNext(n) => {
+                r.literals(&mut out, n)?;
+                Step::Match(r.byte()?)
+            }
+        };
+    }
+
+    if out.len() != out_len {
+        bail!("LZO chunk decoded to {} bytes, expected {out_len}", out.len());
+    }
+    Ok(out)
+}
+
+/// Greedy LZO1X compressor. Emits only literal runs, M2 and M3 matches (chunks are
+/// at most 8 KiB, so M4's long distances are never needed) and the standard
+/// end-of-stream marker, which any LZO1X decompressor accepts.
+pub fn lzo1x_compress(src: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut table = vec![usize::MAX; 1 << 14];
+    let mut lit_start = 0;
+    let mut ip = 0;
+
+    while ip < src.len() {
+        let (from, len) = find_match(src, ip, &mut table, 0x4000, usize::MAX);
+        if len >= 3 {
+            emit_lzo_literals(&mut out, &src[lit_start..ip]);
+            emit_lzo_match(&mut out, ip - from, len);
+            ip += len;
+            lit_start = ip;
+        } else {
+            ip += 1;
+        }
+    }
+    emit_lzo_literals(&mut out, &src[lit_start..]);
+    out.extend_from_slice(&[0x11, 0x00, 0x00]); // M4 with distance 0 = end of stream
+    out
+}
+
+fn emit_lzo_literals(out: &mut Vec<u8>, lits: &[u8]) {
+    let n = lits.len();
+    if n == 0 {
+        return;
+    }
+    if out.is_empty() && n <= 238 {
+        out.push(17 + n as u8);
+    } else if n <= 3 {
+        // Up to three literals ride in the low bits of the previous match.
+        let at = out.len() - 2;
+        out[at] |= n as u8;
+    } else if n <= 18 {
+        out.push(n as u8 - 3);
+    } else {
+        out.push(0);
+        push_lzo_run(out, n - 18);
+    }
+    out.extend_from_slice(lits);
+}
+
+fn emit_lzo_match(out: &mut Vec<u8>, dist: usize, len: usize) {
+    let off = dist - 1;
+    if len <= 8 && dist <= 0x0800 {
+        // M2
+        out.push((((len - 1) << 5) | ((off & 7) << 2)) as u8);
+        out.push((off >> 3) as u8);
+    } else {
+        // M3
+        if len <= 33 {
+            out.push(32 | (len - 2) as u8);
+        } else {
+            out.push(32);
+            push_lzo_run(out, len - 33);
+        }
+        out.push((off << 2) as u8);
+        out.push((off >> 6) as u8);
+    }
+}
+
+/// Extended length: zero bytes worth 255 each, then the remainder (never zero).
+fn push_lzo_run(out: &mut Vec<u8>, mut n: usize) {
+    while n > 255 {
+        out.push(0);
+        n -= 255;
+    }
+    out.push(n as u8);
+}
+
+/// The low two bits of the byte before a match's last byte carry 0..3 trailing literals.
+fn after_match(r: &Input) -> Step {
+    match r.src[r.ip - 2] & 3 {
+        0 => Step::Literal,
+        n => Step::MatchNext(n as usize),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::map_document::MapDocument;
+
+    const SAMPLES: [&str; 2] = ["src/samplemap/sample.map", "src/samplemap/sample_edge_higher.map"];
+
+    fn pack(doc: &MapDocument, name: &str, decode: fn(&[u8]) -> Result<Vec<u8>>) -> Vec<u8> {
+        let section = doc.section(name).unwrap();
+        decode(&decode_base64_section(section).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn format5_round_trips_the_sample_packs() {
+        for path in SAMPLES {
+            let doc = MapDocument::load(path).unwrap();
+            for name in ["IsoMapPack5", "PreviewPack"] {
+                let data = pack(&doc, name, decode_format5);
+                assert!(!data.is_empty(), "{path} [{name}]");
+                assert_eq!(decode_format5(&encode_format5(&data)).unwrap(), data, "{path} [{name}]");
+            }
+        }
+    }
+
+    #[test]
+    fn format80_round_trips_the_sample_packs() {
+        for path in SAMPLES {
+            let doc = MapDocument::load(path).unwrap();
+            for name in ["OverlayPack", "OverlayDataPack"] {
+                let data = pack(&doc, name, decode_format80);
+                assert!(!data.is_empty(), "{path} [{name}]");
+                assert_eq!(decode_format80(&encode_format80(&data)).unwrap(), data, "{path} [{name}]");
+            }
+        }
+    }
+
+    #[test]
+    fn base64_sections_round_trip() {
+        let doc = MapDocument::load(SAMPLES[0]).unwrap();
+        let section = doc.section("OverlayPack").unwrap();
+        let mut copy = section.clone();
+        encode_base64_section(&mut copy, &decode_base64_section(section).unwrap());
+        assert_eq!(decode_base64_section(&copy).unwrap(), decode_base64_section(section).unwrap());
+    }
+}
@@ -0,0 +1,187 @@
+//! RA2 string tables (`ra2.csf`, `ra2md.csf`): the localized text behind
+//! `UIName=` labels, trigger messages and the rest of the game's UI.
+//!
+//! ```text
+//! " FSC" | u32 version | u32 label_count | u32 string_count | u32 unused | u32 language
+//! label_count × labels:
+//!   " LBL" | u32 string_count | u32 name_len | name (ASCII)
+//!   string_count × strings:
+//!     " RTS" | u32 len | len × u16 UTF-16LE, every byte inverted
+//!     "WRTS" | the same, then u32 extra_len | extra_len bytes (ASCII extra value)
+//! ```
+//!
+//! Only the first string of a label is used, as in the game.
+
+use anyhow::{anyhow, bail, Result};
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use super::mix::Vfs;
+
+const LANGUAGES: [&str; 10] = [
+    "English (US)", "English (UK)", "German", "French", "Spanish", "Italian", "Japanese", "Jabberwockie",
+    "Korean", "Chinese",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsfString {
+    pub value: String,
+    /// The extra value of `WRTS` strings (usually the name of a speech file).
+    pub extra: Option<String>,
+}
+
+/// Every label of the game's string tables, looked up case-insensitively.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct StringTable {
+    pub language: u32,
+    /// Upper-cased label to its string.
+    pub strings: HashMap<String, CsfString>,
+    /// Files the table was read from.
+    pub sources: Vec<PathBuf>,
+}
+
+fn u32_at(b: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(b.get(at..at + 4)?.try_into().ok()?))
+}
+
+impl StringTable {
+    /// `ra2.csf` with `ra2md.csf` (Yuri's Revenge) on top; `None` when the
+    /// game files have neither.
+    pub fn from_vfs(vfs: &Vfs) -> Option<Result<Self>> {
+        let files: Vec<_> = ["ra2.csf", "ra2md.csf"].iter().filter_map(|name| vfs.read(name)).collect();
+        if files.is_empty() {
+            return None;
+        }
+        let mut table = Self::default();
+        for file in files {
+            match file.and_then(|(source, bytes)| Ok((source, Self::parse(&bytes)?))) {
+                Ok((source, next)) => {
+                    table.language = next.language;
+                    table.strings.extend(next.strings);
+                    table.sources.push(source);
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        Some(Ok(table))
+    }
+
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        if !bytes.starts_with(b" FSC") || bytes.len() < 24 {
+            bail!("not a CSF file");
+        }
+        let labels = u32_at(bytes, 8).unwrap_or(0) as usize;
+        let language = u32_at(bytes, 20).unwrap_or(0);
+        let mut strings = HashMap::with_capacity(labels.min(1 << 16));
+        let mut pos = 24;
+        for _ in 0..labels {
+            // Some tools leave padding between labels; the game looks for the next marker.
+            let Some(skip) = bytes.get(pos..).and_then(|b| b.windows(4).position(|w| w == b" LBL")) else {
+                break;
+            };
+            pos += skip;
+            let truncated = || anyhow!("CSF label at byte {pos} is truncated");
+            let count = u32_at(bytes, pos + 4).ok_or_else(truncated)?;
+            let name_len = u32_at(bytes, pos + 8).ok_or_else(truncated)? as usize;
+            let name = bytes.get(pos + 12..pos + 12 + name_len).ok_or_else(truncated)?;
+            let name = String::from_utf8_lossy(name).to_ascii_uppercase();
+            pos += 12 + name_len;
+            for k in 0..count {
+                let (s, next) = parse_string(bytes, pos).ok_or_else(|| anyhow!("CSF string of {name} is truncated"))?;
+                pos = next;
+                if k == 0 {
+                    strings.insert(name.clone(), s);
+                }
+            }
+        }
+        Ok(Self { language, strings, sources: Vec::new() })
+    }
+
+    pub fn language_name(&self) -> &'static str {
+        LANGUAGES.get(self.language as usize).copied().unwrap_or("Unknown")
+    }
+
+    /// Text of `label` (`Name:HTNK`), if the tables have it.
+    pub fn get(&self, label: &str) -> Option<&str> {
+        let label = label.trim();
+        if label.is_empty() {
+            return None;
+        }
+        self.strings.get(&label.to_ascii_uppercase()).map(|s| s.value.as_str())
+    }
+}
+
+/// One ` RTS`/`WRTS` string at `pos`, and the position after it.
+fn parse_string(bytes: &[u8], pos: usize) -> Option<(CsfString, usize)> {
+    let has_extra = match bytes.get(pos..pos + 4)? {
+        b" RTS" => false,
+        b"WRTS" => true,
+        _ => return None,
+    };
+    let len = u32_at(bytes, pos + 4)? as usize;
+    let raw = bytes.get(pos + 8..pos + 8 + len * 2)?;
+    let units: Vec<u16> = raw.chunks_exact(2).map(|c| u16::from_le_bytes([!c[0], !c[1]])).collect();
+    let value = String::from_utf16_lossy(&units);
+    let mut next = pos + 8 + len * 2;
+    let extra = if has_extra {
+        let n = u32_at(bytes, next)? as usize;
+        let extra = String::from_utf8_lossy(bytes.get(next + 4..next + 4 + n)?).into_owned();
+        next += 4 + n;
+        Some(extra)
+    } else {
+        None
+    };
+    Some((CsfString { value, extra }, next))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `text` as CSF string data: UTF-16LE with every byte inverted.
+    fn encoded(text: &str) -> Vec<u8> {
+        text.encode_utf16().flat_map(u16::to_le_bytes).map(|b| !b).collect()
+    }
+
+    fn label(bytes: &mut Vec<u8>, name: &str, marker: &[u8; 4], text: &str, extra: Option<&str>) {
+        bytes.extend_from_slice(b" LBL");
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        bytes.extend_from_slice(&(name.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(name.as_bytes());
+        bytes.extend_from_slice(marker);
+        bytes.extend_from_slice(&(text.encode_utf16().count() as u32).to_le_bytes());
+        bytes.extend_from_slice(&encoded(text));
+        if let Some(x) = extra {
+            bytes.extend_from_slice(&(x.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(x.as_bytes());
+        }
+    }
+
+    fn table() -> Vec<u8> {
+        let mut bytes = b" FSC".to_vec();
+        for n in [3u32, 2, 2, 0, 2] {
+            bytes.extend_from_slice(&n.to_le_bytes());
+        }
+        label(&mut bytes, "Name:HTNK", b" RTS", "Rhino Tank", None);
+        label(&mut bytes, "TXT_Intro", b"WRTS", "Commandeur, écoutez", Some("ceva001"));
+        bytes
+    }
+
+    #[test]
+    fn reads_inverted_utf16_and_wrts_extras() {
+        let csf = StringTable::parse(&table()).unwrap();
+        assert_eq!(csf.language_name(), "German");
+        assert_eq!(csf.get("name:htnk"), Some("Rhino Tank"));
+        assert_eq!(
+            csf.strings.get("TXT_INTRO"),
+            Some(&CsfString { value: "Commandeur, écoutez".to_string(), extra: Some("ceva001".to_string()) })
+        );
+    }
+
+    #[test]
+    fn rejects_a_truncated_string() {
+        let bytes = table();
+        assert!(StringTable::parse(&bytes[..bytes.len() - 1]).is_err());
+    }
+}
@@ -0,0 +1,37 @@
+//! `[Digest] 1=`: a base64 SHA-1 FinalAlert writes at the end of every map.
+//!
+//! What it covers hasn't been worked out: no layout of the sample map's INI
+//! text (raw, re-serialized, with or without CRLF or blank lines) hashes to
+//! the stored value. The editor therefore neither checks nor rewrites it;
+//! saving keeps the section exactly as it was opened.
+
+use super::map_document::MapDocument;
+
+/// `[Digest] 1=` as stored in the file, if any.
+pub fn stored_digest(doc: &MapDocument) -> Option<&str> {
+    doc.get("Digest", "1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::iso_map::IsoMapGrid;
+    use crate::backend::map_parser::MapHeader;
+    use crate::backend::map_writer::save_map;
+    use crate::backend::overlay::OverlayLayer;
+
+    #[test]
+    fn saving_keeps_the_stored_digest() {
+        let mut doc = MapDocument::load("src/samplemap/sample.map").unwrap();
+        let before = stored_digest(&doc).map(str::to_string);
+        let h = MapHeader::from_document(&doc).unwrap();
+        let iso = IsoMapGrid::from_document(&doc, h.width, h.height).unwrap();
+        let overlay = OverlayLayer::from_document(&doc).unwrap();
+        let path = std::env::temp_dir().join("digest_keeps_stored.map");
+        save_map(&path, &mut doc, Some(&iso), Some(&overlay)).unwrap();
+        let saved = MapDocument::load(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(before.as_deref(), Some("IY6aSY+j9EvdnTf907xdzoJsKE4="));
+        assert_eq!(stored_digest(&saved), before.as_deref());
+    }
+}
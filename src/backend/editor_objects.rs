@@ -1,23 +1,45 @@
 use bevy::prelude::*;
 use bevy_egui::egui;
 
+use super::csf::StringTable;
+use super::map_parser::Theater;
+use super::objects::{MapObject, ObjectKind};
+use super::rules_catalog::{CatalogEntry, CatalogKind, ObjectCatalog};
+use super::overlay::{Overlay, ResourceKind};
+use super::terrain_objects::{Smudge, TerrainObject};
+use super::waypoints::Waypoint;
+
 // ----- Tools / placements -----
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Tool {
     Select,
     Spawn,
-    Resource,
-    Unit,
+    /// Places ore or gems (an `[OverlayPack]` resource overlay).
+    Resource(ResourceKind),
+    /// Places `ToolState::object_type` into the matching placement section.
+    Object(ObjectKind),
+    /// Places `ToolState::object_type` as a `[Terrain]` object / `[Smudge]`.
+    Terrain,
+    Smudge,
+    /// Places the `[OverlayTypes]` entry with this index.
+    Overlay(u8),
+    Waypoint,
 }
 
 #[derive(Resource, Debug, Clone)]
 pub struct ToolState {
     pub current: Tool,
+    /// Start location (waypoint 0-7) the Spawn tool places or moves.
+    pub spawn_slot: u32,
+    /// Type ID (`MTNK`, `E1`, `TREE01`, ...) the Object, Terrain and Smudge
+    /// tools place, and the owner of placed objects.
+    pub object_type: String,
+    pub object_house: String,
 }
 impl Default for ToolState {
     fn default() -> Self {
-        Self { current: Tool::Select }
+        Self { current: Tool::Select, spawn_slot: 0, object_type: String::new(), object_house: String::new() }
     }
 }
 
@@ -26,19 +48,95 @@ pub struct Placement {
     pub kind: Tool,
     pub x: i32,
     pub y: i32,
+    /// For `Tool::Resource`: the `[OverlayPack]` ore/gem overlay this marker stands for.
+    pub overlay: Option<Overlay>,
 }
 
 #[derive(Resource, Debug, Default, Clone)]
 pub struct EditorObjects {
     pub items: Vec<Placement>,
+    /// `[Waypoints]` of the open map (0-7 are the player start locations).
+    pub waypoints: Vec<Waypoint>,
+    /// `[Units]`, `[Infantry]`, `[Structures]` and `[Aircraft]` of the open map.
+    pub objects: Vec<MapObject>,
+    /// `[Terrain]` (trees, rocks, ...) and `[Smudge]` (craters, scorch marks).
+    pub terrain: Vec<TerrainObject>,
+    pub smudges: Vec<Smudge>,
+}
+
+impl EditorObjects {
+    pub fn waypoint_at(&self, x: i32, y: i32) -> Option<&Waypoint> {
+        self.waypoints.iter().find(|w| w.x == x && w.y == y)
+    }
+
+    pub fn objects_at(&self, x: i32, y: i32) -> impl Iterator<Item = &MapObject> {
+        self.objects.iter().filter(move |o| o.x == x && o.y == y)
+    }
+
+    pub fn terrain_at(&self, x: i32, y: i32) -> Option<&TerrainObject> {
+        self.terrain.iter().find(|t| (t.x, t.y) == (x, y))
+    }
+
+    pub fn smudges_at(&self, x: i32, y: i32) -> impl Iterator<Item = &Smudge> {
+        self.smudges.iter().filter(move |s| (s.x, s.y) == (x, y))
+    }
+
+    /// Place a new object unless the cell is taken: one vehicle, structure and
+    /// aircraft per cell, infantry in the free sub-cells. Objects without an
+    /// owner are refused, since the game can't read them.
+    pub fn place_object(&mut self, mut object: MapObject) -> bool {
+        if object.house.is_empty() {
+            return false;
+        }
+        let here: Vec<&MapObject> = self.objects_at(object.x, object.y).filter(|o| o.kind == object.kind).collect();
+        if object.kind == ObjectKind::Infantry {
+            let Some(free) = super::objects::SUB_CELLS.into_iter().find(|s| here.iter().all(|o| o.sub_cell != *s)) else {
+                return false;
+            };
+            object.sub_cell = free;
+        } else if !here.is_empty() {
+            return false;
+        }
+        self.objects.push(object);
+        true
+    }
 }
 
 pub fn kind_color(kind: Tool) -> egui::Color32 {
     match kind {
         Tool::Select   => egui::Color32::from_rgb(200, 200, 220),
         Tool::Spawn    => egui::Color32::from_rgb( 60, 220, 120),
-        Tool::Resource => egui::Color32::from_rgb(245, 210,  60),
-        Tool::Unit     => egui::Color32::from_rgb( 60, 200, 245),
+        Tool::Resource(_) => egui::Color32::from_rgb(245, 210,  60),
+        Tool::Object(_) => egui::Color32::from_rgb( 60, 200, 245),
+        Tool::Terrain  => egui::Color32::from_rgb( 50, 140,  60),
+        Tool::Smudge   => egui::Color32::from_rgb( 70,  55,  45),
+        Tool::Overlay(_) => egui::Color32::from_rgb(150, 150, 160),
+        Tool::Waypoint => egui::Color32::from_rgb(170, 170, 255),
+    }
+}
+
+/// Marker color; resources loaded from the map are told apart as ore vs gems.
+pub fn placement_color(p: &Placement) -> egui::Color32 {
+    match p.overlay.and_then(|o| o.resource()) {
+        Some(ResourceKind::Ore) => egui::Color32::from_rgb(230, 70, 70),
+        Some(ResourceKind::Gem) => egui::Color32::from_rgb(245, 220, 80),
+        None => kind_color(p.kind),
+    }
+}
+
+/// Rough RGB for the house colors in rules' `[Colors]`; unknown names get grey.
+pub fn house_color(color: &str) -> egui::Color32 {
+    match color.to_ascii_lowercase().as_str() {
+        "gold" | "yellow" => egui::Color32::from_rgb(230, 190, 40),
+        "darkblue" | "blue" => egui::Color32::from_rgb(50, 80, 220),
+        "lightblue" => egui::Color32::from_rgb(120, 180, 240),
+        "darkred" | "red" => egui::Color32::from_rgb(200, 40, 40),
+        "teal" => egui::Color32::from_rgb(40, 170, 170),
+        "purple" | "purple2" | "purple3" => egui::Color32::from_rgb(150, 70, 200),
+        "orange" => egui::Color32::from_rgb(240, 140, 30),
+        "green" | "darkgreen" => egui::Color32::from_rgb(50, 170, 60),
+        "pink" => egui::Color32::from_rgb(240, 130, 200),
+        _ => egui::Color32::from_rgb(150, 150, 150),
     }
 }
 
@@ -48,14 +146,24 @@ pub fn kind_color(kind: Tool) -> egui::Color32 {
 pub enum PaletteTab {
     Resource,
     SpawnPoints,
-    // add Units, Structures, etc. later
+    Vehicles,
+    Infantry,
+    Structures,
+    Aircraft,
+    Overlays,
+    Terrain,
+    Smudges,
 }
 
 #[derive(Debug, Clone)]
 pub struct PaletteEntry {
-    pub name: &'static str,
+    pub name: String,
     pub emoji: &'static str,
     pub color: egui::Color32,
+    /// Tool that becomes active when the entry is picked.
+    pub tool: Tool,
+    /// Type ID placed by Object, Terrain and Smudge entries.
+    pub type_id: Option<String>,
 }
 
 #[derive(Resource, Debug, Clone)]
@@ -70,18 +178,114 @@ impl Default for PaletteState {
     }
 }
 
+// Spawn entries come first in their tab, so "Player N" sits at index N-1 = its
+// start waypoint number.
+// Object tabs list the loaded rules catalog (types allowed in the map's
+// `theater`, when a map is open) under their localized names, and
+// fall back to a few stock types when no game directory is set.
 // Return an owned Vec to avoid borrowing a temporary slice (fixes E0515).
-pub fn palette_entries(tab: PaletteTab) -> Vec<PaletteEntry> {
+pub fn palette_entries(
+    tab: PaletteTab,
+    catalog: &ObjectCatalog,
+    strings: &StringTable,
+    theater: Option<Theater>,
+) -> Vec<PaletteEntry> {
+    let catalog_tab = match tab {
+        PaletteTab::Vehicles => Some((CatalogKind::Vehicle, "🚙")),
+        PaletteTab::Infantry => Some((CatalogKind::Infantry, "🚶")),
+        PaletteTab::Structures => Some((CatalogKind::Building, "🏭")),
+        PaletteTab::Aircraft => Some((CatalogKind::Aircraft, "✈")),
+        PaletteTab::Overlays => Some((CatalogKind::Overlay, "🧱")),
+        PaletteTab::Terrain => Some((CatalogKind::Terrain, "🌲")),
+        PaletteTab::Smudges => Some((CatalogKind::Smudge, "💥")),
+        PaletteTab::Resource | PaletteTab::SpawnPoints => None,
+    };
+    if let Some((kind, emoji)) = catalog_tab
+        && catalog.of_kind(kind).next().is_some()
+    {
+        return catalog
+            .of_kind(kind)
+            .filter(|e| theater.is_none_or(|t| e.allowed_in(t)))
+            .filter_map(|e| {
+                let tool = catalog_tool(e)?;
+                let name = e.localized_name(strings).to_string();
+                Some(PaletteEntry { name, emoji, color: kind_color(tool), tool, type_id: Some(e.id.clone()) })
+            })
+            .collect();
+    }
+
     match tab {
         PaletteTab::Resource => vec![
-            PaletteEntry { name: "Ore",   emoji: "🧱", color: egui::Color32::from_rgb(230, 70, 70) },
-            PaletteEntry { name: "Gem",   emoji: "💎", color: egui::Color32::from_rgb(245, 220, 80) },
-            PaletteEntry { name: "Oil",   emoji: "🛢️", color: egui::Color32::from_rgb(70, 200, 110) },
+            PaletteEntry { name: "Ore".into(),   emoji: "🧱", color: egui::Color32::from_rgb(230, 70, 70),  tool: Tool::Resource(ResourceKind::Ore), type_id: None },
+            PaletteEntry { name: "Gem".into(),   emoji: "💎", color: egui::Color32::from_rgb(245, 220, 80), tool: Tool::Resource(ResourceKind::Gem), type_id: None },
+            // Oil is a tech building, not a field overlay.
+            PaletteEntry { name: "Oil Derrick".into(), emoji: "🛢️", color: egui::Color32::from_rgb(70, 200, 110), tool: Tool::Object(ObjectKind::Structure), type_id: Some("CAOILD".into()) },
         ],
         PaletteTab::SpawnPoints => vec![
-            PaletteEntry { name: "Player 1", emoji: "①", color: egui::Color32::from_rgb( 90, 180, 255) },
-            PaletteEntry { name: "Player 2", emoji: "②", color: egui::Color32::from_rgb(255, 150, 120) },
-            PaletteEntry { name: "Waypoint", emoji: "⭐", color: egui::Color32::from_rgb(170, 170, 255) },
+            PaletteEntry { name: "Player 1".into(), emoji: "①", color: egui::Color32::from_rgb( 90, 180, 255), tool: Tool::Spawn, type_id: None },
+            PaletteEntry { name: "Player 2".into(), emoji: "②", color: egui::Color32::from_rgb(255, 150, 120), tool: Tool::Spawn, type_id: None },
+            PaletteEntry { name: "Player 3".into(), emoji: "③", color: egui::Color32::from_rgb(120, 230, 140), tool: Tool::Spawn, type_id: None },
+            PaletteEntry { name: "Player 4".into(), emoji: "④", color: egui::Color32::from_rgb(250, 220, 100), tool: Tool::Spawn, type_id: None },
+            PaletteEntry { name: "Player 5".into(), emoji: "⑤", color: egui::Color32::from_rgb(200, 130, 250), tool: Tool::Spawn, type_id: None },
+            PaletteEntry { name: "Player 6".into(), emoji: "⑥", color: egui::Color32::from_rgb(100, 230, 230), tool: Tool::Spawn, type_id: None },
+            PaletteEntry { name: "Player 7".into(), emoji: "⑦", color: egui::Color32::from_rgb(250, 160, 210), tool: Tool::Spawn, type_id: None },
+            PaletteEntry { name: "Player 8".into(), emoji: "⑧", color: egui::Color32::from_rgb(190, 190, 190), tool: Tool::Spawn, type_id: None },
+            PaletteEntry { name: "Waypoint".into(), emoji: "⭐", color: egui::Color32::from_rgb(170, 170, 255), tool: Tool::Waypoint, type_id: None },
         ],
+        PaletteTab::Vehicles => object_entries(ObjectKind::Unit, "🚙", &[
+            ("Grizzly", "MTNK"), ("Rhino", "HTNK"), ("Apocalypse", "APOC"), ("IFV", "FV"),
+            ("Prism Tank", "SREF"), ("Flak Track", "HTK"), ("Allied MCV", "AMCV"), ("Soviet MCV", "SMCV"),
+            ("Chrono Miner", "CMIN"), ("War Miner", "HARV"),
+        ]),
+        PaletteTab::Infantry => object_entries(ObjectKind::Infantry, "🚶", &[
+            ("GI", "E1"), ("Conscript", "E2"), ("Engineer", "ENGINEER"), ("Attack Dog", "ADOG"),
+            ("Tesla Trooper", "SHK"), ("Rocketeer", "JUMPJET"), ("Tanya", "TANY"), ("Spy", "SPY"),
+        ]),
+        PaletteTab::Structures => object_entries(ObjectKind::Structure, "🏭", &[
+            ("Allied Con. Yard", "GACNST"), ("Soviet Con. Yard", "NACNST"), ("Allied Power", "GAPOWR"),
+            ("Tesla Reactor", "NAPOWR"), ("Allied Refinery", "GAREFN"), ("Soviet Refinery", "NAREFN"),
+            ("Pillbox", "GAPILL"), ("Sentry Gun", "NALASR"), ("Tech Oil Derrick", "CAOILD"),
+            ("Tech Airport", "CAAIRP"),
+        ]),
+        PaletteTab::Aircraft => object_entries(ObjectKind::Aircraft, "✈", &[
+            ("Harrier", "ORCA"), ("Black Eagle", "BEAG"), ("Kirov", "ZEP"), ("Night Hawk", "SHAD"),
+        ]),
+        PaletteTab::Terrain => stock_entries(Tool::Terrain, "🌲", &[
+            ("Tree 1", "TREE01"), ("Tree 2", "TREE02"), ("Tree 5", "TREE05"), ("Tree 10", "TREE10"),
+            ("Tree 15", "TREE15"), ("Clump 1", "TC01"), ("Clump 2", "TC02"), ("Clump 3", "TC03"),
+            ("Clump 4", "TC04"), ("Clump 5", "TC05"),
+        ]),
+        PaletteTab::Smudges => stock_entries(Tool::Smudge, "💥", &[
+            ("Crater 1", "CR1"), ("Crater 2", "CR2"), ("Crater 3", "CR3"), ("Crater 4", "CR4"),
+            ("Scorch 1", "SC1"), ("Scorch 2", "SC2"), ("Scorch 3", "SC3"), ("Scorch 4", "SC4"),
+        ]),
+        // Overlay IDs depend on the game's list, so there is no stock set.
+        PaletteTab::Overlays => Vec::new(),
     }
 }
+
+/// Tool that places a catalog entry.
+fn catalog_tool(e: &CatalogEntry) -> Option<Tool> {
+    match e.kind {
+        CatalogKind::Infantry | CatalogKind::Vehicle | CatalogKind::Building | CatalogKind::Aircraft => {
+            e.kind.object_kind().map(Tool::Object)
+        }
+        // Overlay cells store the list index in one byte.
+        CatalogKind::Overlay => u8::try_from(e.index).ok().map(Tool::Overlay),
+        CatalogKind::Terrain => Some(Tool::Terrain),
+        CatalogKind::Smudge => Some(Tool::Smudge),
+    }
+}
+
+/// Stock objects for one tab (stand-in until the game's rules are loaded).
+fn object_entries(kind: ObjectKind, emoji: &'static str, items: &[(&'static str, &'static str)]) -> Vec<PaletteEntry> {
+    stock_entries(Tool::Object(kind), emoji, items)
+}
+
+fn stock_entries(tool: Tool, emoji: &'static str, items: &[(&'static str, &'static str)]) -> Vec<PaletteEntry> {
+    let color = kind_color(tool);
+    items
+        .iter()
+        .map(|&(name, id)| PaletteEntry { name: name.into(), emoji, color, tool, type_id: Some(id.into()) })
+        .collect()
+}
@@ -1,5 +1,7 @@
 use bevy::prelude::*;
 
+use super::triggers::Trigger;
+
 /// Menu action: open/close a folder via OS dialog.
 #[derive(Event, Debug, Clone)]
 pub enum OpenFolder {
@@ -13,3 +15,39 @@ pub enum OpenFolder {
 pub struct OpenMap {
     pub path: String, // absolute or normalized path from Node.id
 }
+
+/// Menu action: write the open map back to disk.
+#[derive(Event, Debug, Clone)]
+pub enum SaveMap {
+    /// Overwrite the file the map was opened from.
+    Save,
+    /// Ask for a new file name via OS dialog.
+    SaveAs,
+}
+
+/// Trigger editor action; applied to the open map's `TriggerSet` by the backend.
+#[derive(Event, Debug, Clone)]
+pub enum TriggerEdit {
+    /// New trigger owned by `house`, with a one-time tag bound to it.
+    Add { house: String },
+    /// Delete a trigger with its tags and the cell tags using them.
+    Remove { id: String },
+    /// Replace the trigger with the same ID (properties, events and actions).
+    Update(Trigger),
+}
+
+/// Ask for an `ai.ini`/`aimd.ini` to name the map's `[AITriggerTypesEnable]` entries.
+#[derive(Event, Debug, Clone)]
+pub struct LoadAiIni;
+
+/// Menu action: pick the game directory the rules/art catalog is read from.
+#[derive(Event, Debug, Clone)]
+pub struct SetGameDir;
+
+/// Rules overrides action: remove `[section] key` from the open map so the
+/// global rules value applies again.
+#[derive(Event, Debug, Clone)]
+pub struct ResetRulesKey {
+    pub section: String,
+    pub key: String,
+}
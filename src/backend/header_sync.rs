@@ -0,0 +1,83 @@
+use super::map_document::MapDocument;
+use super::waypoints::{Waypoint, START_WAYPOINTS};
+
+/// `[Header]` values the game's multiplayer setup reads instead of `[Waypoints]`,
+/// in FinalAlert's coordinate space (not iso cells).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeaderStarts {
+    pub start_x: i32,
+    pub start_y: i32,
+    /// `Waypoint1..8` = start waypoints 0-7; missing ones are written as `0,0`.
+    pub waypoints: [(i32, i32); START_WAYPOINTS as usize],
+    pub number_starting_points: usize,
+}
+
+impl HeaderStarts {
+    /// Derive the header values from `[Map] Size`/`LocalSize` and the start waypoints.
+    pub fn compute(doc: &MapDocument, waypoints: &[Waypoint]) -> Self {
+        let width = rect(doc, "Size").map_or(0, |[_, _, w, _]| w);
+        let [local_x, local_y, ..] = rect(doc, "LocalSize").unwrap_or_default();
+
+        let mut slots = [(0, 0); START_WAYPOINTS as usize];
+        let mut count = 0;
+        for w in waypoints.iter().filter(|w| w.is_start_location()) {
+            slots[w.number as usize] = header_coords(w);
+            count += 1;
+        }
+
+        Self {
+            start_x: local_x + 256 - width / 2,
+            start_y: local_y + width / 2 + 1,
+            waypoints: slots,
+            number_starting_points: count,
+        }
+    }
+
+    fn entries(&self) -> Vec<(String, String)> {
+        let mut out = vec![
+            ("StartX".to_string(), self.start_x.to_string()),
+            ("StartY".to_string(), self.start_y.to_string()),
+        ];
+        for (i, (x, y)) in self.waypoints.iter().enumerate() {
+            out.push((format!("Waypoint{}", i + 1), format!("{x},{y}")));
+        }
+        out.push(("NumberStartingPoints".to_string(), self.number_starting_points.to_string()));
+        out
+    }
+
+    /// Write the values into `[Header]`; unchanged keys keep their original lines.
+    pub fn apply(&self, doc: &mut MapDocument) {
+        let section = doc.ensure_section("Header");
+        for (key, value) in self.entries() {
+            section.set(&key, &value);
+        }
+    }
+
+    /// `key=file value (expected ...)` for every `[Header]` key that disagrees.
+    pub fn mismatches(&self, doc: &MapDocument) -> Vec<String> {
+        self.entries()
+            .into_iter()
+            .filter_map(|(key, want)| {
+                let have = doc.get("Header", &key).map(|v| v.replace(' ', ""));
+                (have.as_deref() != Some(want.as_str()))
+                    .then(|| format!("{key}={} (expected {want})", have.as_deref().unwrap_or("<missing>")))
+            })
+            .collect()
+    }
+}
+
+/// Iso cell → `[Header] WaypointN` coordinates.
+fn header_coords(w: &Waypoint) -> (i32, i32) {
+    ((w.x - w.y + 512) / 2, (w.x + w.y + 1) / 2)
+}
+
+/// `[Map] key=X,Y,W,H`.
+fn rect(doc: &MapDocument, key: &str) -> Option<[i32; 4]> {
+    let nums: Vec<i32> = doc
+        .get("Map", key)?
+        .split(',')
+        .map(|s| s.trim().parse())
+        .collect::<Result<_, _>>()
+        .ok()?;
+    nums.try_into().ok()
+}
@@ -0,0 +1,192 @@
+use super::map_document::{parse_bool, MapDocument, Section};
+use super::rules_overrides::MAP_SECTIONS;
+
+/// Edges a house's reinforcements can arrive from.
+pub const EDGES: [&str; 4] = ["North", "East", "South", "West"];
+
+/// One house from `[Houses]` plus its own `[<Name>]` section.
+///
+/// Only the keys below are typed; anything else in the section (base nodes,
+/// `PlayerControl` extras from other editors, ...) is left untouched on write.
+#[derive(Debug, Clone, PartialEq)]
+pub struct House {
+    pub name: String,
+    pub iq: i32,
+    pub edge: String,
+    pub color: String,
+    /// House names this house is allied with (itself included, as FinalAlert writes it).
+    pub allies: Vec<String>,
+    pub country: String,
+    pub credits: i32,
+    pub node_count: i32,
+    pub tech_level: i32,
+    pub percent_built: i32,
+    pub player_control: bool,
+}
+
+impl House {
+    /// A new house with FinalAlert's defaults, allied only with itself.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            iq: 0,
+            edge: "North".to_string(),
+            color: "Grey".to_string(),
+            allies: vec![name.to_string()],
+            country: name.to_string(),
+            credits: 0,
+            node_count: 0,
+            tech_level: 1,
+            percent_built: 0,
+            player_control: false,
+        }
+    }
+
+    /// Read the typed keys from the house's section; missing ones keep the defaults.
+    pub fn from_section(name: &str, section: Option<&Section>) -> Self {
+        let mut h = Self::new(name);
+        let Some(s) = section else {
+            return h;
+        };
+        let int = |key: &str, default: i32| s.get(key).and_then(|v| v.trim().parse().ok()).unwrap_or(default);
+
+        h.iq = int("IQ", h.iq);
+        h.credits = int("Credits", h.credits);
+        h.node_count = int("NodeCount", h.node_count);
+        h.tech_level = int("TechLevel", h.tech_level);
+        h.percent_built = int("PercentBuilt", h.percent_built);
+        if let Some(v) = s.get("Edge") {
+            h.edge = v.to_string();
+        }
+        if let Some(v) = s.get("Color") {
+            h.color = v.to_string();
+        }
+        if let Some(v) = s.get("Country") {
+            h.country = v.to_string();
+        }
+        if let Some(v) = s.get("Allies") {
+            h.allies = v.split(',').map(str::trim).filter(|a| !a.is_empty()).map(String::from).collect();
+        }
+        if let Some(v) = s.get("PlayerControl") {
+            h.player_control = parse_bool(v);
+        }
+        h
+    }
+
+    /// Allies that are not the name of any house in `houses`.
+    pub fn unknown_allies<'a>(&'a self, houses: &[House]) -> Vec<&'a str> {
+        self.allies
+            .iter()
+            .filter(|a| !houses.iter().any(|h| h.name.eq_ignore_ascii_case(a)))
+            .map(String::as_str)
+            .collect()
+    }
+
+    fn write(&self, section: &mut Section) {
+        section.set("IQ", &self.iq.to_string());
+        section.set("Edge", &self.edge);
+        section.set("Color", &self.color);
+        section.set("Allies", &self.allies.join(","));
+        section.set("Country", &self.country);
+        section.set("Credits", &self.credits.to_string());
+        section.set("NodeCount", &self.node_count.to_string());
+        section.set("TechLevel", &self.tech_level.to_string());
+        section.set("PercentBuilt", &self.percent_built.to_string());
+        section.set("PlayerControl", if self.player_control { "yes" } else { "no" });
+    }
+}
+
+/// Names listed in `[Houses]`, in index order.
+fn house_names(doc: &MapDocument) -> Vec<String> {
+    let Some(section) = doc.section("Houses") else {
+        return Vec::new();
+    };
+    let mut list: Vec<(i32, String)> = section
+        .entries()
+        .filter_map(|e| Some((e.key().parse().ok()?, e.value().to_string())))
+        .collect();
+    list.sort_by_key(|(i, _)| *i);
+    list.into_iter().map(|(_, name)| name).collect()
+}
+
+/// Every house listed in `[Houses]` with its section parsed.
+pub fn parse_houses(doc: &MapDocument) -> Vec<House> {
+    house_names(doc)
+        .iter()
+        .map(|name| House::from_section(name, doc.section(name)))
+        .collect()
+}
+
+/// Whether section `name` of `doc` holds a house: it is listed in `[Houses]` and
+/// isn't one of the map's own sections or a TeamType/ScriptType/TaskForce.
+fn is_house_section(doc: &MapDocument, name: &str) -> bool {
+    let listed = |list: &str| {
+        doc.section(list).is_some_and(|s| s.entries().any(|e| e.value().eq_ignore_ascii_case(name)))
+    };
+    listed("Houses")
+        && !MAP_SECTIONS.iter().any(|s| s.eq_ignore_ascii_case(name))
+        && !["TeamTypes", "ScriptTypes", "TaskForces"].into_iter().any(listed)
+}
+
+/// Whether a house called `name` would share its section with something else
+/// in `doc` (`[Map]`, a TeamType, a rules override, ...).
+pub fn is_taken_section_name(doc: &MapDocument, name: &str) -> bool {
+    let reserved = MAP_SECTIONS.iter().any(|s| s.eq_ignore_ascii_case(name));
+    reserved || (doc.has_section(name) && !is_house_section(doc, name))
+}
+
+/// Rewrite `[Houses]` and each house section. Sections of houses that are no
+/// longer listed are removed; sections that aren't house sections are never
+/// written to or removed.
+pub fn write_houses(doc: &mut MapDocument, houses: &[House]) {
+    for old in house_names(doc) {
+        if !houses.iter().any(|h| h.name.eq_ignore_ascii_case(&old)) && is_house_section(doc, &old) {
+            doc.remove_section(&old);
+        }
+    }
+    let (houses, clashing): (Vec<&House>, Vec<&House>) =
+        houses.iter().partition(|h| !is_taken_section_name(doc, &h.name));
+    for h in clashing {
+        eprintln!("[backend] House {} shares its name with another section; not saved", h.name);
+    }
+
+    let list = doc.ensure_section("Houses");
+    list.clear_entries();
+    for (i, h) in houses.iter().enumerate() {
+        list.push(&i.to_string(), &h.name);
+    }
+
+    for h in houses {
+        h.write(doc.ensure_section(&h.name));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn houses_never_touch_other_sections() {
+        let mut doc = MapDocument::load("src/samplemap/sample.map").unwrap();
+        let lines = |d: &MapDocument| -> Vec<String> {
+            d.section("Map").unwrap().entries().map(|e| format!("{}={}", e.key(), e.value())).collect()
+        };
+        let map = lines(&doc);
+        assert!(is_taken_section_name(&doc, "Map"));
+        assert!(!is_taken_section_name(&doc, "Neutral"));
+        assert!(!is_taken_section_name(&doc, "NewHouse"));
+
+        let mut houses = parse_houses(&doc);
+        houses.push(House::new("Map"));
+        write_houses(&mut doc, &houses);
+        assert!(doc.get("Map", "IQ").is_none());
+        assert!(!house_names(&doc).iter().any(|h| h == "Map"));
+
+        // Even a map that already lists it keeps its [Map] when the house goes.
+        let n = doc.section("Houses").unwrap().entries().count();
+        doc.ensure_section("Houses").push(&n.to_string(), "Map");
+        houses.pop();
+        write_houses(&mut doc, &houses);
+        assert_eq!(lines(&doc), map);
+    }
+}
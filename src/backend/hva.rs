@@ -0,0 +1,87 @@
+//! Voxel animations (`.hva`): one transform per limb per frame.
+//!
+//! ```text
+//! char[16] name | u32 frame_count | u32 section_count
+//! section_count × char[16] limb names
+//! frame_count × section_count × f32[3][4] transforms (frame-major)
+//! ```
+
+use anyhow::{bail, Result};
+
+use super::vxl::{name_at, Matrix};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hva {
+    pub frames: usize,
+    /// Limb names, in the order of the transforms within a frame.
+    pub sections: Vec<String>,
+    transforms: Vec<Matrix>,
+}
+
+impl Hva {
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        let count = |at: usize| bytes.get(at..at + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize);
+        let (Some(frames), Some(sections)) = (count(16), count(20)) else {
+            bail!("not an HVA file");
+        };
+        let data_at = 24 + sections * 16;
+        if sections > 256 || frames > 4096 || bytes.len() < data_at + frames * sections * 48 {
+            bail!("HVA file is truncated");
+        }
+        let names = (0..sections).filter_map(|i| name_at(bytes, 24 + i * 16)).collect();
+        let transforms = bytes[data_at..data_at + frames * sections * 48]
+            .chunks_exact(48)
+            .map(|m| {
+                let f = |i: usize| f32::from_le_bytes([m[i * 4], m[i * 4 + 1], m[i * 4 + 2], m[i * 4 + 3]]);
+                [0, 1, 2].map(|r| [0, 1, 2, 3].map(|c| f(r * 4 + c)))
+            })
+            .collect();
+        Ok(Self { frames, sections: names, transforms })
+    }
+
+    /// Transform of limb `section` in `frame`.
+    pub fn transform(&self, frame: usize, section: usize) -> Option<&Matrix> {
+        if section >= self.sections.len() {
+            return None;
+        }
+        self.transforms.get(frame * self.sections.len() + section)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two frames of one limb; frame `f` translates by `f` along x.
+    fn two_frames() -> Vec<u8> {
+        let mut bytes = b"anim".to_vec();
+        bytes.resize(16, 0);
+        bytes.extend_from_slice(&2u32.to_le_bytes());
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        let mut name = b"body".to_vec();
+        name.resize(16, 0);
+        bytes.extend_from_slice(&name);
+        for f in [0.0f32, 1.0] {
+            for v in [1.0, 0.0, 0.0, f, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0] {
+                bytes.extend_from_slice(&f32::to_le_bytes(v));
+            }
+        }
+        bytes
+    }
+
+    #[test]
+    fn reads_transforms_per_frame_and_limb() {
+        let hva = Hva::parse(&two_frames()).unwrap();
+        assert_eq!((hva.frames, hva.sections.as_slice()), (2, ["body".to_string()].as_slice()));
+        assert_eq!(hva.transform(1, 0).map(|m| m[0][3]), Some(1.0));
+        assert_eq!(hva.transform(0, 0).map(|m| m[0][3]), Some(0.0));
+        assert!(hva.transform(0, 1).is_none());
+        assert!(hva.transform(2, 0).is_none());
+    }
+
+    #[test]
+    fn rejects_a_truncated_file() {
+        let bytes = two_frames();
+        assert!(Hva::parse(&bytes[..bytes.len() - 4]).is_err());
+    }
+}
@@ -0,0 +1,128 @@
+use anyhow::{anyhow, Result};
+
+use super::codec::{decode_base64_section, decode_format5};
+use super::map_document::MapDocument;
+
+/// Size of one `[IsoMapPack5]` record in bytes.
+const RECORD_LEN: usize = 11;
+
+/// One decoded `[IsoMapPack5]` cell.
+///
+/// Coordinates are the game's isometric cell coordinates (the same space used by
+/// waypoints, overlays and object placements), not workspace screen rows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IsoCell {
+    pub x: i32,
+    pub y: i32,
+    /// Index into the theater's tile list; FinalAlert writes `0xFFFF` for clear ground (tile 0).
+    pub tile: i32,
+    pub sub_tile: u8,
+    /// Height level, 0..=14 (one level is half a cell high on screen).
+    pub height: u8,
+    /// Raw flags byte; bit 0 is the "ice growth" marker.
+    /// Ramp shapes are a property of the tile itself (TMP data), not of the map.
+    pub flags: u8,
+}
+
+impl IsoCell {
+    pub fn is_ice(&self) -> bool {
+        self.flags & 1 != 0
+    }
+
+    /// Tile index with the "clear" sentinels (`0xFFFF`, negative) normalized to 0.
+    pub fn tile_index(&self) -> i32 {
+        if self.tile < 0 || self.tile == 0xFFFF { 0 } else { self.tile }
+    }
+}
+
+/// Per-cell terrain grid keyed to the `[Map] Size` rectangle.
+///
+/// The visible map is a screen-aligned `width x height` rectangle cut out of the
+/// isometric world, so the grid spans `0..=width+height` on both iso axes and only
+/// cells inside that rectangle are valid.
+#[derive(Debug, Clone, Default)]
+pub struct IsoMapGrid {
+    pub width: i32,
+    pub height: i32,
+    cells: Vec<IsoCell>,
+}
+
+impl IsoMapGrid {
+    /// Flat clear-ground grid, used when a map has no (or a broken) `[IsoMapPack5]`.
+    pub fn blank(width: i32, height: i32) -> Self {
+        let dim = (width + height + 1).max(0);
+        let mut cells = vec![IsoCell::default(); (dim * dim) as usize];
+        for y in 0..dim {
+            for x in 0..dim {
+                let c = &mut cells[(y * dim + x) as usize];
+                c.x = x;
+                c.y = y;
+            }
+        }
+        Self { width, height, cells }
+    }
+
+    /// Decode `[IsoMapPack5]` (base64 → format-5/LZO chunks → 11-byte records).
+    /// Cells the pack does not mention stay clear ground at height 0.
+    pub fn from_document(doc: &MapDocument, width: i32, height: i32) -> Result<Self> {
+        let mut grid = Self::blank(width, height);
+        let section = doc
+            .section("IsoMapPack5")
+            .ok_or_else(|| anyhow!("map has no [IsoMapPack5]"))?;
+        let data = decode_format5(&decode_base64_section(section)?)?;
+
+        for rec in data.chunks_exact(RECORD_LEN) {
+            let x = u16::from_le_bytes([rec[0], rec[1]]) as i32;
+            let y = u16::from_le_bytes([rec[2], rec[3]]) as i32;
+            // FinalAlert pads the pack with all-zero records; (0,0) is never a map cell.
+            if (x, y) == (0, 0) {
+                continue;
+            }
+            if let Some(cell) = grid.get_mut(x, y) {
+                *cell = IsoCell {
+                    x,
+                    y,
+                    tile: i32::from_le_bytes([rec[4], rec[5], rec[6], rec[7]]),
+                    sub_tile: rec[8],
+                    height: rec[9],
+                    flags: rec[10],
+                };
+            }
+        }
+        Ok(grid)
+    }
+
+    /// Extent of both iso axes (`width + height + 1`).
+    pub fn dim(&self) -> i32 {
+        self.width + self.height + 1
+    }
+
+    /// Whether iso cell `(x, y)` lies inside the `[Map] Size` rectangle.
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        // Screen-space half-cell column/row of the cell (inverse of the game's
+        // rx = (dx + dy) / 2 + 1, ry = dy - rx + width + 1).
+        let dx = x - y + self.width - 1;
+        let dy = x + y - self.width - 1;
+        (0..2 * self.width - 1).contains(&dx) && (0..2 * self.height).contains(&dy)
+    }
+
+    pub fn get(&self, x: i32, y: i32) -> Option<&IsoCell> {
+        if !self.contains(x, y) {
+            return None;
+        }
+        self.cells.get((y * self.dim() + x) as usize)
+    }
+
+    pub fn get_mut(&mut self, x: i32, y: i32) -> Option<&mut IsoCell> {
+        if !self.contains(x, y) {
+            return None;
+        }
+        let dim = self.dim();
+        self.cells.get_mut((y * dim + x) as usize)
+    }
+
+    /// All cells inside the map rectangle, row by row.
+    pub fn cells(&self) -> impl Iterator<Item = &IsoCell> {
+        self.cells.iter().filter(|c| self.contains(c.x, c.y))
+    }
+}
@@ -0,0 +1,135 @@
+use bevy_egui::egui;
+
+use super::map_document::{read_num, MapDocument, Section};
+
+/// One `[Lighting]` set. The game lights a cell with
+/// `Ambient + Level * height`, multiplied per channel by Red/Green/Blue.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LightingSet {
+    pub red: f32,
+    pub green: f32,
+    pub blue: f32,
+    pub ambient: f32,
+    pub level: f32,
+    pub ground: f32,
+}
+
+impl Default for LightingSet {
+    fn default() -> Self {
+        Self { red: 1.0, green: 1.0, blue: 1.0, ambient: 1.0, level: 0.0, ground: 0.0 }
+    }
+}
+
+impl LightingSet {
+    /// Fields with their key suffix (`Red`, `IonRed`, `DominatorRed`, ...).
+    pub fn values_mut(&mut self) -> [(&'static str, &mut f32); 6] {
+        [
+            ("Red", &mut self.red),
+            ("Green", &mut self.green),
+            ("Blue", &mut self.blue),
+            ("Ambient", &mut self.ambient),
+            ("Level", &mut self.level),
+            ("Ground", &mut self.ground),
+        ]
+    }
+
+    /// `base` as it would look under this light at `height`.
+    pub fn tint(&self, base: egui::Color32, height: u8) -> egui::Color32 {
+        let light = self.ambient + self.level * height as f32;
+        let ch = |c: u8, k: f32| (c as f32 * light * k).clamp(0.0, 255.0) as u8;
+        egui::Color32::from_rgb(ch(base.r(), self.red), ch(base.g(), self.green), ch(base.b(), self.blue))
+    }
+}
+
+/// Which `[Lighting]` set the workspace previews.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LightingMode {
+    /// Plain theater colors.
+    Off,
+    #[default]
+    Normal,
+    IonStorm,
+    Dominator,
+}
+
+impl LightingMode {
+    pub const ALL: [LightingMode; 4] = [Self::Off, Self::Normal, Self::IonStorm, Self::Dominator];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Off => "Unlit",
+            Self::Normal => "Normal",
+            Self::IonStorm => "Ion storm",
+            Self::Dominator => "Dominator",
+        }
+    }
+}
+
+/// Typed `[Lighting]`: the normal set plus the ion storm and Psychic Dominator sets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lighting {
+    pub normal: LightingSet,
+    pub ion: LightingSet,
+    pub dominator: LightingSet,
+    pub dominator_ambient_change_rate: f32,
+}
+
+impl Default for Lighting {
+    /// FinalAlert's values for a new map.
+    fn default() -> Self {
+        Self {
+            normal: LightingSet::default(),
+            ion: LightingSet::default(),
+            dominator: LightingSet { red: 0.85, green: 0.2, blue: 0.3, ambient: 1.5, level: 0.0, ground: 0.0 },
+            dominator_ambient_change_rate: 0.009,
+        }
+    }
+}
+
+impl Lighting {
+    /// Each set with its key prefix.
+    pub fn sets_mut(&mut self) -> [(&'static str, &mut LightingSet); 3] {
+        [("", &mut self.normal), ("Ion", &mut self.ion), ("Dominator", &mut self.dominator)]
+    }
+
+    pub fn for_mode(&self, mode: LightingMode) -> Option<&LightingSet> {
+        match mode {
+            LightingMode::Off => None,
+            LightingMode::Normal => Some(&self.normal),
+            LightingMode::IonStorm => Some(&self.ion),
+            LightingMode::Dominator => Some(&self.dominator),
+        }
+    }
+
+    pub fn from_document(doc: &MapDocument) -> Self {
+        let mut lighting = Self::default();
+        let Some(s) = doc.section("Lighting") else {
+            return lighting;
+        };
+        read_num(s, "DominatorAmbientChangeRate", &mut lighting.dominator_ambient_change_rate);
+        for (prefix, set) in lighting.sets_mut() {
+            for (name, value) in set.values_mut() {
+                read_num(s, &format!("{prefix}{name}"), value);
+            }
+        }
+        lighting
+    }
+
+    pub fn apply(&self, doc: &mut MapDocument) {
+        let s = doc.ensure_section("Lighting");
+        let mut lighting = self.clone();
+        for (prefix, set) in lighting.sets_mut() {
+            for (name, value) in set.values_mut() {
+                write_lighting(s, &format!("{prefix}{name}"), *value);
+            }
+        }
+        write_lighting(s, "DominatorAmbientChangeRate", self.dominator_ambient_change_rate);
+    }
+}
+
+/// Like `write_num`, but changed values use FinalAlert's six decimals.
+fn write_lighting(s: &mut Section, key: &str, value: f32) {
+    if s.get(key).and_then(|v| v.trim().parse::<f32>().ok()) != Some(value) {
+        s.set(key, &format!("{value:.6}"));
+    }
+}
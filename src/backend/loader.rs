@@ -4,6 +4,7 @@ use std::{
     path::{Path, PathBuf},
 };
 
+use super::mix::{mix_node, MixArchive};
 use super::project::{Node, NodeKind};
 
 pub fn load_tree_from(root: &Path, max_depth: usize, max_nodes: usize) -> Result<Node> {
@@ -54,6 +55,17 @@ fn load_dir(
                     .unwrap_or("")
                     .to_string();
 
+                // Archives browse like folders; unreadable ones stay plain files.
+                if ext.eq_ignore_ascii_case("mix") {
+                    match MixArchive::open(Path::new(&id)) {
+                        Ok(mix) => {
+                            children.push(mix_node(&mix, name, depth + 1, max_depth, max_nodes, count));
+                            continue;
+                        }
+                        Err(e) => eprintln!("[backend] {}: {e}", p.display()),
+                    }
+                }
+
                 children.push(Node {
                     id,
                     name,
@@ -102,3 +114,13 @@ fn make_leaf(path: &Path) -> Node {
         kind: NodeKind::Folder { children: vec![] },
     }
 }
+
+/// Case-insensitive lookup of `name` directly inside `dir` (game files are
+/// `RULES.INI` on some installs and `rules.ini` on others).
+pub fn find_file(dir: &Path, name: &str) -> Option<PathBuf> {
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .find(|p| p.is_file() && p.file_name().is_some_and(|n| n.eq_ignore_ascii_case(name)))
+}
@@ -0,0 +1,452 @@
+use anyhow::Result;
+use std::{fmt, fs, path::Path, str::FromStr};
+
+/// Lossless INI model of an RA2/YR `.map` file.
+///
+/// Every line is kept in file order: the comment banner before the first section,
+/// section headers, `key=value` entries (duplicates included), comments and blank
+/// lines. Untouched lines are written back verbatim, so `MapDocument::parse(t).to_string()`
+/// reproduces `t` byte for byte. Lines changed through the setters are regenerated as
+/// plain `key=value` using the file's own line ending.
+///
+/// Lookups follow the game's INI rules: section and key names are case-insensitive,
+/// and when a key appears twice the last occurrence wins.
+#[derive(Debug, Clone)]
+pub struct MapDocument {
+    /// Lines before the first `[Section]` (FinalAlert's comment banner).
+    preamble: Vec<Line>,
+    sections: Vec<Section>,
+    /// Line ending used for generated lines ("\n" or "\r\n", detected on parse).
+    newline: &'static str,
+    /// File was not valid UTF-8 and was decoded as Latin-1 (Windows-1252 maps).
+    latin1: bool,
+}
+
+impl Default for MapDocument {
+    /// An empty document; new files use the game's CRLF line endings.
+    fn default() -> Self {
+        Self { preamble: Vec::new(), sections: Vec::new(), newline: "\r\n", latin1: false }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Section {
+    name: String,
+    /// Original header line including its line ending; `None` once generated.
+    raw_header: Option<String>,
+    lines: Vec<Line>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Line {
+    /// Blank line, `;` comment or any text that is not a `key=value` pair (kept raw).
+    Other(String),
+    Entry(Entry),
+}
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+    key: String,
+    value: String,
+    /// Original text including its line ending; dropped when the value changes.
+    raw: Option<String>,
+}
+
+impl Entry {
+    fn new(key: &str, value: &str) -> Self {
+        Self { key: key.to_string(), value: value.to_string(), raw: None }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
+
+impl Section {
+    fn new(name: &str) -> Self {
+        Self { name: name.to_string(), raw_header: None, lines: Vec::new() }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// All `key=value` entries in file order, duplicates included.
+    pub fn entries(&self) -> impl Iterator<Item = &Entry> {
+        self.lines.iter().filter_map(|l| match l {
+            Line::Entry(e) => Some(e),
+            Line::Other(_) => None,
+        })
+    }
+
+    /// Value of `key` (last occurrence wins, like the game's INI loader).
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.lines.iter().rev().find_map(|l| match l {
+            Line::Entry(e) if e.key.eq_ignore_ascii_case(key) => Some(e.value.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Set `key` to `value`. Updates the last occurrence in place (keeping its
+    /// position), or appends a new entry after the last existing one.
+    pub fn set(&mut self, key: &str, value: &str) {
+        let existing = self.lines.iter_mut().rev().find_map(|l| match l {
+            Line::Entry(e) if e.key.eq_ignore_ascii_case(key) => Some(e),
+            _ => None,
+        });
+        match existing {
+            Some(e) => {
+                if e.value != value {
+                    e.value = value.to_string();
+                    e.raw = None;
+                }
+            }
+            None => self.push(key, value),
+        }
+    }
+
+    /// Append an entry even if `key` already exists, placed before the section's
+    /// trailing blank lines/comments.
+    pub fn push(&mut self, key: &str, value: &str) {
+        let at = self
+            .lines
+            .iter()
+            .rposition(|l| matches!(l, Line::Entry(_)))
+            .map(|i| i + 1)
+            .unwrap_or_else(|| self.leading_len());
+        self.lines.insert(at, Line::Entry(Entry::new(key, value)));
+    }
+
+    /// Remove every occurrence of `key`. Returns `true` if anything was removed.
+    pub fn remove(&mut self, key: &str) -> bool {
+        let before = self.lines.len();
+        self.lines
+            .retain(|l| !matches!(l, Line::Entry(e) if e.key.eq_ignore_ascii_case(key)));
+        self.lines.len() != before
+    }
+
+    /// Remove all entries but keep comments and the blank lines that separate
+    /// this section from the next one.
+    pub fn clear_entries(&mut self) {
+        self.lines.retain(|l| matches!(l, Line::Other(_)));
+    }
+
+    /// Number of non-entry lines at the start of an entry-less section that belong
+    /// *before* new entries (comments directly under the header).
+    fn leading_len(&self) -> usize {
+        self.lines
+            .iter()
+            .take_while(|l| matches!(l, Line::Other(t) if t.trim_start().starts_with(';')))
+            .count()
+    }
+}
+
+impl MapDocument {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let bytes = fs::read(path)?;
+        Ok(Self::from_bytes(&bytes))
+    }
+
+    /// Parse raw file bytes. Non-UTF-8 files are read as Latin-1 and written back
+    /// the same way by [`MapDocument::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        match std::str::from_utf8(bytes) {
+            Ok(text) => Self::parse(text),
+            Err(_) => {
+                let text: String = bytes.iter().map(|&b| b as char).collect();
+                Self { latin1: true, ..Self::parse(&text) }
+            }
+        }
+    }
+
+    pub fn parse(text: &str) -> Self {
+        let newline = if text.contains("\r\n") { "\r\n" } else { "\n" };
+        let mut doc = Self { newline, ..Default::default() };
+
+        for raw in text.split_inclusive('\n') {
+            let line = raw.trim_end_matches(['\r', '\n']).trim();
+
+            if line.starts_with('[')
+                && let Some(end) = line.find(']')
+            {
+                let mut section = Section::new(line[1..end].trim());
+                section.raw_header = Some(raw.to_string());
+                doc.sections.push(section);
+                continue;
+            }
+
+            let parsed = if line.starts_with(';') {
+                None
+            } else {
+                line.split_once('=').map(|(k, v)| {
+                    // Inline `; comment` is not part of the value (Westwood INI rules).
+                    let v = v.split_once(';').map_or(v, |(v, _)| v);
+                    Entry { key: k.trim().to_string(), value: v.trim().to_string(), raw: Some(raw.to_string()) }
+                })
+            };
+
+            let target = match doc.sections.last_mut() {
+                Some(s) => &mut s.lines,
+                None => &mut doc.preamble,
+            };
+            target.push(match parsed {
+                Some(e) => Line::Entry(e),
+                None => Line::Other(raw.to_string()),
+            });
+        }
+        doc
+    }
+
+    /// Serialize back to bytes, honoring the encoding the file was read with.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.encode_text(self.to_string())
+    }
+
+    /// Bytes `text` would have in this file's encoding (UTF-8 or Latin-1).
+    pub(super) fn encode_text(&self, text: String) -> Vec<u8> {
+        if self.latin1 {
+            text.chars().map(|c| if (c as u32) < 256 { c as u8 } else { b'?' }).collect()
+        } else {
+            text.into_bytes()
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        fs::write(path, self.to_bytes())?;
+        Ok(())
+    }
+
+    pub fn sections(&self) -> &[Section] {
+        &self.sections
+    }
+
+    /// Last section called `name` (case-insensitive), the one whose keys win
+    /// in [`MapDocument::get`].
+    pub fn section(&self, name: &str) -> Option<&Section> {
+        self.sections.iter().rev().find(|s| s.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn has_section(&self, name: &str) -> bool {
+        self.section(name).is_some()
+    }
+
+    /// Section `name` (the last one if it is duplicated), appended at the end
+    /// of the file if missing.
+    pub fn ensure_section(&mut self, name: &str) -> &mut Section {
+        let idx = match self.sections.iter().rposition(|s| s.name.eq_ignore_ascii_case(name)) {
+            Some(i) => i,
+            None => {
+                // Keep FinalAlert's "blank line between sections" layout.
+                if let Some(last) = self.sections.last_mut() {
+                    let ends_blank = matches!(last.lines.last(), Some(Line::Other(t)) if t.trim().is_empty());
+                    if !ends_blank {
+                        last.lines.push(Line::Other(self.newline.to_string()));
+                    }
+                }
+                let mut section = Section::new(name);
+                section.lines.push(Line::Other(self.newline.to_string()));
+                self.sections.push(section);
+                self.sections.len() - 1
+            }
+        };
+        &mut self.sections[idx]
+    }
+
+    /// Remove every section called `name`. Returns `true` if anything was removed.
+    pub fn remove_section(&mut self, name: &str) -> bool {
+        let before = self.sections.len();
+        self.sections.retain(|s| !s.name.eq_ignore_ascii_case(name));
+        self.sections.len() != before
+    }
+
+    /// Remove `key` from every section called `section`, duplicates included,
+    /// and drop the sections that are left without entries. Returns `true` if
+    /// anything was removed.
+    pub fn remove_key(&mut self, section: &str, key: &str) -> bool {
+        let mut emptied = Vec::new();
+        let mut removed = false;
+        for (i, s) in self.sections.iter_mut().enumerate() {
+            if s.name.eq_ignore_ascii_case(section) && s.remove(key) {
+                removed = true;
+                if s.entries().next().is_none() {
+                    emptied.push(i);
+                }
+            }
+        }
+        for i in emptied.into_iter().rev() {
+            self.sections.remove(i);
+        }
+        removed
+    }
+
+    /// Value of `[section] key`, looking through duplicate sections (last wins).
+    pub fn get(&self, section: &str, key: &str) -> Option<&str> {
+        self.sections
+            .iter()
+            .rev()
+            .filter(|s| s.name.eq_ignore_ascii_case(section))
+            .find_map(|s| s.get(key))
+    }
+
+    /// Replace the entries of section `name` with `entries`, unless they are
+    /// already exactly that (so untouched maps keep their bytes). An absent
+    /// section is only created when there is something to write.
+    pub fn replace_entries_if_changed(&mut self, name: &str, entries: &[(String, String)]) {
+        let unchanged = self.section(name).is_some_and(|s| {
+            s.entries().count() == entries.len()
+                && s.entries().zip(entries).all(|(e, (k, v))| e.key() == k && e.value() == v)
+        });
+        if unchanged || (entries.is_empty() && !self.has_section(name)) {
+            return;
+        }
+        let section = self.ensure_section(name);
+        section.clear_entries();
+        for (k, v) in entries {
+            section.push(k, v);
+        }
+    }
+}
+
+/// INI booleans as the game reads them (first letter y/t/1 is true).
+pub fn parse_bool(v: &str) -> bool {
+    matches!(v.trim().chars().next(), Some('y' | 'Y' | 't' | 'T' | '1'))
+}
+
+// Typed accessors for the editors built on top of the document. The writers
+// leave a key alone when the file already says the same thing, so untouched
+// values keep their spelling and the line stays byte-identical.
+
+pub(super) fn read_text(s: &Section, key: &str, out: &mut String) {
+    if let Some(v) = s.get(key) {
+        *out = v.to_string();
+    }
+}
+
+pub(super) fn read_num<T: FromStr>(s: &Section, key: &str, out: &mut T) {
+    if let Some(v) = s.get(key).and_then(|v| v.trim().parse().ok()) {
+        *out = v;
+    }
+}
+
+pub(super) fn read_flag(s: &Section, key: &str, out: &mut bool) {
+    if let Some(v) = s.get(key) {
+        *out = parse_bool(v);
+    }
+}
+
+pub(super) fn write_text(s: &mut Section, key: &str, value: &str) {
+    if s.get(key) != Some(value) {
+        s.set(key, value);
+    }
+}
+
+pub(super) fn write_num<T: Copy + PartialEq + FromStr + ToString>(s: &mut Section, key: &str, value: T) {
+    if s.get(key).and_then(|v| v.trim().parse::<T>().ok()) != Some(value) {
+        s.set(key, &value.to_string());
+    }
+}
+
+pub(super) fn write_flag(s: &mut Section, key: &str, value: bool) {
+    if s.get(key).map(parse_bool) != Some(value) {
+        s.set(key, if value { "yes" } else { "no" });
+    }
+}
+
+impl fmt::Display for MapDocument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut w = LineWriter { f, newline: self.newline, at_line_start: true };
+        for line in &self.preamble {
+            w.line(line)?;
+        }
+        for section in &self.sections {
+            match &section.raw_header {
+                Some(raw) => w.raw(raw)?,
+                None => w.generated(&format!("[{}]", section.name))?,
+            }
+            for line in &section.lines {
+                w.line(line)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes raw lines verbatim and makes sure generated lines always start on a
+/// fresh line, even after a final raw line that had no line ending.
+struct LineWriter<'a, 'b> {
+    f: &'a mut fmt::Formatter<'b>,
+    newline: &'static str,
+    at_line_start: bool,
+}
+
+impl LineWriter<'_, '_> {
+    fn line(&mut self, line: &Line) -> fmt::Result {
+        match line {
+            Line::Other(raw) => self.raw(raw),
+            Line::Entry(Entry { raw: Some(raw), .. }) => self.raw(raw),
+            Line::Entry(e) => self.generated(&format!("{}={}", e.key, e.value)),
+        }
+    }
+
+    fn raw(&mut self, text: &str) -> fmt::Result {
+        if text.is_empty() {
+            return Ok(());
+        }
+        if !self.at_line_start {
+            self.f.write_str(self.newline)?;
+        }
+        self.f.write_str(text)?;
+        self.at_line_start = text.ends_with('\n');
+        Ok(())
+    }
+
+    fn generated(&mut self, text: &str) -> fmt::Result {
+        if !self.at_line_start {
+            self.f.write_str(self.newline)?;
+        }
+        self.f.write_str(text)?;
+        self.f.write_str(self.newline)?;
+        self.at_line_start = true;
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_maps_round_trip_byte_for_byte() {
+        for path in ["src/samplemap/sample.map", "src/samplemap/sample_edge_higher.map"] {
+            let bytes = fs::read(path).unwrap();
+            assert_eq!(MapDocument::from_bytes(&bytes).to_bytes(), bytes, "{path}");
+        }
+    }
+
+    #[test]
+    fn duplicate_sections_are_read_and_written_through_the_last_one() {
+        let mut doc = MapDocument::parse("[Basic]\nName=First\nPercent=0\n[Basic]\nName=Second\n");
+        assert_eq!(doc.section("Basic").and_then(|s| s.get("Name")), doc.get("Basic", "Name"));
+        assert_eq!(doc.get("Basic", "Percent"), Some("0"));
+
+        doc.ensure_section("Basic").set("Name", "Third");
+        doc.ensure_section("Basic").set("Percent", "50");
+        assert_eq!(doc.get("Basic", "Name"), Some("Third"));
+        assert_eq!(doc.get("Basic", "Percent"), Some("50"));
+        assert_eq!(doc.to_string(), "[Basic]\nName=First\nPercent=0\n[Basic]\nName=Third\nPercent=50\n");
+    }
+
+    #[test]
+    fn removing_a_key_reaches_every_duplicate_section() {
+        let mut doc = MapDocument::parse("[HTNK]\nCost=900\nStrength=500\n[Map]\nSize=1\n[HTNK]\nCost=1000\n");
+        assert!(doc.remove_key("HTNK", "Cost"));
+        assert_eq!(doc.get("HTNK", "Cost"), None);
+        assert_eq!(doc.to_string(), "[HTNK]\nStrength=500\n[Map]\nSize=1\n");
+        assert!(!doc.remove_key("HTNK", "Cost"));
+    }
+}
@@ -1,5 +1,6 @@
 use anyhow::{anyhow, Result};
-use std::fs;
+
+use super::map_document::MapDocument;
 
 /// Minimal parse for RA2/YR `.map` headers we care about:
 /// - [Map] Theater=Temperate|Snow|Urban|NewUrban|Desert|Lunar (any case)
@@ -8,7 +9,8 @@ use std::fs;
 ///     * "X,Y,W,H"  ← common in RA2/YR (x,y offset + size in tiles)
 /// - Fallback: [Header] Width / Height (if [Map] Size missing)
 ///
-/// We are NOT decoding IsoMapPack5 yet; this is only enough to paint a theater grid.
+/// The values are read from a [`MapDocument`]; other sections stay available there.
+/// Terrain itself (`[IsoMapPack5]`) is decoded separately by `iso_map::IsoMapGrid`.
 #[derive(Debug, Clone)]
 pub struct MapHeader {
     pub theater: Theater,
@@ -28,6 +30,10 @@ pub enum Theater {
 }
 
 impl Theater {
+    /// Every real theater, in `[Map] Theater=` order of introduction.
+    pub const ALL: [Theater; 6] =
+        [Theater::Temperate, Theater::Snow, Theater::Urban, Theater::NewUrban, Theater::Desert, Theater::Lunar];
+
     pub fn from_str(s: &str) -> Self {
         match s.trim().to_ascii_lowercase().as_str() {
             "temperate" => Theater::Temperate,
@@ -39,107 +45,98 @@ impl Theater {
             _ => Theater::Unknown,
         }
     }
-}
 
-pub fn parse_map_header(path: &str) -> Result<MapHeader> {
-    let text = fs::read_to_string(path)?;
-
-    // State
-    let mut in_map = false;
-    let mut in_header = false;
-
-    // Parsed values
-    let mut theater: Option<Theater> = None;
-    let mut map_size_wh: Option<(i32, i32)> = None;   // from [Map] Size
-    let mut header_wh: Option<(i32, i32)> = None;     // from [Header] Width/Height
+    /// Three-letter code used in theater file names: `isotem.pal`, `tree01.tem`.
+    pub fn suffix(self) -> Option<&'static str> {
+        match self {
+            Theater::Temperate => Some("tem"),
+            Theater::Snow => Some("sno"),
+            Theater::Urban => Some("urb"),
+            Theater::NewUrban => Some("ubn"),
+            Theater::Desert => Some("des"),
+            Theater::Lunar => Some("lun"),
+            Theater::Unknown => None,
+        }
+    }
 
-    for raw in text.lines() {
-        let line = raw.trim();
-        if line.is_empty() || line.starts_with(';') { continue; }
+    /// Letter swapped into the second character of `NewTheater=yes` building
+    /// images (`GAPOWR` becomes `GNPOWR` in New Urban).
+    pub fn letter(self) -> Option<char> {
+        self.suffix().map(|s| match self {
+            Theater::Snow => 'A',
+            Theater::NewUrban => 'N',
+            _ => s.as_bytes()[0].to_ascii_uppercase() as char,
+        })
+    }
 
-        // Section switches
-        if line.starts_with('[') && line.ends_with(']') {
-            let section = &line[1..line.len()-1];
-            in_map = section.eq_ignore_ascii_case("Map");
-            in_header = section.eq_ignore_ascii_case("Header");
-            continue;
+    /// Name shared by the theater's control INI and palette (`temperat.ini`,
+    /// `temperat.pal`).
+    pub fn file_stem(self) -> Option<&'static str> {
+        match self {
+            Theater::Temperate => Some("temperat"),
+            Theater::Snow => Some("snow"),
+            Theater::Urban => Some("urban"),
+            Theater::NewUrban => Some("urbann"),
+            Theater::Desert => Some("desert"),
+            Theater::Lunar => Some("lunar"),
+            Theater::Unknown => None,
         }
+    }
 
-        // Key = Value
-        if let Some((k, v)) = line.split_once('=') {
-            let key = k.trim();
-            let val = v.trim();
-
-            if in_map {
-                match key.to_ascii_lowercase().as_str() {
-                    "theater" => theater = Some(Theater::from_str(val)),
-                    "size" => {
-                        // Accept "W,H" or "X,Y,W,H". Use the LAST two numbers as width/height.
-                        let nums: Vec<_> = val
-                            .split(',')
-                            .map(|s| s.trim().parse::<i32>())
-                            .collect();
-
-                        // All must parse; otherwise ignore this key gracefully.
-                        if nums.iter().all(|r| r.is_ok()) {
-                            let parsed: Vec<i32> = nums.into_iter().map(|r| r.unwrap()).collect();
-                            match parsed.len() {
-                                2 => map_size_wh = Some((parsed[0], parsed[1])),
-                                4 => map_size_wh = Some((parsed[2], parsed[3])),
-                                _ => { /* ignore weird sizes */ }
-                            }
-                        }
-                    }
-                    _ => {}
-                }
-            } else if in_header {
-                match key.to_ascii_lowercase().as_str() {
-                    "width" => {
-                        // Capture width; height may come later (or earlier)
-                        let w = val.parse::<i32>().ok();
-                        if let Some(w) = w {
-                            header_wh = Some((w, header_wh.map(|(_,h)| h).unwrap_or_default()));
-                        }
-                    }
-                    "height" => {
-                        let h = val.parse::<i32>().ok();
-                        if let Some(h) = h {
-                            header_wh = Some((header_wh.map(|(w,_)| w).unwrap_or_default(), h));
-                        }
-                    }
-                    _ => {}
-                }
-            }
-        }
+    /// Palette for terrain objects, overlays and smudges.
+    pub fn palette(self) -> Option<String> {
+        self.file_stem().map(|s| format!("{s}.pal"))
     }
 
-    // Normalize header_wh if one of width/height was missing when set in two steps
-    if let Some((w, h)) = header_wh {
-        if w == 0 || h == 0 {
-            // leave it; final validation happens below with fallbacks
-        }
+    /// Palette for the isometric tiles (`isotem.pal`).
+    pub fn iso_palette(self) -> Option<String> {
+        self.suffix().map(|s| format!("iso{s}.pal"))
     }
+}
+
+impl MapHeader {
+    /// Derive the header from an already-parsed document (no file access).
+    pub fn from_document(doc: &MapDocument) -> Result<Self> {
+        let theater = doc.get("Map", "Theater").map(Theater::from_str);
+
+        // Accept "W,H" or "X,Y,W,H". Use the LAST two numbers as width/height.
+        // All must parse; otherwise ignore this key gracefully.
+        let map_size_wh = doc.get("Map", "Size").and_then(|val| {
+            let parsed: Vec<i32> = val
+                .split(',')
+                .map(|s| s.trim().parse::<i32>())
+                .collect::<Result<_, _>>()
+                .ok()?;
+            match parsed.len() {
+                2 => Some((parsed[0], parsed[1])),
+                4 => Some((parsed[2], parsed[3])),
+                _ => None, // ignore weird sizes
+            }
+        });
+
+        // [Header] Width/Height; a missing half defaults to 0 and is rejected below.
+        let header_w = doc.get("Header", "Width").and_then(|v| v.parse::<i32>().ok());
+        let header_h = doc.get("Header", "Height").and_then(|v| v.parse::<i32>().ok());
+        let header_wh = match (header_w, header_h) {
+            (None, None) => None,
+            (w, h) => Some((w.unwrap_or_default(), h.unwrap_or_default())),
+        };
 
-    // Prefer [Map] Size; fallback to [Header] Width/Height; else defaults.
-    let (mut width, mut height) = if let Some((w, h)) = map_size_wh {
-        (w, h)
-    } else if let Some((w, h)) = header_wh {
-        (w, h)
-    } else {
-        (64, 64)
-    };
-
-    // Final sanity: width/height must be > 0. If not, try other fallbacks before failing.
-    if width <= 0 || height <= 0 {
-        if let Some((w, h)) = header_wh {
+        // Prefer [Map] Size; fallback to [Header] Width/Height; else defaults.
+        let (mut width, mut height) = map_size_wh.or(header_wh).unwrap_or((64, 64));
+
+        // Final sanity: width/height must be > 0. If not, try other fallbacks before failing.
+        if (width <= 0 || height <= 0)
+            && let Some((w, h)) = header_wh
+        {
             width = w;
             height = h;
         }
-    }
-    if width <= 0 || height <= 0 {
-        return Err(anyhow!("Invalid size (width/height <= 0) in {}", path));
-    }
+        if width <= 0 || height <= 0 {
+            return Err(anyhow!("Invalid size (width/height <= 0)"));
+        }
 
-    let theater = theater.unwrap_or(Theater::Unknown);
-    Ok(MapHeader { theater, width, height })
+        let theater = theater.unwrap_or(Theater::Unknown);
+        Ok(MapHeader { theater, width, height })
+    }
 }
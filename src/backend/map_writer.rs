@@ -0,0 +1,113 @@
+use anyhow::Result;
+use std::path::Path;
+
+use super::codec::{encode_base64_section, encode_format5, encode_format80};
+use super::editor_objects::{EditorObjects, Tool};
+use super::iso_map::IsoMapGrid;
+use super::map_document::MapDocument;
+use super::overlay::{Overlay, OverlayLayer};
+use super::thumbnail::MapThumbnail;
+
+/// Bytes per `[IsoMapPack5]` record; see `iso_map`.
+const ISO_RECORD_LEN: usize = 11;
+
+/// Rebuild the ore/gem part of the overlay layer from the editor's Resource
+/// placements (walls, bridges and other overlays are left alone), then add
+/// overlays placed from the palette.
+pub fn apply_placements(overlay: &mut OverlayLayer, objs: &EditorObjects) {
+    let stale: Vec<(i32, i32)> = overlay
+        .iter()
+        .filter(|(_, _, o)| o.resource().is_some())
+        .map(|(x, y, _)| (x, y))
+        .collect();
+    for (x, y) in stale {
+        overlay.set(x, y, None);
+    }
+    for p in objs.items.iter() {
+        if let Tool::Resource(kind) = p.kind {
+            overlay.set(p.x, p.y, Some(p.overlay.unwrap_or(kind.overlay())));
+        }
+    }
+    for p in objs.items.iter() {
+        if let Tool::Overlay(id) = p.kind {
+            overlay.set(p.x, p.y, Some(p.overlay.unwrap_or(Overlay { id, data: 0 })));
+        }
+    }
+}
+
+/// Re-encode every binary pack into `doc`: `[IsoMapPack5]` (LZO), `[OverlayPack]`
+/// and `[OverlayDataPack]` (LCW) and `[PreviewPack]` (LZO). Existing sections are
+/// rewritten in place so the file keeps FinalAlert's section order.
+///
+/// `None` for `iso` or `overlay` means the map's pack didn't decode: its sections
+/// are left as they are rather than overwritten with a placeholder.
+pub fn write_packs(doc: &mut MapDocument, iso: Option<&IsoMapGrid>, overlay: Option<&OverlayLayer>) {
+    if let Some(iso) = iso {
+        // Row-major (y, then x) over the map rectangle, like FinalAlert.
+        let mut cells = Vec::with_capacity(iso.cells().count() * ISO_RECORD_LEN);
+        for c in iso.cells() {
+            cells.extend_from_slice(&(c.x as u16).to_le_bytes());
+            cells.extend_from_slice(&(c.y as u16).to_le_bytes());
+            cells.extend_from_slice(&c.tile.to_le_bytes());
+            cells.extend_from_slice(&[c.sub_tile, c.height, c.flags]);
+        }
+        encode_base64_section(doc.ensure_section("IsoMapPack5"), &encode_format5(&cells));
+    }
+
+    if let Some(overlay) = overlay {
+        encode_base64_section(doc.ensure_section("OverlayPack"), &encode_format80(overlay.ids()));
+        encode_base64_section(doc.ensure_section("OverlayDataPack"), &encode_format80(overlay.data()));
+    }
+
+    // The thumbnail is carried over as-is (we don't render new previews yet).
+    match MapThumbnail::from_document(doc) {
+        Ok(Some(t)) => encode_base64_section(doc.ensure_section("PreviewPack"), &encode_format5(&t.rgb)),
+        Ok(None) => {}
+        Err(e) => eprintln!("[backend] Keeping undecodable [PreviewPack] as-is: {e}"),
+    }
+}
+
+/// Write the open map to `path` with freshly encoded packs. `[Digest]` is kept
+/// as opened; see `digest`.
+pub fn save_map(
+    path: &Path,
+    doc: &mut MapDocument,
+    iso: Option<&IsoMapGrid>,
+    overlay: Option<&OverlayLayer>,
+) -> Result<()> {
+    write_packs(doc, iso, overlay);
+    doc.save(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::editor_objects::Placement;
+    use crate::backend::overlay::ResourceKind;
+
+    #[test]
+    fn placed_gems_save_as_gems() {
+        let mut objs = EditorObjects::default();
+        for (x, kind) in [(10, ResourceKind::Ore), (11, ResourceKind::Gem)] {
+            objs.items.push(Placement { kind: Tool::Resource(kind), x, y: 20, overlay: Some(kind.overlay()) });
+        }
+        let mut overlay = OverlayLayer::default();
+        apply_placements(&mut overlay, &objs);
+        assert_eq!(overlay.get(10, 20), Some(Overlay::ORE));
+        assert_eq!(overlay.get(11, 20), Some(Overlay::GEM));
+        assert_eq!(overlay.get(11, 20).and_then(|o| o.resource()), Some(ResourceKind::Gem));
+    }
+
+    #[test]
+    fn packs_that_did_not_decode_are_left_alone() {
+        let mut doc = MapDocument::load("src/samplemap/sample.map").unwrap();
+        let before = doc.clone();
+        write_packs(&mut doc, None, None);
+        let lines = |d: &MapDocument, name: &str| -> Vec<String> {
+            d.section(name).unwrap().entries().map(|e| format!("{}={}", e.key(), e.value())).collect()
+        };
+        for name in ["IsoMapPack5", "OverlayPack", "OverlayDataPack"] {
+            assert_eq!(lines(&doc, name), lines(&before, name), "{name}");
+        }
+    }
+}
@@ -0,0 +1,384 @@
+//! Westwood `.mix` archives and the virtual filesystem built from them.
+//!
+//! Layout (RA2/YR):
+//!
+//! ```text
+//! u32 flags                      only in newer files (low 16 bits are 0)
+//! [80 bytes key source]          if flags & ENCRYPTED
+//! u16 count | u32 body_size | count × (u32 id, u32 offset, u32 size)
+//! body                           offsets are relative to its start
+//! ```
+//!
+//! An encrypted index is Blowfish (little-endian words) in 8-byte blocks. Its
+//! 56-byte key is RSA-decoded from the key source with Westwood's public key.
+//! Files are found by the CRC32 of their upper-cased name ([`mix_id`]); the
+//! names themselves are not stored, so listings rely on known names and on
+//! XCC's `local mix database.dat` when an archive carries one.
+
+use anyhow::{anyhow, bail, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use bevy::prelude::*;
+use blowfish::cipher::{generic_array::GenericArray, BlockDecrypt, KeyInit};
+use blowfish::BlowfishLE;
+use num_bigint::BigUint;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use super::loader::find_file;
+use super::project::{Node, NodeKind};
+
+const FLAG_ENCRYPTED: u32 = 0x0002_0000;
+/// Westwood's public key: a DER integer holding the 320-bit modulus.
+const PUBLIC_KEY: &str = "AihRvNoIbTn85FZRYNZRcT+i6KpU+maCsEqr3Q5q+LDB5tH7Tz2qQ38V";
+const PUBLIC_EXPONENT: u32 = 0x10001;
+const LOCAL_DATABASE: &str = "local mix database.dat";
+/// Separates the archive path from the names inside it in virtual paths:
+/// `C:/RA2/ra2.mix::local.mix::rules.ini`.
+pub const VFS_SEP: &str = "::";
+
+/// Names that stock RA2/YR archives contain, used to label listings. Lookups
+/// by name work for any file; this only decides what a listing can show.
+const KNOWN_NAMES: &[&str] = &[
+    "local.mix", "localmd.mix", "cache.mix", "cachemd.mix", "conquer.mix", "conqmd.mix", "generic.mix",
+    "genermd.mix", "isogen.mix", "isogenmd.mix", "temperat.mix", "temperatmd.mix", "isotemp.mix", "isotemmd.mix",
+    "snow.mix", "snowmd.mix", "isosnow.mix", "isosnomd.mix", "urban.mix", "urbanmd.mix", "isourb.mix",
+    "isourbmd.mix", "desert.mix", "desertmd.mix", "isodes.mix", "isodesmd.mix", "lunar.mix", "lunarmd.mix",
+    "isolun.mix", "isolunmd.mix", "ubnmd.mix", "isoubnmd.mix", "neutral.mix", "sidec01.mix", "sidec02.mix",
+    "sidec02md.mix", "sidenc01.mix", "sidenc02.mix", "cameo.mix", "cameomd.mix", "audio.mix", "audiomd.mix",
+    "maps01.mix", "maps02.mix", "mapsmd03.mix", "movmd03.mix", "multi.mix", "multimd.mix", "thememd.mix",
+    "rules.ini", "rulesmd.ini", "art.ini", "artmd.ini", "ai.ini", "aimd.ini", "sound.ini", "soundmd.ini",
+    "eva.ini", "evamd.ini", "theme.ini", "thememd.ini", "battle.ini", "battlemd.ini", "mpmodes.ini",
+    "mpmodesmd.ini", "temperat.ini", "temperatmd.ini", "snow.ini", "snowmd.ini", "urban.ini", "urbanmd.ini",
+    "desert.ini", "desertmd.ini", "lunar.ini", "lunarmd.ini", "urbann.ini", "urbannmd.ini", "ra2.csf",
+    "ra2md.csf", "unittem.pal", "unitsno.pal", "uniturb.pal", "unitdes.pal", "unitlun.pal", "unitubn.pal",
+    "isotem.pal", "isosno.pal", "isourb.pal", "isodes.pal", "isolun.pal", "isoubn.pal", "temperat.pal",
+    "snow.pal", "urban.pal", "desert.pal", "lunar.pal", "urbann.pal", "anim.pal", "cameo.pal", "mousepal.pal",
+    "palette.pal", "mouse.sha", "keyboard.ini", "keyboardmd.ini",
+];
+
+/// RA2's file ID: CRC32 of the upper-cased name after Westwood's padding
+/// (names not a multiple of 4 long get their remainder length, then copies of
+/// the first byte of the last partial group).
+pub fn mix_id(name: &str) -> u32 {
+    let mut bytes = name.to_ascii_uppercase().into_bytes();
+    let len = bytes.len();
+    let whole = len & !3;
+    if len & 3 != 0 {
+        bytes.push((len - whole) as u8);
+        let fill = bytes[whole];
+        bytes.extend(std::iter::repeat_n(fill, 3 - (len & 3)));
+    }
+    crc32(&bytes)
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &b in bytes {
+        crc ^= b as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+/// The 56-byte Blowfish key: each 40-byte block of the key source is an RSA
+/// ciphertext (little-endian) that decodes to 39 key bytes.
+fn blowfish_key(source: &[u8; 80]) -> Result<[u8; 56]> {
+    let der = STANDARD.decode(PUBLIC_KEY)?;
+    let modulus = BigUint::from_bytes_be(&der[2..]);
+    let exponent = BigUint::from(PUBLIC_EXPONENT);
+    let mut out = Vec::with_capacity(78);
+    for block in source.chunks(40) {
+        let mut plain = BigUint::from_bytes_le(block).modpow(&exponent, &modulus).to_bytes_le();
+        plain.resize(39, 0);
+        out.extend(plain);
+    }
+    let mut key = [0u8; 56];
+    key.copy_from_slice(&out[..56]);
+    Ok(key)
+}
+
+fn decrypt(cipher: &BlowfishLE, bytes: &mut [u8]) {
+    for block in bytes.chunks_exact_mut(8) {
+        cipher.decrypt_block(GenericArray::from_mut_slice(block));
+    }
+}
+
+fn u32_at(b: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([b[at], b[at + 1], b[at + 2], b[at + 3]])
+}
+
+/// One file in an archive; `offset` is absolute within the file on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MixEntry {
+    pub id: u32,
+    pub offset: u64,
+    pub size: u32,
+}
+
+/// An opened archive: its index, read from `path` (or from inside another
+/// archive in that file, starting at `offset`).
+#[derive(Debug, Clone)]
+pub struct MixArchive {
+    pub path: PathBuf,
+    /// Virtual path of the archive itself (`ra2.mix::local.mix`).
+    pub vpath: String,
+    pub offset: u64,
+    pub entries: Vec<MixEntry>,
+    /// Names known for some of the IDs.
+    pub names: HashMap<u32, String>,
+}
+
+impl MixArchive {
+    pub fn open(path: &Path) -> Result<Self> {
+        let size = fs::metadata(path)?.len();
+        Self::open_at(path, path.to_string_lossy().to_string(), 0, size)
+    }
+
+    /// A MIX stored as `entry` of this archive.
+    pub fn open_nested(&self, entry: &MixEntry) -> Result<Self> {
+        let vpath = format!("{}{VFS_SEP}{}", self.vpath, self.display_name(entry.id));
+        Self::open_at(&self.path, vpath, entry.offset, entry.size as u64)
+    }
+
+    fn open_at(path: &Path, vpath: String, offset: u64, size: u64) -> Result<Self> {
+        let mut f = File::open(path)?;
+        f.seek(SeekFrom::Start(offset))?;
+        let mut word = [0u8; 4];
+        f.read_exact(&mut word)?;
+        let flags = u32::from_le_bytes(word);
+        let has_flags = flags & 0xFFFF == 0;
+        let encrypted = has_flags && flags & FLAG_ENCRYPTED != 0;
+
+        let (index, body) = if encrypted {
+            let mut source = [0u8; 80];
+            f.read_exact(&mut source)?;
+            let cipher = BlowfishLE::new_from_slice(&blowfish_key(&source)?)
+                .map_err(|_| anyhow!("bad Blowfish key length"))?;
+            let mut first = [0u8; 8];
+            f.read_exact(&mut first)?;
+            decrypt(&cipher, &mut first);
+            let count = u16::from_le_bytes([first[0], first[1]]) as usize;
+            let padded = (6 + count * 12).div_ceil(8) * 8;
+            let mut header = first.to_vec();
+            header.resize(padded, 0);
+            f.read_exact(&mut header[8..])?;
+            decrypt(&cipher, &mut header[8..]);
+            (header[6..6 + count * 12].to_vec(), 4 + 80 + padded as u64)
+        } else {
+            let start = if has_flags { 4 } else { 0 };
+            f.seek(SeekFrom::Start(offset + start))?;
+            let mut head = [0u8; 6];
+            f.read_exact(&mut head)?;
+            let count = u16::from_le_bytes([head[0], head[1]]) as usize;
+            let mut index = vec![0u8; count * 12];
+            f.read_exact(&mut index)?;
+            (index, start + 6 + count as u64 * 12)
+        };
+
+        let mut entries = Vec::with_capacity(index.len() / 12);
+        for e in index.chunks_exact(12) {
+            let entry = MixEntry { id: u32_at(e, 0), offset: offset + body + u32_at(e, 4) as u64, size: u32_at(e, 8) };
+            if entry.offset + entry.size as u64 > offset + size {
+                bail!("{vpath}: not a MIX file (entry {:08X} runs past the end)", entry.id);
+            }
+            entries.push(entry);
+        }
+
+        let mut mix = Self { path: path.to_path_buf(), vpath, offset, entries, names: HashMap::new() };
+        let database = mix.read_file(LOCAL_DATABASE).and_then(Result::ok).map(|b| local_database_names(&b));
+        let names = KNOWN_NAMES.iter().chain([&LOCAL_DATABASE]).map(|s| s.to_string());
+        for name in names.chain(database.into_iter().flatten()) {
+            let id = mix_id(&name);
+            if mix.entry(id).is_some() {
+                mix.names.entry(id).or_insert(name);
+            }
+        }
+        Ok(mix)
+    }
+
+    pub fn entry(&self, id: u32) -> Option<&MixEntry> {
+        self.entries.iter().find(|e| e.id == id)
+    }
+
+    /// Entry for a name, or for the `[1A2B3C4D]` placeholder listings use for
+    /// unnamed IDs.
+    pub fn find(&self, name: &str) -> Option<&MixEntry> {
+        let placeholder = name.strip_prefix('[').and_then(|s| s.strip_suffix(']'));
+        match placeholder.and_then(|hex| u32::from_str_radix(hex, 16).ok()) {
+            Some(id) => self.entry(id),
+            None => self.entry(mix_id(name)),
+        }
+    }
+
+    pub fn display_name(&self, id: u32) -> String {
+        self.names.get(&id).cloned().unwrap_or_else(|| format!("[{id:08X}]"))
+    }
+
+    pub fn read(&self, entry: &MixEntry) -> Result<Vec<u8>> {
+        let mut f = File::open(&self.path)?;
+        f.seek(SeekFrom::Start(entry.offset))?;
+        let mut bytes = vec![0u8; entry.size as usize];
+        f.read_exact(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Contents of `name`, if the archive has it.
+    pub fn read_file(&self, name: &str) -> Option<Result<Vec<u8>>> {
+        self.find(name).map(|e| self.read(e))
+    }
+
+    /// Entries named like archives, opened. Ones that fail to parse are logged
+    /// and left out.
+    pub fn nested(&self) -> Vec<MixArchive> {
+        self.entries
+            .iter()
+            .filter(|e| self.names.get(&e.id).is_some_and(|n| n.to_ascii_lowercase().ends_with(".mix")))
+            .filter_map(|e| {
+                self.open_nested(e).map_err(|err| eprintln!("[backend] Skipping nested MIX: {err}")).ok()
+            })
+            .collect()
+    }
+}
+
+/// Names listed in XCC's `local mix database.dat`: a 44-byte header (32-byte
+/// signature, u32 size, type, version), u32 game, u32 count, then
+/// NUL-terminated names.
+fn local_database_names(bytes: &[u8]) -> Vec<String> {
+    let Some(body) = bytes.get(52..) else {
+        return Vec::new();
+    };
+    let count = u32_at(bytes, 48) as usize;
+    body.split(|&b| b == 0)
+        .take(count)
+        .filter(|n| !n.is_empty())
+        .map(|n| String::from_utf8_lossy(n).to_string())
+        .collect()
+}
+
+/// Whether `path` points inside an archive rather than at a file on disk.
+pub fn is_virtual_path(path: &str) -> bool {
+    path.contains(VFS_SEP)
+}
+
+/// Explorer folder for an archive at `depth`: named entries first, then the
+/// unnamed IDs, with nested archives as subfolders. Archives count against the
+/// tree's `max_nodes` like folders do; past `max_depth` or the budget they are
+/// listed without their contents (and nested ones are not opened).
+pub fn mix_node(
+    mix: &MixArchive,
+    name: String,
+    depth: usize,
+    max_depth: usize,
+    max_nodes: usize,
+    count: &mut usize,
+) -> Node {
+    *count += 1;
+    if depth >= max_depth || *count > max_nodes {
+        return Node { id: mix.vpath.clone(), name, kind: NodeKind::Folder { children: Vec::new() } };
+    }
+    let nested = mix.nested();
+    let mut entries: Vec<(String, &MixEntry)> = mix.entries.iter().map(|e| (mix.display_name(e.id), e)).collect();
+    entries.sort_by_key(|(n, _)| (n.starts_with('['), n.to_ascii_lowercase()));
+
+    let children = entries
+        .into_iter()
+        .map(|(name, e)| match nested.iter().find(|m| m.offset == e.offset) {
+            Some(child) => mix_node(child, name, depth + 1, max_depth, max_nodes, count),
+            None => {
+                let path = format!("{}{VFS_SEP}{name}", mix.vpath);
+                let ext = name.rsplit_once('.').map(|(_, e)| e.to_string()).unwrap_or_default();
+                Node { id: path.clone(), name, kind: NodeKind::File { path, ext } }
+            }
+        })
+        .collect();
+    Node { id: mix.vpath.clone(), name, kind: NodeKind::Folder { children } }
+}
+
+/// Game files as the game sees them: loose files in the game directory first,
+/// then every archive in load order.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct Vfs {
+    pub dir: Option<PathBuf>,
+    /// Top-level archives, then the ones nested in them, level by level.
+    pub archives: Vec<MixArchive>,
+}
+
+/// Load order within one level: `expandmd##` (highest number first), then
+/// Yuri's Revenge (`*md.mix`) before RA2 archives, then by name.
+fn load_rank(name: &str) -> (u8, u32, String) {
+    let lower = name.to_ascii_lowercase();
+    let stem = lower.trim_end_matches(".mix");
+    let expand = stem.strip_prefix("expandmd").or_else(|| stem.strip_prefix("expand"));
+    match expand.and_then(|n| n.parse::<u32>().ok()) {
+        Some(n) => (0, u32::MAX - n, lower),
+        None if stem.ends_with("md") || stem.starts_with("ra2md") => (1, 0, lower),
+        None => (2, 0, lower),
+    }
+}
+
+impl Vfs {
+    /// Open every `.mix` directly in `dir` and the archives nested in them.
+    /// Unreadable archives are logged and skipped.
+    pub fn mount(dir: &Path) -> Result<Self> {
+        let mut level: Vec<MixArchive> = fs::read_dir(dir)?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.is_file() && p.extension().is_some_and(|x| x.eq_ignore_ascii_case("mix")))
+            .filter_map(|p| MixArchive::open(&p).map_err(|e| eprintln!("[backend] {}: {e}", p.display())).ok())
+            .collect();
+
+        let mut archives = Vec::new();
+        while !level.is_empty() {
+            level.sort_by_cached_key(|m| load_rank(m.vpath.rsplit(['/', '\\', ':']).next().unwrap_or_default()));
+            let next = level.iter().flat_map(MixArchive::nested).collect();
+            archives.append(&mut level);
+            level = next;
+        }
+        Ok(Self { dir: Some(dir.to_path_buf()), archives })
+    }
+
+    /// Whether the game files have `name`, without reading it.
+    pub fn contains(&self, name: &str) -> bool {
+        self.archives.iter().any(|mix| mix.find(name).is_some())
+            || self.dir.as_deref().is_some_and(|d| find_file(d, name).is_some())
+    }
+
+    /// Contents of `name` and where they came from (disk path or virtual path).
+    pub fn read(&self, name: &str) -> Option<Result<(PathBuf, Vec<u8>)>> {
+        if let Some(path) = self.dir.as_deref().and_then(|d| find_file(d, name)) {
+            return Some(fs::read(&path).map(|b| (path, b)).map_err(Into::into));
+        }
+        self.archives.iter().find_map(|mix| {
+            let entry = mix.find(name)?;
+            let source = PathBuf::from(format!("{}{VFS_SEP}{name}", mix.vpath));
+            Some(mix.read(entry).map(|b| (source, b)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mix_id_matches_known_ids() {
+        assert_eq!(mix_id(LOCAL_DATABASE), 0x366E_051F);
+        assert_eq!(mix_id("LOCAL MIX DATABASE.DAT"), mix_id("local mix database.dat"));
+    }
+
+    #[test]
+    fn reads_local_database_names() {
+        let mut bytes = b"XCC by Olaf van der Spek\x1a\x04\x17\x27\x10\x19\x80\x00".to_vec();
+        // size, type, version, game, count
+        for n in [0u32, 0, 0, 5, 2] {
+            bytes.extend_from_slice(&n.to_le_bytes());
+        }
+        assert_eq!(bytes.len(), 52);
+        bytes.extend_from_slice(b"rules.ini\0art.ini\0ignored.ini\0");
+        assert_eq!(local_database_names(&bytes), ["rules.ini", "art.ini"]);
+        assert!(local_database_names(&bytes[..51]).is_empty());
+    }
+}
@@ -5,16 +5,72 @@ mod project;
 mod loader;
 mod systems;
 mod map_parser;
+mod map_document;
+mod codec;
+mod iso_map;
+mod overlay;
+mod thumbnail;
+mod map_writer;
+mod digest;
+mod waypoints;
+mod header_sync;
+mod houses;
+mod basic;
+mod special_flags;
+mod lighting;
+mod triggers;
+mod ai_scripts;
+mod ai_triggers;
+mod objects;
+mod rules_catalog;
+mod rules_overrides;
+mod terrain_objects;
+mod mix;
+mod palette;
+mod shp;
+mod tmp;
+mod tileset;
+mod vxl;
+mod hva;
+mod voxel_renderer;
+mod csf;
 mod editor_objects; // keep as a private module
 
-pub use events::{OpenFolder, OpenMap};
+pub use events::{LoadAiIni, OpenFolder, OpenMap, ResetRulesKey, SaveMap, SetGameDir, TriggerEdit};
 pub use project::{EditorLayout, Node, NodeKind, ProjectState};
+pub use map_document::MapDocument;
+pub use iso_map::{IsoCell, IsoMapGrid};
+pub use overlay::Overlay;
+pub use thumbnail::MapThumbnail;
+pub use digest::stored_digest;
+pub use waypoints::{next_free_waypoint, Waypoint, START_WAYPOINTS};
+pub use houses::{is_taken_section_name, House, EDGES};
+pub use basic::BasicProperties;
+pub use lighting::LightingMode;
+pub use triggers::{
+    action_name, event_name, letters_to_waypoint, named_action_count, named_event_count, waypoint_to_letters,
+    Action, Event, ReferenceKind, Trigger,
+};
+pub use ai_scripts::{is_known_type, AiScripts, ScriptType, TaskForce, TeamType};
+pub use ai_triggers::{is_multiplayer, AiTriggerCatalog};
+pub use objects::{MapObject, ObjectKind, SUB_CELLS};
+pub use terrain_objects::{Smudge, TerrainObject};
+pub use rules_catalog::{CatalogEntry, CatalogKind, ObjectCatalog};
+pub use rules_overrides::rules_overrides;
+pub use mix::{is_virtual_path, Vfs};
+pub use palette::Palette;
+pub use shp::Shp;
+pub use tileset::TileLibrary;
+pub use vxl::Vxl;
+pub use hva::Hva;
+pub use voxel_renderer::{render_voxels, snap_facing};
+pub use csf::StringTable;
 pub use systems::{MapPreview, MapView, WorkspaceSettings, theater_color};
 
 // Re-export editor objects (including palette API) so ui can `use crate::backend::{...}`
 pub use editor_objects::{
     Tool, ToolState, EditorObjects, Placement,
-    PaletteTab, PaletteState, palette_entries, // <-- added
+    PaletteTab, PaletteState, palette_entries, kind_color, placement_color, house_color,
 };
 
 pub struct BackendPlugin;
@@ -29,8 +85,31 @@ impl Plugin for BackendPlugin {
             .init_resource::<WorkspaceSettings>()
             .init_resource::<ToolState>()
             .init_resource::<EditorObjects>()
+            .init_resource::<AiTriggerCatalog>()
+            .init_resource::<ObjectCatalog>()
+            .init_resource::<Vfs>()
+            .init_resource::<TileLibrary>()
+            .init_resource::<StringTable>()
             .add_event::<events::OpenFolder>()
             .add_event::<events::OpenMap>()
-            .add_systems(Update, (systems::handle_open_folder, systems::handle_open_map));
+            .add_event::<events::SaveMap>()
+            .add_event::<events::TriggerEdit>()
+            .add_event::<events::LoadAiIni>()
+            .add_event::<events::SetGameDir>()
+            .add_event::<events::ResetRulesKey>()
+            .add_systems(
+                Update,
+                (
+                    systems::handle_open_folder,
+                    systems::handle_open_map,
+                    systems::handle_save_map,
+                    systems::sync_header,
+                    systems::handle_trigger_edit,
+                    systems::handle_load_ai_ini,
+                    systems::handle_set_game_dir,
+                    systems::sync_map_rules,
+                    systems::sync_tile_library,
+                ),
+            );
     }
 }
@@ -0,0 +1,224 @@
+use super::map_document::MapDocument;
+
+/// Which placement section an object lives in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ObjectKind {
+    Unit,
+    Infantry,
+    Structure,
+    Aircraft,
+}
+
+impl ObjectKind {
+    pub const ALL: [ObjectKind; 4] = [Self::Unit, Self::Infantry, Self::Structure, Self::Aircraft];
+
+    pub fn section(self) -> &'static str {
+        match self {
+            Self::Unit => "Units",
+            Self::Infantry => "Infantry",
+            Self::Structure => "Structures",
+            Self::Aircraft => "Aircraft",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Unit => "Vehicle",
+            Self::Infantry => "Infantry",
+            Self::Structure => "Structure",
+            Self::Aircraft => "Aircraft",
+        }
+    }
+
+    /// Fields after the typed ones, with FinalAlert's values for a new object.
+    fn default_extra(self) -> &'static [&'static str] {
+        match self {
+            // OnBridge, FollowsID, AutoNORecruitType, AutoYESRecruitType
+            Self::Unit => &["0", "-1", "0", "0"],
+            // OnBridge, AutoNORecruitType, AutoYESRecruitType
+            Self::Infantry => &["0", "0", "0"],
+            // AISellable, AIRebuildable, Powered, Upgrades, SpotLight,
+            // Upgrade1-3, AIRepairable, Nominal
+            Self::Structure => &["1", "0", "1", "0", "0", "None", "None", "None", "0", "0"],
+            // AutoNORecruitType, AutoYESRecruitType
+            Self::Aircraft => &["0", "0"],
+        }
+    }
+
+    /// Number of typed fields at the start of an entry.
+    fn typed_fields(self) -> usize {
+        match self {
+            Self::Unit | Self::Aircraft => 10,
+            Self::Infantry => 11,
+            Self::Structure => 7,
+        }
+    }
+
+    /// Orders a new object of this kind can start with.
+    pub fn missions(self) -> &'static [&'static str] {
+        match self {
+            Self::Unit | Self::Infantry => {
+                &["Guard", "Area Guard", "Sleep", "Hunt", "Harmless", "Sticky", "Attack", "Move", "Unload", "Ambush"]
+            }
+            Self::Aircraft => &["Guard", "Area Guard", "Sleep", "Hunt", "Attack", "Move"],
+            Self::Structure => &[],
+        }
+    }
+}
+
+/// Infantry share a cell in up to three spots; `0` is the cell center.
+pub const SUB_CELLS: [i32; 3] = [2, 3, 4];
+
+/// One entry of `[Units]`, `[Infantry]`, `[Structures]` or `[Aircraft]`.
+///
+/// Layouts (after `ID=`):
+/// - Units: `House,Type,Health,X,Y,Facing,Mission,Tag,Veteran,Group,...`
+/// - Infantry: `House,Type,Health,X,Y,SubCell,Mission,Facing,Tag,Veteran,Group,...`
+/// - Structures: `House,Type,Health,X,Y,Facing,Tag,...`
+/// - Aircraft: `House,Type,Health,X,Y,Facing,Mission,Tag,Veteran,Group,...`
+///
+/// Fields past the typed ones (bridge/recruit flags, structure upgrades, ...)
+/// are kept verbatim in `extra`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapObject {
+    pub kind: ObjectKind,
+    pub house: String,
+    pub type_id: String,
+    /// 0-256; 256 is full strength.
+    pub health: i32,
+    pub x: i32,
+    pub y: i32,
+    /// 0-255, clockwise from north.
+    pub facing: i32,
+    /// Unused by structures.
+    pub mission: String,
+    pub tag: Option<String>,
+    /// 0-200: 100 is veteran, 200 elite. Unused by structures.
+    pub veteran: i32,
+    /// Unused by structures.
+    pub group: i32,
+    /// Infantry only.
+    pub sub_cell: i32,
+    pub extra: Vec<String>,
+}
+
+impl MapObject {
+    /// A full-health object with FinalAlert's defaults.
+    pub fn new(kind: ObjectKind, house: &str, type_id: &str, x: i32, y: i32) -> Self {
+        Self {
+            kind,
+            house: house.to_string(),
+            type_id: type_id.to_string(),
+            health: 256,
+            x,
+            y,
+            facing: 64,
+            mission: kind.missions().first().copied().unwrap_or_default().to_string(),
+            tag: None,
+            veteran: 0,
+            group: -1,
+            sub_cell: if kind == ObjectKind::Infantry { SUB_CELLS[0] } else { 0 },
+            extra: kind.default_extra().iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    pub fn parse(kind: ObjectKind, value: &str) -> Option<Self> {
+        let f: Vec<&str> = value.split(',').map(str::trim).collect();
+        if f.len() < kind.typed_fields() {
+            return None;
+        }
+        let num = |i: usize| f[i].parse::<i32>().ok();
+        let tag = |i: usize| (!f[i].eq_ignore_ascii_case("None")).then(|| f[i].to_string());
+
+        let mut o = Self::new(kind, f[0], f[1], num(3)?, num(4)?);
+        o.health = num(2)?;
+        match kind {
+            ObjectKind::Unit | ObjectKind::Aircraft => {
+                o.facing = num(5)?;
+                o.mission = f[6].to_string();
+                o.tag = tag(7);
+                o.veteran = num(8)?;
+                o.group = num(9)?;
+            }
+            ObjectKind::Infantry => {
+                o.sub_cell = num(5)?;
+                o.mission = f[6].to_string();
+                o.facing = num(7)?;
+                o.tag = tag(8);
+                o.veteran = num(9)?;
+                o.group = num(10)?;
+            }
+            ObjectKind::Structure => {
+                o.facing = num(5)?;
+                o.mission.clear();
+                o.tag = tag(6);
+            }
+        }
+        o.extra = f[kind.typed_fields()..].iter().map(|s| s.to_string()).collect();
+        Some(o)
+    }
+
+    /// Entry value in the game's comma-separated layout.
+    pub fn to_value(&self) -> String {
+        let tag = self.tag.as_deref().unwrap_or("None");
+        let mut f: Vec<String> = vec![
+            self.house.clone(),
+            self.type_id.clone(),
+            self.health.to_string(),
+            self.x.to_string(),
+            self.y.to_string(),
+        ];
+        match self.kind {
+            ObjectKind::Unit | ObjectKind::Aircraft => f.extend([
+                self.facing.to_string(),
+                self.mission.clone(),
+                tag.to_string(),
+                self.veteran.to_string(),
+                self.group.to_string(),
+            ]),
+            ObjectKind::Infantry => f.extend([
+                self.sub_cell.to_string(),
+                self.mission.clone(),
+                self.facing.to_string(),
+                tag.to_string(),
+                self.veteran.to_string(),
+                self.group.to_string(),
+            ]),
+            ObjectKind::Structure => f.extend([self.facing.to_string(), tag.to_string()]),
+        }
+        f.extend(self.extra.iter().cloned());
+        f.join(",")
+    }
+}
+
+/// Read all four placement sections, in section then file order.
+/// Malformed entries are skipped with a log line.
+pub fn parse_objects(doc: &MapDocument) -> Vec<MapObject> {
+    let mut out = Vec::new();
+    for kind in ObjectKind::ALL {
+        let Some(section) = doc.section(kind.section()) else {
+            continue;
+        };
+        for e in section.entries() {
+            match MapObject::parse(kind, e.value()) {
+                Some(o) => out.push(o),
+                None => eprintln!("[backend] Ignoring bad [{}] entry {}={}", kind.section(), e.key(), e.value()),
+            }
+        }
+    }
+    out
+}
+
+/// Write each placement section as `0=`, `1=`, ... Sections whose entries would
+/// not change are left alone, and empty ones are only kept if the map had them.
+pub fn write_objects(doc: &mut MapDocument, objects: &[MapObject]) {
+    for kind in ObjectKind::ALL {
+        let entries: Vec<(String, String)> = objects
+            .iter()
+            .filter(|o| o.kind == kind)
+            .enumerate()
+            .map(|(i, o)| (i.to_string(), o.to_value()))
+            .collect();
+        doc.replace_entries_if_changed(kind.section(), &entries);
+    }
+}
@@ -0,0 +1,129 @@
+use anyhow::{anyhow, bail, Result};
+
+use super::codec::{decode_base64_section, decode_format80};
+use super::map_document::{MapDocument, Section};
+
+/// Overlay arrays cover a fixed 512x512 iso cell area.
+pub const OVERLAY_DIM: i32 = 512;
+const OVERLAY_LEN: usize = (OVERLAY_DIM * OVERLAY_DIM) as usize;
+/// `[OverlayPack]` value for "no overlay on this cell".
+pub const NO_OVERLAY: u8 = 0xFF;
+
+/// Overlay type ID ranges, as FinalAlert classifies them.
+const RIPARIUS: std::ops::RangeInclusive<u8> = 102..=121; // ore (TIB01-TIB20)
+const CRUENTUS: std::ops::RangeInclusive<u8> = 27..=38; // gems (GEM01-GEM12)
+const VINIFERA: std::ops::RangeInclusive<u8> = 127..=146; // ore
+const ABOREUS: std::ops::RangeInclusive<u8> = 147..=166; // gems
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceKind {
+    Ore,
+    Gem,
+}
+
+impl ResourceKind {
+    /// Overlay the editor places for this resource.
+    pub fn overlay(self) -> Overlay {
+        match self {
+            Self::Ore => Overlay::ORE,
+            Self::Gem => Overlay::GEM,
+        }
+    }
+}
+
+/// One cell's overlay: the `[OverlayTypes]` index plus its per-cell data byte
+/// (ore/gem density, wall connection frame, bridge part, ...).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overlay {
+    pub id: u8,
+    pub data: u8,
+}
+
+impl Overlay {
+    /// First ore overlay (TIB01), used for resources placed in the editor.
+    pub const ORE: Overlay = Overlay { id: 102, data: 11 };
+    /// First gem overlay (GEM01), used for gems placed in the editor.
+    pub const GEM: Overlay = Overlay { id: 27, data: 11 };
+
+    pub fn resource(&self) -> Option<ResourceKind> {
+        if RIPARIUS.contains(&self.id) || VINIFERA.contains(&self.id) {
+            Some(ResourceKind::Ore)
+        } else if CRUENTUS.contains(&self.id) || ABOREUS.contains(&self.id) {
+            Some(ResourceKind::Gem)
+        } else {
+            None
+        }
+    }
+}
+
+/// Decoded `[OverlayPack]` + `[OverlayDataPack]`, indexed by iso cell
+/// (`y * 512 + x`).
+#[derive(Debug, Clone)]
+pub struct OverlayLayer {
+    ids: Vec<u8>,
+    data: Vec<u8>,
+}
+
+impl Default for OverlayLayer {
+    fn default() -> Self {
+        Self { ids: vec![NO_OVERLAY; OVERLAY_LEN], data: vec![0; OVERLAY_LEN] }
+    }
+}
+
+impl OverlayLayer {
+    /// Decode both packs (base64 → format-80/LCW chunks → 262,144-byte arrays).
+    /// A map without overlay packs simply has no overlays.
+    pub fn from_document(doc: &MapDocument) -> Result<Self> {
+        let mut layer = Self::default();
+        if let Some(section) = doc.section("OverlayPack") {
+            layer.ids = decode_array(section, "OverlayPack")?;
+        }
+        if let Some(section) = doc.section("OverlayDataPack") {
+            layer.data = decode_array(section, "OverlayDataPack")?;
+        }
+        Ok(layer)
+    }
+
+    pub fn ids(&self) -> &[u8] {
+        &self.ids
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    fn index(x: i32, y: i32) -> Option<usize> {
+        ((0..OVERLAY_DIM).contains(&x) && (0..OVERLAY_DIM).contains(&y))
+            .then(|| (y * OVERLAY_DIM + x) as usize)
+    }
+
+    pub fn get(&self, x: i32, y: i32) -> Option<Overlay> {
+        let i = Self::index(x, y)?;
+        (self.ids[i] != NO_OVERLAY).then(|| Overlay { id: self.ids[i], data: self.data[i] })
+    }
+
+    pub fn set(&mut self, x: i32, y: i32, overlay: Option<Overlay>) {
+        if let Some(i) = Self::index(x, y) {
+            let o = overlay.unwrap_or(Overlay { id: NO_OVERLAY, data: 0 });
+            self.ids[i] = o.id;
+            self.data[i] = o.data;
+        }
+    }
+
+    /// Every occupied cell as `(x, y, overlay)`.
+    pub fn iter(&self) -> impl Iterator<Item = (i32, i32, Overlay)> + '_ {
+        self.ids.iter().enumerate().filter(|(_, id)| **id != NO_OVERLAY).map(|(i, &id)| {
+            let i = i as i32;
+            (i % OVERLAY_DIM, i / OVERLAY_DIM, Overlay { id, data: self.data[i as usize] })
+        })
+    }
+}
+
+fn decode_array(section: &Section, name: &str) -> Result<Vec<u8>> {
+    let bytes = decode_format80(&decode_base64_section(section)?)
+        .map_err(|e| anyhow!("[{name}]: {e}"))?;
+    if bytes.len() != OVERLAY_LEN {
+        bail!("[{name}] decoded to {} bytes, expected {OVERLAY_LEN}", bytes.len());
+    }
+    Ok(bytes)
+}
@@ -0,0 +1,46 @@
+use anyhow::{bail, Result};
+use std::ops::Range;
+
+/// Indices recolored to the owner's house color in unit palettes.
+pub const REMAP_RANGE: Range<usize> = 16..32;
+
+/// A 256-color `.pal`: 768 bytes of 6-bit VGA RGB. Index 0 is transparent
+/// when drawing sprites.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette {
+    pub colors: [[u8; 3]; 256],
+}
+
+impl Palette {
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < 768 {
+            bail!("palette is {} bytes, expected 768", bytes.len());
+        }
+        let mut colors = [[0u8; 3]; 256];
+        for (c, rgb) in colors.iter_mut().zip(bytes.chunks_exact(3)) {
+            // Scale 0-63 to 0-255, filling the low bits like the game does.
+            *c = [0, 1, 2].map(|i| (rgb[i] & 63) << 2 | (rgb[i] & 63) >> 4);
+        }
+        Ok(Self { colors })
+    }
+
+    /// Copy with the remap range shaded in `color`. The stock range is a ramp
+    /// of reds, so each entry's brightest channel sets the shade.
+    pub fn remapped(&self, color: [u8; 3]) -> Self {
+        let mut out = self.clone();
+        let top = REMAP_RANGE.map(|i| *self.colors[i].iter().max().unwrap_or(&0)).max().unwrap_or(0).max(1);
+        for i in REMAP_RANGE {
+            let k = *self.colors[i].iter().max().unwrap_or(&0) as u32;
+            out.colors[i] = color.map(|c| (c as u32 * k / top as u32) as u8);
+        }
+        out
+    }
+
+    pub fn rgba(&self, index: u8) -> [u8; 4] {
+        if index == 0 {
+            return [0; 4];
+        }
+        let [r, g, b] = self.colors[index as usize];
+        [r, g, b, 255]
+    }
+}
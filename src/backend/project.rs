@@ -8,12 +8,31 @@ pub struct EditorLayout {
     /// Set of folder IDs that are expanded. We use `Node.id` which is the full path
     /// (string) for stability across frames.
     pub open_folders: HashSet<String>,
+    /// Right-hand map info panel (thumbnail, name, size). Shown when a map opens.
+    pub show_map_info: bool,
+    /// Houses editor window (View → Houses).
+    pub show_houses: bool,
+    /// `[Basic]` properties dialog (Map → Properties…).
+    pub show_map_properties: bool,
+    /// `[SpecialFlags]` and `[Lighting]` editor windows (View menu).
+    pub show_special_flags: bool,
+    pub show_lighting: bool,
+    /// Trigger editor window (View → Triggers).
+    pub show_triggers: bool,
+    /// TeamTypes/ScriptTypes/TaskForces editor (View → Teams & Scripts).
+    pub show_ai_scripts: bool,
+    /// `[AITriggerTypesEnable]` manager (View → AI Trigger Enabling).
+    pub show_ai_triggers: bool,
+    /// Map-embedded rules.ini overrides viewer (View → Rules Overrides).
+    pub show_rules_overrides: bool,
 }
 
 #[derive(Resource, Default, Debug, Clone)]
 pub struct ProjectState {
     pub root: Option<Node>,
     pub root_path: Option<std::path::PathBuf>,
+    /// RA2/YR install the object catalog was loaded from (File → Set Game Directory…).
+    pub game_dir: Option<std::path::PathBuf>,
 }
 
 #[derive(Debug, Clone)]
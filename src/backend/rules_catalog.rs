@@ -0,0 +1,302 @@
+use anyhow::{anyhow, Result};
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use super::csf::StringTable;
+use super::map_document::{parse_bool, MapDocument, Section};
+use super::map_parser::Theater;
+use super::mix::Vfs;
+use super::objects::ObjectKind;
+use super::rules_overrides::merge_rules;
+
+/// Which rules list an entry comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CatalogKind {
+    Infantry,
+    Vehicle,
+    Building,
+    Aircraft,
+    Overlay,
+    Terrain,
+    Smudge,
+}
+
+impl CatalogKind {
+    pub const ALL: [CatalogKind; 7] = [
+        Self::Infantry,
+        Self::Vehicle,
+        Self::Building,
+        Self::Aircraft,
+        Self::Overlay,
+        Self::Terrain,
+        Self::Smudge,
+    ];
+
+    /// The rules.ini section listing every type of this kind.
+    pub fn list_section(self) -> &'static str {
+        match self {
+            Self::Infantry => "InfantryTypes",
+            Self::Vehicle => "VehicleTypes",
+            Self::Building => "BuildingTypes",
+            Self::Aircraft => "AircraftTypes",
+            Self::Overlay => "OverlayTypes",
+            Self::Terrain => "TerrainTypes",
+            Self::Smudge => "SmudgeTypes",
+        }
+    }
+
+    /// The map placement section the type goes into, for TechnoTypes.
+    pub fn object_kind(self) -> Option<ObjectKind> {
+        match self {
+            Self::Infantry => Some(ObjectKind::Infantry),
+            Self::Vehicle => Some(ObjectKind::Unit),
+            Self::Building => Some(ObjectKind::Structure),
+            Self::Aircraft => Some(ObjectKind::Aircraft),
+            Self::Overlay | Self::Terrain | Self::Smudge => None,
+        }
+    }
+}
+
+/// One type from the rules lists, with what the palette and workspace need.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatalogEntry {
+    pub id: String,
+    pub kind: CatalogKind,
+    /// Position in its list; for overlays this is the `[OverlayPack]` ID.
+    pub index: usize,
+    /// `UIName=` string table label (`Name:MTNK`), resolved once string tables load.
+    pub ui_name: String,
+    /// `Name=`: the plain English name rules.ini carries alongside.
+    pub name: String,
+    /// Cells covered, from art's `Foundation=WxH` (1x1 when absent).
+    pub foundation: (i32, i32),
+    /// Art section and file name stem (`Image=`, else the ID).
+    pub image: String,
+    /// Art `Theater=yes`: the image is drawn from per-theater files.
+    pub theater_specific: bool,
+    /// Art `NewTheater=yes`: the image's second letter names the theater.
+    pub new_theater: bool,
+    /// Art `Voxel=yes`: drawn from a `.vxl` model rather than an SHP.
+    pub voxel: bool,
+    /// Theaters the type can be placed in.
+    pub theaters: Vec<Theater>,
+}
+
+impl CatalogEntry {
+    /// Best name to show: `Name=`, else the ID.
+    pub fn display_name(&self) -> &str {
+        if self.name.is_empty() { &self.id } else { &self.name }
+    }
+
+    /// The localized `UIName=` text, else [`Self::display_name`].
+    pub fn localized_name<'a>(&'a self, strings: &'a StringTable) -> &'a str {
+        strings.get(&self.ui_name).unwrap_or_else(|| self.display_name())
+    }
+
+    pub fn allowed_in(&self, theater: Theater) -> bool {
+        theater == Theater::Unknown || self.theaters.contains(&theater)
+    }
+
+    /// File the type is drawn from in `theater`: the `.vxl` model for voxels,
+    /// else an SHP. Terrain and `Theater=yes` images use the theater extension
+    /// (`tree01.tem`).
+    pub fn sprite_file(&self, theater: Theater) -> Option<String> {
+        let image = self.image.to_ascii_lowercase();
+        if self.voxel {
+            return Some(format!("{image}.vxl"));
+        }
+        if self.theater_specific || self.kind == CatalogKind::Terrain {
+            return Some(format!("{image}.{}", theater.suffix()?));
+        }
+        match theater.letter() {
+            Some(letter) if self.new_theater && image.len() >= 2 && image.is_char_boundary(2) => {
+                Some(format!("{}{}{}.shp", &image[..1], letter.to_ascii_lowercase(), &image[2..]))
+            }
+            _ => Some(format!("{image}.shp")),
+        }
+    }
+
+    /// Sprite file and palette to draw the type with in `theater`. Units,
+    /// infantry and structures use the remappable unit palette (`unittem.pal`);
+    /// terrain, overlays and smudges the theater one (`temperat.pal`).
+    pub fn sprite(&self, theater: Theater) -> Option<(String, String)> {
+        let palette = match self.kind.object_kind() {
+            Some(_) => format!("unit{}.pal", theater.suffix()?),
+            None => theater.palette()?,
+        };
+        Some((self.sprite_file(theater)?, palette))
+    }
+}
+
+/// Every placeable type from `rules(md).ini`/`art(md).ini` in the game directory,
+/// with the open map's rules overrides merged in.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ObjectCatalog {
+    pub entries: Vec<CatalogEntry>,
+    /// Files the catalog was read from.
+    pub sources: Vec<PathBuf>,
+    /// The global rules, kept to diff and merge map overrides against.
+    pub rules: Option<MapDocument>,
+    art: Option<MapDocument>,
+    /// Path of the map whose overrides `entries` include.
+    pub merged_map: Option<String>,
+    /// Upper-cased ID to the first entry with it; the workspace looks types up every frame.
+    by_id: HashMap<String, usize>,
+}
+
+/// Value with any trailing `; comment` removed (rules.ini is full of them).
+fn value<'a>(s: &'a Section, key: &str) -> Option<&'a str> {
+    s.get(key).map(|v| v.split(';').next().unwrap_or_default().trim()).filter(|v| !v.is_empty())
+}
+
+/// `WxH` foundation; `Custom` and malformed values count as one cell.
+fn parse_foundation(v: &str) -> (i32, i32) {
+    v.split_once(['x', 'X'])
+        .and_then(|(w, h)| Some((w.trim().parse().ok()?, h.trim().parse().ok()?)))
+        .filter(|&(w, h)| w > 0 && h > 0)
+        .unwrap_or((1, 1))
+}
+
+/// Theaters a type can be placed in. Per-theater images (terrain and art
+/// `Theater=yes`) exist only in the theaters whose file is in the game
+/// (`tree01.tem`, `tree01.sno`, ...); other types go anywhere.
+fn theaters(vfs: &Vfs, image: &str, per_theater: bool) -> Vec<Theater> {
+    if !per_theater {
+        return Theater::ALL.to_vec();
+    }
+    let image = image.to_ascii_lowercase();
+    Theater::ALL
+        .into_iter()
+        .filter(|t| t.suffix().is_some_and(|suffix| vfs.contains(&format!("{image}.{suffix}"))))
+        .collect()
+}
+
+impl ObjectCatalog {
+    /// Read the Yuri's Revenge files (`rulesmd.ini`, `artmd.ini`) when present,
+    /// else the RA2 ones, as loose files or from the game's archives. Each md
+    /// file is a complete replacement, not a patch.
+    pub fn load(vfs: &Vfs) -> Result<Self> {
+        let (rules_path, bytes) = vfs
+            .read("rulesmd.ini")
+            .or_else(|| vfs.read("rules.ini"))
+            .ok_or_else(|| anyhow!("no rules.ini or rulesmd.ini in the game files"))??;
+        let rules = MapDocument::from_bytes(&bytes);
+        let mut sources = vec![rules_path];
+
+        let art = match vfs.read("artmd.ini").or_else(|| vfs.read("art.ini")).transpose()? {
+            Some((path, bytes)) => {
+                sources.push(path);
+                Some(MapDocument::from_bytes(&bytes))
+            }
+            None => {
+                eprintln!("[backend] No art.ini in the game files; foundations default to 1x1");
+                None
+            }
+        };
+
+        let mut catalog = Self { sources, ..Self::default() };
+        catalog.set_entries(Self::entries_from(&rules, art.as_ref(), vfs));
+        catalog.rules = Some(rules);
+        catalog.art = art;
+        Ok(catalog)
+    }
+
+    /// Rebuild `entries` from the global rules with `map`'s overrides on top
+    /// (or the global rules alone). Does nothing before a game directory is set.
+    pub fn apply_map(&mut self, vfs: &Vfs, map: Option<&MapDocument>) {
+        let Some(rules) = &self.rules else {
+            return;
+        };
+        let entries = match map {
+            Some(map) => Self::entries_from(&merge_rules(rules, map), self.art.as_ref(), vfs),
+            None => Self::entries_from(rules, self.art.as_ref(), vfs),
+        };
+        self.set_entries(entries);
+    }
+
+    fn set_entries(&mut self, entries: Vec<CatalogEntry>) {
+        self.by_id.clear();
+        for (i, e) in entries.iter().enumerate() {
+            self.by_id.entry(e.id.to_ascii_uppercase()).or_insert(i);
+        }
+        self.entries = entries;
+    }
+
+    /// Build entries from already-parsed rules and art files, checking
+    /// per-theater images against the game files in `vfs`.
+    pub fn entries_from(rules: &MapDocument, art: Option<&MapDocument>, vfs: &Vfs) -> Vec<CatalogEntry> {
+        let mut entries = Vec::new();
+        for kind in CatalogKind::ALL {
+            let Some(list) = rules.section(kind.list_section()) else {
+                continue;
+            };
+            let ids = list.entries().map(|e| e.value().split(';').next().unwrap_or_default().trim().to_string());
+            for (index, id) in ids.enumerate() {
+                if id.is_empty() {
+                    continue;
+                }
+                let section = rules.section(&id);
+                let get = |key: &str| section.and_then(|s| value(s, key)).unwrap_or_default().to_string();
+                let image = section.and_then(|s| value(s, "Image")).unwrap_or(&id);
+                let art_section = art.and_then(|a| a.section(image).or_else(|| a.section(&id)));
+                let art_flag = |key: &str| art_section.and_then(|s| value(s, key)).is_some_and(parse_bool);
+                let (theater_specific, voxel) = (art_flag("Theater"), art_flag("Voxel"));
+                let per_theater = !voxel && (kind == CatalogKind::Terrain || theater_specific);
+                entries.push(CatalogEntry {
+                    kind,
+                    index,
+                    ui_name: get("UIName"),
+                    name: get("Name"),
+                    foundation: art_section.and_then(|s| value(s, "Foundation")).map_or((1, 1), parse_foundation),
+                    image: image.to_string(),
+                    theater_specific,
+                    new_theater: art_flag("NewTheater"),
+                    voxel,
+                    theaters: theaters(vfs, image, per_theater),
+                    id,
+                });
+            }
+        }
+        entries
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// First entry with this ID (overlays and terrain may share IDs with nothing else).
+    pub fn get(&self, id: &str) -> Option<&CatalogEntry> {
+        self.by_id.get(&id.to_ascii_uppercase()).map(|&i| &self.entries[i])
+    }
+
+    pub fn of_kind(&self, kind: CatalogKind) -> impl Iterator<Item = &CatalogEntry> {
+        self.entries.iter().filter(move |e| e.kind == kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn per_theater_images_are_offered_where_their_files_exist() {
+        let dir = std::env::temp_dir().join(format!("rules-catalog-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["tree01.tem", "TREE01.SNO", "cabarn.urb"] {
+            std::fs::write(dir.join(name), b"").unwrap();
+        }
+        let vfs = Vfs { dir: Some(dir.clone()), archives: Vec::new() };
+        let rules = MapDocument::parse(
+            "[TerrainTypes]\n0=TREE01\n[BuildingTypes]\n0=CABARN\n1=GAPOWR\n[GAPOWR]\nName=Power Plant\n",
+        );
+        let art = MapDocument::parse("[CABARN]\nTheater=yes\n[GAPOWR]\nNewTheater=yes\n");
+        let entries = ObjectCatalog::entries_from(&rules, Some(&art), &vfs);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let theaters = |id: &str| entries.iter().find(|e| e.id == id).map(|e| e.theaters.clone());
+        assert_eq!(theaters("TREE01"), Some(vec![Theater::Temperate, Theater::Snow]));
+        assert_eq!(theaters("CABARN"), Some(vec![Theater::Urban]));
+        assert_eq!(theaters("GAPOWR"), Some(Theater::ALL.to_vec()));
+    }
+}
@@ -0,0 +1,129 @@
+use super::map_document::{MapDocument, Section};
+use super::rules_catalog::CatalogKind;
+
+/// Sections that belong to the map format itself. Some share a name with a
+/// rules.ini section but never override it.
+pub(crate) const MAP_SECTIONS: &[&str] = &[
+    "Basic", "Map", "Header", "Preview", "PreviewPack", "IsoMapPack5", "OverlayPack", "OverlayDataPack",
+    "Digest", "Waypoints", "Houses", "Lighting", "SpecialFlags", "Triggers", "Events", "Actions", "Tags",
+    "CellTags", "TaskForces", "ScriptTypes", "TeamTypes", "AITriggerTypes", "AITriggerTypesEnable", "Units",
+    "Infantry", "Structures", "Aircraft", "Terrain", "Smudge", "Tubes", "VariableNames", "Ranking",
+];
+
+/// One `key=value` the map sets in a rules section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyOverride {
+    pub key: String,
+    /// The global rules value (comment stripped); `None` if rules.ini lacks the key.
+    /// For type lists this is the ID when rules.ini already lists it.
+    pub default: Option<String>,
+    pub value: String,
+}
+
+impl KeyOverride {
+    /// The map repeats the global value, so removing it changes nothing.
+    pub fn is_redundant(&self) -> bool {
+        self.default.as_deref() == Some(self.value.as_str())
+    }
+}
+
+/// A rules section the map changes, with the keys it sets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RulesOverride {
+    pub section: String,
+    /// A type the map adds through one of the type lists; rules.ini has no such section.
+    pub new_section: bool,
+    /// The section is a type list (`[VehicleTypes]`, ...), whose entries add types.
+    pub is_list: bool,
+    pub keys: Vec<KeyOverride>,
+}
+
+/// Value with any trailing `; comment` removed.
+fn strip(v: &str) -> &str {
+    v.split(';').next().unwrap_or_default().trim()
+}
+
+fn list_ids(s: &Section) -> impl Iterator<Item = &str> {
+    s.entries().map(|e| strip(e.value())).filter(|v| !v.is_empty())
+}
+
+fn is_list_section(name: &str) -> bool {
+    CatalogKind::ALL.iter().any(|k| k.list_section().eq_ignore_ascii_case(name))
+}
+
+/// Map sections that override or extend `rules`, in map file order.
+///
+/// A section counts when rules.ini has it, when it is a type list, or when a
+/// map type list adds it as a new type. Map structure sections and the map's
+/// own house sections (named like the rules countries) are skipped.
+pub fn rules_overrides(rules: &MapDocument, map: &MapDocument) -> Vec<RulesOverride> {
+    let houses: Vec<&str> = map.section("Houses").map(|s| list_ids(s).collect()).unwrap_or_default();
+    let added: Vec<&str> = CatalogKind::ALL
+        .iter()
+        .filter_map(|k| map.section(k.list_section()))
+        .flat_map(list_ids)
+        .collect();
+
+    let mut out: Vec<RulesOverride> = Vec::new();
+    for section in map.sections() {
+        let name = section.name();
+        let skip = MAP_SECTIONS.iter().chain(&houses).any(|s| s.eq_ignore_ascii_case(name))
+            || out.iter().any(|o| o.section.eq_ignore_ascii_case(name));
+        if skip {
+            continue;
+        }
+        let global = rules.section(name);
+        let is_list = is_list_section(name);
+        let new_section = global.is_none() && !is_list;
+        if new_section && !added.iter().any(|id| id.eq_ignore_ascii_case(name)) {
+            continue;
+        }
+
+        // Read through duplicate sections so the last occurrence wins, like `get`.
+        let mut keys: Vec<KeyOverride> = Vec::new();
+        for e in map.sections().iter().filter(|s| s.name().eq_ignore_ascii_case(name)).flat_map(Section::entries) {
+            let value = e.value().trim().to_string();
+            let default = if is_list {
+                let id = strip(&value);
+                global.and_then(|g| list_ids(g).find(|v| v.eq_ignore_ascii_case(id))).map(str::to_string)
+            } else {
+                global.and_then(|g| g.get(e.key())).map(|v| strip(v).to_string())
+            };
+            let k = KeyOverride { key: e.key().to_string(), default, value };
+            match keys.iter_mut().find(|o| o.key.eq_ignore_ascii_case(&k.key)) {
+                Some(existing) => *existing = k,
+                None => keys.push(k),
+            }
+        }
+        if !keys.is_empty() {
+            out.push(RulesOverride { section: name.to_string(), new_section, is_list, keys });
+        }
+    }
+    out
+}
+
+/// The rules the game sees with this map loaded: every override applied on top
+/// of `rules`. Type list entries add the types rules.ini lacks; list keys are
+/// only positions, so they never replace a global entry.
+pub fn merge_rules(rules: &MapDocument, map: &MapDocument) -> MapDocument {
+    let mut merged = rules.clone();
+    for o in rules_overrides(rules, map) {
+        let section = merged.ensure_section(&o.section);
+        for k in o.keys {
+            if !o.is_list {
+                section.set(&k.key, &k.value);
+            } else if k.default.is_none() {
+                let key = section.entries().count().to_string();
+                section.push(&key, strip(&k.value));
+            }
+        }
+    }
+    merged
+}
+
+/// Drop the map's `[section] key` from every duplicate of the section, falling
+/// back to the global value. Sections left without entries go too. Returns
+/// `true` if anything was removed.
+pub fn reset_override(map: &mut MapDocument, section: &str, key: &str) -> bool {
+    map.remove_key(section, key)
+}
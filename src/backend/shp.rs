@@ -0,0 +1,166 @@
+//! Westwood SHP sprites as used by Tiberian Sun and RA2.
+//!
+//! ```text
+//! u16 0 | u16 width | u16 height | u16 frame_count
+//! frame_count × 24-byte headers:
+//!   u16 x | u16 y | u16 w | u16 h | u8 compression | 11 bytes (radar color, padding) | u32 data offset
+//! ```
+//!
+//! Each frame is a `w × h` rectangle at `(x, y)` inside the shared canvas.
+//! Compression 1 is raw pixels; 2 prefixes each line with its u16 length
+//! (including the prefix); 3 does the same and also run-length encodes
+//! transparent pixels as `0, count`. Offset 0 is an empty frame.
+
+use anyhow::{bail, Result};
+
+use super::palette::Palette;
+
+/// Largest canvas or frame accepted, in pixels. Game sprites are far smaller;
+/// anything bigger is a corrupt header that would ask for gigabytes.
+const MAX_PIXELS: usize = 4096 * 4096;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShpFrame {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    /// Palette indices, `width * height`; 0 is transparent.
+    pub pixels: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shp {
+    pub width: u32,
+    pub height: u32,
+    pub frames: Vec<ShpFrame>,
+}
+
+fn u16_at(b: &[u8], at: usize) -> u32 {
+    u16::from_le_bytes([b[at], b[at + 1]]) as u32
+}
+
+impl Shp {
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < 8 || u16_at(bytes, 0) != 0 {
+            bail!("not a TS/RA2 SHP file");
+        }
+        let (width, height, count) = (u16_at(bytes, 2), u16_at(bytes, 4), u16_at(bytes, 6) as usize);
+        if width as usize * height as usize > MAX_PIXELS {
+            bail!("SHP canvas {width}x{height} is too large");
+        }
+        if bytes.len() < 8 + count * 24 {
+            bail!("SHP frame table is truncated");
+        }
+        let mut frames = Vec::with_capacity(count);
+        for i in 0..count {
+            let h = &bytes[8 + i * 24..8 + (i + 1) * 24];
+            let (x, y, w, fh) = (u16_at(h, 0), u16_at(h, 2), u16_at(h, 4), u16_at(h, 6));
+            let offset = u32::from_le_bytes([h[20], h[21], h[22], h[23]]) as usize;
+            if w as usize * fh as usize > MAX_PIXELS {
+                bail!("SHP frame {i} ({w}x{fh}) is too large");
+            }
+            let mut frame = ShpFrame { x, y, width: w, height: fh, pixels: vec![0; w as usize * fh as usize] };
+            if offset != 0 && w > 0 && fh > 0 {
+                let Some(data) = bytes.get(offset..) else {
+                    bail!("SHP frame {i} starts past the end of the file");
+                };
+                decode_frame(h[8], data, &mut frame)
+                    .ok_or_else(|| anyhow::anyhow!("SHP frame {i} is truncated"))?;
+            }
+            frames.push(frame);
+        }
+        Ok(Self { width, height, frames })
+    }
+
+    /// Frame `index` on the full canvas as RGBA (`width * height * 4`).
+    pub fn frame_rgba(&self, index: usize, palette: &Palette) -> Option<Vec<u8>> {
+        let f = self.frames.get(index)?;
+        let (width, height) = (self.width as usize, self.height as usize);
+        let mut out = vec![0u8; width.checked_mul(height)?.checked_mul(4)?];
+        let (fx, fy, fw) = (f.x as usize, f.y as usize, f.width as usize);
+        for row in 0..(f.height as usize).min(height.saturating_sub(fy)) {
+            for col in 0..fw.min(width.saturating_sub(fx)) {
+                let at = ((fy + row) * width + fx + col) * 4;
+                let index = f.pixels[row * fw + col];
+                out[at..at + 4].copy_from_slice(&palette.rgba(index));
+            }
+        }
+        Some(out)
+    }
+}
+
+/// Fill `frame.pixels` from its data; `None` if the data ends early.
+fn decode_frame(compression: u8, data: &[u8], frame: &mut ShpFrame) -> Option<()> {
+    let w = frame.width as usize;
+    if compression < 2 {
+        let n = frame.pixels.len();
+        frame.pixels.copy_from_slice(data.get(..n)?);
+        return Some(());
+    }
+    let mut pos = 0;
+    for line in frame.pixels.chunks_exact_mut(w) {
+        let len = u16::from_le_bytes([*data.get(pos)?, *data.get(pos + 1)?]) as usize;
+        let src = data.get(pos + 2..pos + len.max(2))?;
+        pos += len.max(2);
+        if compression == 2 {
+            let n = src.len().min(w);
+            line[..n].copy_from_slice(&src[..n]);
+            continue;
+        }
+        let (mut i, mut x) = (0, 0);
+        while i < src.len() && x < w {
+            if src[i] == 0 {
+                x += *src.get(i + 1)? as usize;
+                i += 2;
+            } else {
+                line[x] = src[i];
+                x += 1;
+                i += 1;
+            }
+        }
+    }
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A one-frame `width × height` SHP whose frame covers the canvas, with
+    /// `data` as the frame's pixel data.
+    fn one_frame(width: u16, height: u16, compression: u8, data: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for n in [0, width, height, 1, 0, 0, width, height] {
+            bytes.extend_from_slice(&n.to_le_bytes());
+        }
+        bytes.push(compression);
+        bytes.extend_from_slice(&[0; 11]);
+        bytes.extend_from_slice(&32u32.to_le_bytes());
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    #[test]
+    fn decodes_transparent_runs() {
+        // Line 0: 5, two transparent pixels, 6. Line 1: three transparent pixels, 7.
+        let data = [6, 0, 5, 0, 2, 6, 5, 0, 0, 3, 7];
+        let shp = Shp::parse(&one_frame(4, 2, 3, &data)).unwrap();
+        assert_eq!((shp.width, shp.height), (4, 2));
+        assert_eq!(shp.frames[0].pixels, [5, 0, 0, 6, 0, 0, 0, 7]);
+    }
+
+    #[test]
+    fn rejects_a_truncated_run() {
+        assert!(Shp::parse(&one_frame(4, 1, 3, &[6, 0, 5, 0, 2])).is_err());
+    }
+
+    #[test]
+    fn rejects_an_oversized_canvas() {
+        let mut bytes = vec![0, 0];
+        for n in [u16::MAX, u16::MAX, 0] {
+            bytes.extend_from_slice(&n.to_le_bytes());
+        }
+        assert!(Shp::parse(&bytes).is_err());
+    }
+}
@@ -0,0 +1,78 @@
+use super::map_document::{read_flag, write_flag, MapDocument};
+
+/// Typed `[SpecialFlags]`: game rule switches for this map.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpecialFlags {
+    pub inert: bool,
+    pub fog_of_war: bool,
+    pub ion_storms: bool,
+    pub mcv_deploy: bool,
+    pub meteorites: bool,
+    pub visceroids: bool,
+    pub fixed_alliance: bool,
+    pub tiberium_grows: bool,
+    pub initial_veteran: bool,
+    pub harvester_immune: bool,
+    pub tiberium_spreads: bool,
+    pub tiberium_explosive: bool,
+    pub destroyable_bridges: bool,
+}
+
+impl Default for SpecialFlags {
+    /// FinalAlert's values for a new map.
+    fn default() -> Self {
+        Self {
+            inert: false,
+            fog_of_war: false,
+            ion_storms: false,
+            mcv_deploy: false,
+            meteorites: false,
+            visceroids: true,
+            fixed_alliance: false,
+            tiberium_grows: true,
+            initial_veteran: false,
+            harvester_immune: false,
+            tiberium_spreads: true,
+            tiberium_explosive: false,
+            destroyable_bridges: true,
+        }
+    }
+}
+
+impl SpecialFlags {
+    /// Every flag with its INI key, in FinalAlert's order (drives I/O and the panel).
+    pub fn flags_mut(&mut self) -> [(&'static str, &mut bool); 13] {
+        [
+            ("Inert", &mut self.inert),
+            ("FogOfWar", &mut self.fog_of_war),
+            ("IonStorms", &mut self.ion_storms),
+            ("MCVDeploy", &mut self.mcv_deploy),
+            ("Meteorites", &mut self.meteorites),
+            ("Visceroids", &mut self.visceroids),
+            ("FixedAlliance", &mut self.fixed_alliance),
+            ("TiberiumGrows", &mut self.tiberium_grows),
+            ("InitialVeteran", &mut self.initial_veteran),
+            ("HarvesterImmune", &mut self.harvester_immune),
+            ("TiberiumSpreads", &mut self.tiberium_spreads),
+            ("TiberiumExplosive", &mut self.tiberium_explosive),
+            ("DestroyableBridges", &mut self.destroyable_bridges),
+        ]
+    }
+
+    pub fn from_document(doc: &MapDocument) -> Self {
+        let mut flags = Self::default();
+        if let Some(s) = doc.section("SpecialFlags") {
+            for (key, value) in flags.flags_mut() {
+                read_flag(s, key, value);
+            }
+        }
+        flags
+    }
+
+    pub fn apply(&self, doc: &mut MapDocument) {
+        let s = doc.ensure_section("SpecialFlags");
+        for (key, value) in self.clone().flags_mut() {
+            write_flag(s, key, *value);
+        }
+    }
+}
@@ -1,15 +1,69 @@
 use bevy::prelude::*;
 use bevy_egui::egui;
 
-use super::events::{OpenFolder, OpenMap};
+use super::events::{LoadAiIni, OpenFolder, OpenMap, ResetRulesKey, SaveMap, SetGameDir, TriggerEdit};
 use super::loader::load_tree_from;
-use super::map_parser::{parse_map_header, MapHeader, Theater};
+use super::editor_objects::{EditorObjects, Placement, Tool};
+use super::iso_map::IsoMapGrid;
+use super::map_document::MapDocument;
+use super::map_parser::{MapHeader, Theater};
+use super::map_writer::{apply_placements, save_map};
+use super::objects::{parse_objects, write_objects};
+use super::terrain_objects::{parse_smudges, parse_terrain, write_smudges, write_terrain};
+use super::waypoints::{parse_waypoints, write_waypoints, Waypoint};
+use super::header_sync::HeaderStarts;
+use super::houses::{parse_houses, write_houses, House};
+use super::lighting::{Lighting, LightingMode};
+use super::special_flags::SpecialFlags;
+use super::triggers::{Tag, Trigger, TriggerSet};
+use super::ai_scripts::AiScripts;
+use super::ai_triggers::{AiTriggerCatalog, AiTriggerEnables};
+use super::rules_catalog::ObjectCatalog;
+use super::mix::Vfs;
+use super::tileset::TileLibrary;
+use super::csf::StringTable;
+use super::rules_overrides::reset_override;
+use super::overlay::OverlayLayer;
 use super::project::{EditorLayout, ProjectState};
 
 /// Holds the currently previewed map (if any) for the workspace to render.
 #[derive(Resource, Debug, Clone, Default)]
 pub struct MapPreview {
     pub header: Option<MapHeader>,
+    /// Full INI contents of the open map, so panels can read any section
+    /// without going back to disk.
+    pub document: Option<MapDocument>,
+    /// Decoded `[IsoMapPack5]` terrain (flat clear ground if the pack is missing).
+    pub iso: Option<IsoMapGrid>,
+    /// Decoded `[OverlayPack]`/`[OverlayDataPack]` (ore, gems, walls, bridges, ...).
+    pub overlay: Option<OverlayLayer>,
+    /// Whether `iso` and `overlay` came from the map's packs. When one is a
+    /// placeholder for a pack that didn't decode, saving keeps the original.
+    pub iso_decoded: bool,
+    pub overlay_decoded: bool,
+    pub path: Option<String>,
+    /// Typed `[Houses]` + house sections, written back on save.
+    pub houses: Vec<House>,
+    pub special_flags: SpecialFlags,
+    pub lighting: Lighting,
+    /// `[Triggers]`, `[Events]`, `[Actions]`, `[Tags]` and `[CellTags]`.
+    pub triggers: TriggerSet,
+    /// `[TaskForces]`, `[ScriptTypes]` and `[TeamTypes]`.
+    pub ai: AiScripts,
+    /// `[AITriggerTypesEnable]`: which global AI triggers the map turns on or off.
+    pub ai_enable: AiTriggerEnables,
+}
+
+impl MapPreview {
+    /// Forget the open map (folder closed/switched or load failed).
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Free ID for a new trigger, tag, team, script or task force.
+    pub fn next_id(&self) -> String {
+        self.triggers.next_id(|id| self.ai.contains_id(id))
+    }
 }
 
 /// Pan/zoom state for the workspace map view.
@@ -29,10 +83,14 @@ impl Default for MapView {
 pub struct WorkspaceSettings {
     pub show_grid: bool,
     pub selected: Option<(i32, i32)>, // (x, y) in tile coords
+    /// `[Lighting]` set used to tint the terrain fill.
+    pub lighting: LightingMode,
+    /// Cell pointed at from another panel (e.g. a trigger's waypoint parameter).
+    pub highlight: Option<(i32, i32)>,
 }
 impl Default for WorkspaceSettings {
     fn default() -> Self {
-        Self { show_grid: true, selected: None }
+        Self { show_grid: true, selected: None, lighting: LightingMode::Normal, highlight: None }
     }
 }
 
@@ -44,6 +102,7 @@ pub fn handle_open_folder(
     mut preview: ResMut<MapPreview>,
     mut view: ResMut<MapView>,
     mut ws: ResMut<WorkspaceSettings>,
+    mut catalog: ResMut<AiTriggerCatalog>,
 ) {
     for ev in evr.read() {
         match ev {
@@ -59,10 +118,23 @@ pub fn handle_open_folder(
                             layout.open_folders.insert(root_id);
 
                             // Reset workspace view & selection when switching folders
-                            preview.header = None;
+                            preview.clear();
                             *view = MapView::default();
                             ws.selected = None;
 
+                            // A game folder names the global AI triggers for the enable panel.
+                            *catalog = match AiTriggerCatalog::from_folder(&dir) {
+                                Some(Ok(c)) => {
+                                    println!("[backend] Loaded {} AI triggers from {}", c.entries.len(), dir.display());
+                                    c
+                                }
+                                Some(Err(e)) => {
+                                    eprintln!("[backend] Failed to read AI triggers in {}: {e}", dir.display());
+                                    AiTriggerCatalog::default()
+                                }
+                                None => AiTriggerCatalog::default(),
+                            };
+
                             println!("[backend] Opened folder: {}", dir.display());
                         }
                         Err(e) => {
@@ -72,7 +144,7 @@ pub fn handle_open_folder(
                             layout.open_folders.clear();
 
                             // Also reset workspace to blank
-                            preview.header = None;
+                            preview.clear();
                             *view = MapView::default();
                             ws.selected = None;
 
@@ -90,7 +162,7 @@ pub fn handle_open_folder(
                 layout.open_folders.clear();
 
                 // ▼ Ensure the workspace returns to its original blank view
-                preview.header = None;
+                preview.clear();
                 *view = MapView::default();
                 ws.selected = None;
 
@@ -105,28 +177,309 @@ pub fn handle_open_map(
     mut preview: ResMut<MapPreview>,
     mut view: ResMut<MapView>,
     mut ws: ResMut<WorkspaceSettings>,
+    mut objs: ResMut<EditorObjects>,
+    mut layout: ResMut<EditorLayout>,
 ) {
     for ev in evr.read() {
         let is_map = ev.path.to_ascii_lowercase().ends_with(".map");
         if !is_map {
             continue;
         }
-        match parse_map_header(&ev.path) {
-            Ok(h) => {
+        let loaded = MapDocument::load(&ev.path)
+            .and_then(|doc| MapHeader::from_document(&doc).map(|h| (h, doc)));
+        match loaded {
+            Ok((h, doc)) => {
+                let (iso, iso_decoded) = match IsoMapGrid::from_document(&doc, h.width, h.height) {
+                    Ok(iso) => (iso, true),
+                    Err(e) => {
+                        eprintln!("[backend] {}: {e}; showing flat terrain, [IsoMapPack5] is kept as-is", ev.path);
+                        (IsoMapGrid::blank(h.width, h.height), false)
+                    }
+                };
+                let (overlay, overlay_decoded) = match OverlayLayer::from_document(&doc) {
+                    Ok(overlay) => (overlay, true),
+                    Err(e) => {
+                        eprintln!("[backend] {}: {e}; ignoring overlays, their packs are kept as-is", ev.path);
+                        (OverlayLayer::default(), false)
+                    }
+                };
+
+                // Placements belong to the map: start from the map's own ore/gem fields.
+                objs.items.clear();
+                objs.items.extend(overlay.iter().filter_map(|(x, y, o)| {
+                    o.resource().map(|r| Placement { kind: Tool::Resource(r), x, y, overlay: Some(o) })
+                }));
+                objs.waypoints = parse_waypoints(&doc);
+                objs.objects = parse_objects(&doc);
+                objs.terrain = parse_terrain(&doc);
+                objs.smudges = parse_smudges(&doc);
+
+                let mismatches = HeaderStarts::compute(&doc, &objs.waypoints).mismatches(&doc);
+                if !mismatches.is_empty() {
+                    eprintln!(
+                        "[backend] {}: [Header] disagrees with [Waypoints] ({}); it will be rewritten on save",
+                        ev.path,
+                        mismatches.join(", "),
+                    );
+                }
+
+                preview.houses = parse_houses(&doc);
+                preview.special_flags = SpecialFlags::from_document(&doc);
+                preview.lighting = Lighting::from_document(&doc);
+                preview.triggers = TriggerSet::from_document(&doc);
+                preview.ai = AiScripts::from_document(&doc);
+                preview.ai_enable = AiTriggerEnables::from_document(&doc);
+                preview.iso = Some(iso);
+                preview.overlay = Some(overlay);
+                preview.iso_decoded = iso_decoded;
+                preview.overlay_decoded = overlay_decoded;
                 preview.header = Some(h);
+                preview.document = Some(doc);
+                preview.path = Some(ev.path.clone());
+                layout.show_map_info = true;
                 // Reset camera & selection so the new map appears centered.
                 *view = MapView::default();
                 ws.selected = None;
                 println!("[backend] Loaded map header from {}", ev.path);
             }
             Err(e) => {
-                preview.header = None;
+                preview.clear();
                 eprintln!("[backend] Failed to parse map {}: {e}", ev.path);
             }
         }
     }
 }
 
+pub fn handle_save_map(
+    mut evr: EventReader<SaveMap>,
+    mut preview: ResMut<MapPreview>,
+    objs: Res<EditorObjects>,
+    catalog: Res<AiTriggerCatalog>,
+) {
+    for ev in evr.read() {
+        let MapPreview {
+            document: Some(doc), iso: Some(iso), overlay: Some(overlay), path: Some(current), iso_decoded, overlay_decoded, houses, special_flags, lighting, triggers, ai, ai_enable, ..
+        } = &mut *preview
+        else {
+            println!("[backend] Save ignored: no map is open.");
+            continue;
+        };
+
+        let target = match ev {
+            SaveMap::Save => std::path::PathBuf::from(current.as_str()),
+            SaveMap::SaveAs => {
+                let current_path = std::path::Path::new(current.as_str());
+                let mut dialog = rfd::FileDialog::new().add_filter("RA2/YR map", &["map", "mpr", "yrm"]);
+                if let Some(dir) = current_path.parent() {
+                    dialog = dialog.set_directory(dir);
+                }
+                if let Some(name) = current_path.file_name() {
+                    dialog = dialog.set_file_name(name.to_string_lossy());
+                }
+                match dialog.save_file() {
+                    Some(p) => p,
+                    None => {
+                        println!("[backend] Save As canceled by user.");
+                        continue;
+                    }
+                }
+            }
+        };
+
+        if *overlay_decoded {
+            apply_placements(overlay, &objs);
+        } else if objs.items.iter().any(|p| matches!(p.kind, Tool::Resource(_) | Tool::Overlay(_))) {
+            eprintln!("[backend] [OverlayPack] didn't decode when opened; overlay edits are not saved");
+        }
+        write_waypoints(doc, &objs.waypoints);
+        write_objects(doc, &objs.objects);
+        write_terrain(doc, &objs.terrain);
+        write_smudges(doc, &objs.smudges);
+        HeaderStarts::compute(doc, &objs.waypoints).apply(doc);
+        write_houses(doc, houses);
+        special_flags.apply(doc);
+        lighting.apply(doc);
+        triggers.apply(doc);
+        ai.apply(doc);
+        ai_enable.apply(doc, &catalog);
+        let (iso, overlay) = (iso_decoded.then_some(&*iso), overlay_decoded.then_some(&*overlay));
+        match save_map(&target, doc, iso, overlay) {
+            Ok(()) => {
+                *current = target.to_string_lossy().to_string();
+                println!("[backend] Saved map to {}", target.display());
+            }
+            Err(e) => eprintln!("[backend] Failed to save map {}: {e}", target.display()),
+        }
+    }
+}
+
+pub fn handle_trigger_edit(mut evr: EventReader<TriggerEdit>, mut preview: ResMut<MapPreview>) {
+    for ev in evr.read() {
+        match ev {
+            TriggerEdit::Add { house } => {
+                let id = preview.next_id();
+                let trigger = Trigger::new(&id, house);
+                // FinalAlert gives every trigger its own tag so it can be attached right away.
+                preview.triggers.triggers.push(trigger.clone());
+                let tag_id = preview.next_id();
+                preview.triggers.tags.push(Tag { id: tag_id, repeat: 0, name: format!("{} 1", trigger.name), trigger: id.clone() });
+                println!("[backend] Added trigger {id}");
+            }
+            TriggerEdit::Remove { id } => {
+                let set = &mut preview.triggers;
+                set.triggers.retain(|t| !t.id.eq_ignore_ascii_case(id));
+                let tags: Vec<String> = set.tags_for_trigger(id).map(|t| t.id.clone()).collect();
+                set.tags.retain(|t| !tags.contains(&t.id));
+                set.cell_tags.retain(|c| !tags.contains(&c.tag));
+                // Events/actions that didn't parse are kept by trigger ID; they go with it.
+                set.unparsed.retain(|(section, key, _)| {
+                    !(matches!(section.as_str(), "Triggers" | "Events" | "Actions") && key.eq_ignore_ascii_case(id))
+                });
+                println!("[backend] Removed trigger {id} ({} tags)", tags.len());
+            }
+            TriggerEdit::Update(trigger) => match preview.triggers.triggers.iter_mut().find(|t| t.id == trigger.id) {
+                Some(t) => *t = trigger.clone(),
+                None => eprintln!("[backend] Trigger {} no longer exists; edit dropped", trigger.id),
+            },
+        }
+    }
+}
+
+pub fn handle_load_ai_ini(mut evr: EventReader<LoadAiIni>, mut catalog: ResMut<AiTriggerCatalog>) {
+    for _ in evr.read() {
+        let Some(path) = rfd::FileDialog::new().add_filter("AI rules", &["ini"]).pick_file() else {
+            println!("[backend] Load ai.ini canceled by user.");
+            continue;
+        };
+        match AiTriggerCatalog::load(&path) {
+            Ok(c) => {
+                println!("[backend] Loaded {} AI triggers from {}", c.entries.len(), path.display());
+                *catalog = c;
+            }
+            Err(e) => eprintln!("[backend] Failed to read {}: {e}", path.display()),
+        }
+    }
+}
+
+/// Mount the picked game directory's archives and load the rules/art catalog
+/// from them; its `ai.ini` (if any) replaces the AI trigger names too.
+pub fn handle_set_game_dir(
+    mut evr: EventReader<SetGameDir>,
+    mut project: ResMut<ProjectState>,
+    mut objects: ResMut<ObjectCatalog>,
+    mut ai: ResMut<AiTriggerCatalog>,
+    mut strings: ResMut<StringTable>,
+    mut vfs: ResMut<Vfs>,
+) {
+    for _ in evr.read() {
+        let Some(dir) = rfd::FileDialog::new().pick_folder() else {
+            println!("[backend] Set Game Directory canceled by user.");
+            continue;
+        };
+        let mounted = match Vfs::mount(&dir) {
+            Ok(v) => v,
+            Err(e) => {
+                eprintln!("[backend] Failed to read {}: {e}", dir.display());
+                continue;
+            }
+        };
+        println!("[backend] Mounted {} MIX archives from {}", mounted.archives.len(), dir.display());
+        match ObjectCatalog::load(&mounted) {
+            Ok(c) => {
+                println!("[backend] Loaded {} object types from {}", c.entries.len(), dir.display());
+                *objects = c;
+            }
+            Err(e) => {
+                eprintln!("[backend] Failed to load object catalog: {e}");
+                continue;
+            }
+        }
+        match AiTriggerCatalog::from_vfs(&mounted) {
+            Some(Ok(c)) => *ai = c,
+            Some(Err(e)) => eprintln!("[backend] Failed to read AI triggers in {}: {e}", dir.display()),
+            None => {}
+        }
+        match StringTable::from_vfs(&mounted) {
+            Some(Ok(t)) => {
+                println!("[backend] Loaded {} {} strings", t.strings.len(), t.language_name());
+                *strings = t;
+            }
+            Some(Err(e)) => eprintln!("[backend] Failed to read string tables in {}: {e}", dir.display()),
+            None => *strings = StringTable::default(),
+        }
+        *vfs = mounted;
+        project.game_dir = Some(dir);
+    }
+}
+
+/// Keep the object catalog in step with the open map's rules overrides: merge
+/// them when a map is opened or the catalog reloaded, and after a key is reset.
+pub fn sync_map_rules(
+    mut evr: EventReader<ResetRulesKey>,
+    mut preview: ResMut<MapPreview>,
+    mut catalog: ResMut<ObjectCatalog>,
+    vfs: Res<Vfs>,
+) {
+    let mut dirty = catalog.merged_map != preview.path;
+    for ev in evr.read() {
+        let Some(doc) = preview.document.as_mut() else {
+            continue;
+        };
+        if reset_override(doc, &ev.section, &ev.key) {
+            println!("[backend] Reset [{}] {} to the global rules value", ev.section, ev.key);
+            dirty = true;
+        }
+    }
+    if dirty {
+        catalog.apply_map(&vfs, preview.document.as_ref());
+        catalog.merged_map = preview.path.clone();
+    }
+}
+
+/// Keep the tile library on the open map's theater, reloading it when the
+/// game directory is remounted. Each theater is tried once per mount.
+pub fn sync_tile_library(
+    vfs: Res<Vfs>,
+    preview: Res<MapPreview>,
+    mut tiles: ResMut<TileLibrary>,
+    mut tried: Local<Option<Theater>>,
+) {
+    if vfs.is_changed() {
+        tiles.clear();
+        *tried = None;
+    }
+    let Some(theater) = preview.header.as_ref().map(|h| h.theater) else {
+        return;
+    };
+    if vfs.dir.is_none() || *tried == Some(theater) {
+        return;
+    }
+    *tried = Some(theater);
+    if let Err(e) = tiles.load(&vfs, theater) {
+        eprintln!("[backend] Can't load {theater:?} tile sets: {e}");
+    }
+}
+
+/// Recompute `[Header]` start positions whenever the waypoints are edited.
+/// A freshly opened map is left untouched (its header is checked on open).
+pub fn sync_header(
+    objs: Res<EditorObjects>,
+    mut preview: ResMut<MapPreview>,
+    mut last: Local<(Option<String>, Vec<Waypoint>)>,
+) {
+    if !objs.is_changed() || (last.0 == preview.path && last.1 == objs.waypoints) {
+        return;
+    }
+    let reopened = last.0 != preview.path;
+    *last = (preview.path.clone(), objs.waypoints.clone());
+    if reopened {
+        return;
+    }
+    if let Some(doc) = preview.document.as_mut() {
+        HeaderStarts::compute(doc, &objs.waypoints).apply(doc);
+    }
+}
+
 /// Theater → base color for preview fill.
 pub fn theater_color(theater: Theater) -> egui::Color32 {
     use egui::Color32;
@@ -0,0 +1,92 @@
+use super::map_document::MapDocument;
+
+/// `[Terrain]` and `[Waypoints]` share the `y * 1000 + x` cell encoding.
+const CELL_STRIDE: i32 = 1000;
+
+/// A tree, rock or other `[Terrain]` object: `cell=TYPE`, one per cell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TerrainObject {
+    pub type_id: String,
+    pub x: i32,
+    pub y: i32,
+}
+
+/// A `[Smudge]` decal (crater, scorch mark): `ID=TYPE,X,Y,...`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Smudge {
+    pub type_id: String,
+    pub x: i32,
+    pub y: i32,
+    /// Fields after `Y`, kept verbatim (FinalAlert writes a single `0`).
+    pub extra: Vec<String>,
+}
+
+impl Smudge {
+    pub fn new(type_id: &str, x: i32, y: i32) -> Self {
+        Self { type_id: type_id.to_string(), x, y, extra: vec!["0".to_string()] }
+    }
+
+    fn to_value(&self) -> String {
+        let mut f = vec![self.type_id.clone(), self.x.to_string(), self.y.to_string()];
+        f.extend(self.extra.iter().cloned());
+        f.join(",")
+    }
+}
+
+/// Read `[Terrain]` in file order. A cell listed twice keeps its last type,
+/// like the game's INI loader.
+pub fn parse_terrain(doc: &MapDocument) -> Vec<TerrainObject> {
+    let Some(section) = doc.section("Terrain") else {
+        return Vec::new();
+    };
+    let mut out: Vec<TerrainObject> = Vec::new();
+    for e in section.entries() {
+        let Ok(cell) = e.key().parse::<i32>() else {
+            eprintln!("[backend] Ignoring bad terrain {}={}", e.key(), e.value());
+            continue;
+        };
+        let t = TerrainObject { type_id: e.value().to_string(), x: cell % CELL_STRIDE, y: cell / CELL_STRIDE };
+        match out.iter_mut().find(|o| (o.x, o.y) == (t.x, t.y)) {
+            Some(existing) => *existing = t,
+            None => out.push(t),
+        }
+    }
+    out
+}
+
+/// Read `[Smudge]` in file order. Malformed entries are skipped with a log line.
+pub fn parse_smudges(doc: &MapDocument) -> Vec<Smudge> {
+    let Some(section) = doc.section("Smudge") else {
+        return Vec::new();
+    };
+    let mut out = Vec::new();
+    for e in section.entries() {
+        let f: Vec<&str> = e.value().split(',').map(str::trim).collect();
+        let parsed = match f.as_slice() {
+            [id, x, y, rest @ ..] => x.parse().ok().zip(y.parse().ok()).map(|(x, y)| Smudge {
+                type_id: id.to_string(),
+                x,
+                y,
+                extra: rest.iter().map(|s| s.to_string()).collect(),
+            }),
+            _ => None,
+        };
+        match parsed {
+            Some(s) => out.push(s),
+            None => eprintln!("[backend] Ignoring bad smudge {}={}", e.key(), e.value()),
+        }
+    }
+    out
+}
+
+pub fn write_terrain(doc: &mut MapDocument, terrain: &[TerrainObject]) {
+    let entries: Vec<(String, String)> =
+        terrain.iter().map(|t| ((t.y * CELL_STRIDE + t.x).to_string(), t.type_id.clone())).collect();
+    doc.replace_entries_if_changed("Terrain", &entries);
+}
+
+pub fn write_smudges(doc: &mut MapDocument, smudges: &[Smudge]) {
+    let entries: Vec<(String, String)> =
+        smudges.iter().enumerate().map(|(i, s)| (i.to_string(), s.to_value())).collect();
+    doc.replace_entries_if_changed("Smudge", &entries);
+}
@@ -0,0 +1,48 @@
+use anyhow::{anyhow, bail, Result};
+
+use super::codec::{decode_base64_section, decode_format5};
+use super::map_document::MapDocument;
+
+/// The map thumbnail FinalAlert stores in `[Preview] Size=` + `[PreviewPack]`
+/// (base64 → format-5/LZO chunks → 24-bit RGB pixels, row-major).
+#[derive(Debug, Clone)]
+pub struct MapThumbnail {
+    pub width: usize,
+    pub height: usize,
+    /// `width * height * 3` bytes.
+    pub rgb: Vec<u8>,
+}
+
+impl MapThumbnail {
+    /// `Ok(None)` when the map carries no preview (or an empty 0x0 one).
+    pub fn from_document(doc: &MapDocument) -> Result<Option<Self>> {
+        let (Some(size), Some(pack)) = (doc.get("Preview", "Size"), doc.section("PreviewPack")) else {
+            return Ok(None);
+        };
+
+        // "X,Y,W,H"; only the last two matter.
+        let nums: Vec<usize> = size
+            .split(',')
+            .map(|s| s.trim().parse::<usize>())
+            .collect::<Result<_, _>>()
+            .map_err(|_| anyhow!("bad [Preview] Size={size}"))?;
+        let (width, height) = match nums.as_slice() {
+            [.., w, h] => (*w, *h),
+            _ => bail!("bad [Preview] Size={size}"),
+        };
+        if width == 0 || height == 0 {
+            return Ok(None);
+        }
+
+        let rgb = decode_format5(&decode_base64_section(pack)?)?;
+        if rgb.len() < width * height * 3 {
+            bail!("[PreviewPack] has {} bytes, expected {}", rgb.len(), width * height * 3);
+        }
+        Ok(Some(Self { width, height, rgb: rgb[..width * height * 3].to_vec() }))
+    }
+
+    /// Load a map file and decode only its thumbnail (used by the explorer).
+    pub fn load(path: &str) -> Result<Option<Self>> {
+        Self::from_document(&MapDocument::load(path)?)
+    }
+}
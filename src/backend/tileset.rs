@@ -0,0 +1,135 @@
+use anyhow::{anyhow, Result};
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use super::map_document::{parse_bool, MapDocument};
+use super::map_parser::Theater;
+use super::mix::Vfs;
+use super::tmp::Tmp;
+
+/// One `[TileSetNNNN]` of a theater control INI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TileSet {
+    /// The `NNNN` in the section name.
+    pub number: usize,
+    /// `SetName=`, e.g. `Clear`, `Cliff Pieces`.
+    pub name: String,
+    /// `FileName=`: stem of the set's files (`clear` → `clear01.tem`).
+    pub file_name: String,
+    /// Global tile number of the set's first tile.
+    pub first_tile: usize,
+    /// `TilesInSet=`
+    pub tiles: usize,
+    /// `Morphable=`: height can be raised and lowered with ramps.
+    pub morphable: bool,
+}
+
+/// Where an `[IsoMapPack5]` tile number points.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TileRef {
+    /// Index into `TheaterTiles::sets`.
+    pub set: usize,
+    /// 1-based position within the set.
+    pub tile_in_set: usize,
+    /// TMP file name, e.g. `clear01.tem`.
+    pub file: String,
+}
+
+/// The tile sets of one theater in global tile order: tile numbers count up
+/// through every set's `TilesInSet`, starting at `[TileSet0000]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TheaterTiles {
+    pub theater: Theater,
+    pub sets: Vec<TileSet>,
+}
+
+impl TheaterTiles {
+    /// Read the control INI (`temperatmd.ini` when present, else `temperat.ini`).
+    pub fn load(vfs: &Vfs, theater: Theater) -> Result<Self> {
+        let stem = theater.file_stem().ok_or_else(|| anyhow!("unknown theater"))?;
+        let (_, bytes) = vfs
+            .read(&format!("{stem}md.ini"))
+            .or_else(|| vfs.read(&format!("{stem}.ini")))
+            .ok_or_else(|| anyhow!("no {stem}.ini in the game files"))??;
+        Ok(Self::from_document(&MapDocument::from_bytes(&bytes), theater))
+    }
+
+    /// Tile sets from `[TileSet0000]` up to the first missing number.
+    pub fn from_document(doc: &MapDocument, theater: Theater) -> Self {
+        let mut sets = Vec::new();
+        let mut first_tile = 0;
+        for number in 0.. {
+            let Some(s) = doc.section(&format!("TileSet{number:04}")) else {
+                break;
+            };
+            let tiles = s.get("TilesInSet").and_then(|v| v.trim().parse().ok()).unwrap_or(0);
+            sets.push(TileSet {
+                number,
+                name: s.get("SetName").unwrap_or_default().trim().to_string(),
+                file_name: s.get("FileName").unwrap_or_default().trim().to_string(),
+                first_tile,
+                tiles,
+                morphable: s.get("Morphable").is_some_and(parse_bool),
+            });
+            first_tile += tiles;
+        }
+        Self { theater, sets }
+    }
+
+    pub fn tile_count(&self) -> usize {
+        self.sets.last().map_or(0, |s| s.first_tile + s.tiles)
+    }
+
+    /// The set and file of global tile `tile`.
+    pub fn resolve(&self, tile: usize) -> Option<TileRef> {
+        let set = self.sets.partition_point(|s| s.first_tile + s.tiles <= tile);
+        let s = self.sets.get(set)?;
+        let tile_in_set = tile - s.first_tile + 1;
+        let suffix = self.theater.suffix()?;
+        Some(TileRef { set, tile_in_set, file: format!("{}{tile_in_set:02}.{suffix}", s.file_name.to_ascii_lowercase()) })
+    }
+}
+
+/// The open map's theater tiles: its tile set index, plus TMP files decoded
+/// on first use. `None` remembers files that are missing or broken.
+#[derive(Resource, Debug, Default)]
+pub struct TileLibrary {
+    pub tiles: Option<TheaterTiles>,
+    tmps: HashMap<String, Option<Tmp>>,
+}
+
+impl TileLibrary {
+    /// Switch to `theater`, reading its control INI. Keeps the loaded files
+    /// when the theater is already current.
+    pub fn load(&mut self, vfs: &Vfs, theater: Theater) -> Result<()> {
+        if self.tiles.as_ref().is_some_and(|t| t.theater == theater) {
+            return Ok(());
+        }
+        self.clear();
+        let tiles = TheaterTiles::load(vfs, theater)?;
+        println!("[backend] {} tile sets, {} tiles for {theater:?}", tiles.sets.len(), tiles.tile_count());
+        self.tiles = Some(tiles);
+        Ok(())
+    }
+
+    /// Forget the theater and every decoded file (the game directory changed).
+    pub fn clear(&mut self) {
+        self.tiles = None;
+        self.tmps.clear();
+    }
+
+    /// Decoded TMP for global tile `tile`.
+    pub fn tmp(&mut self, vfs: &Vfs, tile: usize) -> Option<&Tmp> {
+        let file = self.tiles.as_ref()?.resolve(tile)?.file;
+        self.tmps
+            .entry(file)
+            .or_insert_with_key(|file| match vfs.read(file)?.and_then(|(_, bytes)| Tmp::parse(&bytes)) {
+                Ok(tmp) => Some(tmp),
+                Err(e) => {
+                    eprintln!("[backend] Can't read {file}: {e}");
+                    None
+                }
+            })
+            .as_ref()
+    }
+}
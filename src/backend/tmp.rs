@@ -0,0 +1,208 @@
+//! Isometric terrain tiles (`.tem`, `.sno`, `.urb`, ... TMP files) of TS/RA2.
+//!
+//! ```text
+//! i32 blocks_x | i32 blocks_y | i32 block_width | i32 block_height
+//! blocks_x * blocks_y × u32 offset of each cell's header (0 = no cell)
+//! ```
+//!
+//! Each cell header is 52 bytes; the offsets in it are relative to the header:
+//!
+//! ```text
+//! i32 x, y | i32 extra_offset, z_offset, extra_z_offset | i32 extra_x, extra_y, extra_width, extra_height
+//! u32 flags (1 = extra image, 2 = z data) | u8 height, terrain_type, ramp_type
+//! u8 radar_left[3], radar_right[3] | 3 bytes padding
+//! ```
+//!
+//! The cell image follows the header as a diamond packed row by row: rows
+//! grow by 4 pixels to the full block width, then shrink again. The extra
+//! image is a plain rectangle (cliff faces, buildings on tiles) with 0 as
+//! transparent.
+
+use anyhow::{anyhow, bail, Result};
+
+const HEADER_LEN: usize = 52;
+const FLAG_EXTRA: u32 = 1;
+const FLAG_Z: u32 = 2;
+
+/// The part of a cell that sticks out of its diamond.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TmpExtra {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    /// Palette indices, `width * height`; 0 is transparent.
+    pub pixels: Vec<u8>,
+    pub z: Option<Vec<u8>>,
+}
+
+/// One cell of a tile: the sub-tile `IsoMapPack5` refers to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TmpCell {
+    /// Position of the diamond's bounding box in the template.
+    pub x: i32,
+    pub y: i32,
+    /// Height level relative to the tile's base.
+    pub height: u8,
+    /// Land type (clear, rough, water, cliff, ...).
+    pub terrain_type: u8,
+    /// Slope shape; 0 is flat.
+    pub ramp_type: u8,
+    pub radar_left: [u8; 3],
+    pub radar_right: [u8; 3],
+    /// Diamond as a `block_width * block_height` rectangle; 0 outside it.
+    pub pixels: Vec<u8>,
+    /// Depth per pixel, same layout as `pixels`.
+    pub z: Option<Vec<u8>>,
+    pub extra: Option<TmpExtra>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tmp {
+    pub block_width: u32,
+    pub block_height: u32,
+    pub blocks_x: u32,
+    pub blocks_y: u32,
+    /// Row-major over the `blocks_x * blocks_y` template; `None` for gaps.
+    pub cells: Vec<Option<TmpCell>>,
+}
+
+fn i32_at(b: &[u8], at: usize) -> Option<i32> {
+    Some(i32::from_le_bytes(b.get(at..at + 4)?.try_into().ok()?))
+}
+
+/// Unpack a diamond stored at `bytes[pos..]` into a `width * height` rectangle.
+/// Rows are centered and grow by 4 pixels to the middle, then shrink again.
+fn diamond(bytes: &[u8], mut pos: usize, width: u32, height: u32) -> Option<Vec<u8>> {
+    let (width, height) = (width as usize, height as usize);
+    let mut out = vec![0u8; width * height];
+    for y in 0..height {
+        let w = if y < height / 2 { 4 * (y + 1) } else { 4 * (height - 1 - y) }.min(width);
+        let x = y * width + (width - w) / 2;
+        out[x..x + w].copy_from_slice(bytes.get(pos..pos + w)?);
+        pos += w;
+    }
+    Some(out)
+}
+
+impl Tmp {
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        let field = |i: usize| i32_at(bytes, i * 4).filter(|&v| v >= 0).map(|v| v as u32);
+        let (Some(bx), Some(by), Some(bw), Some(bh)) = (field(0), field(1), field(2), field(3)) else {
+            bail!("not a TMP file");
+        };
+        if bw == 0 || bh == 0 || bx.checked_mul(by).is_none_or(|n| n > 1024) {
+            bail!("not a TMP file ({bw}x{bh} blocks, {bx}x{by} template)");
+        }
+        let mut cells = Vec::with_capacity((bx * by) as usize);
+        for i in 0..(bx * by) as usize {
+            let offset = i32_at(bytes, 16 + i * 4).ok_or_else(|| anyhow!("TMP index is truncated"))?;
+            let cell = match offset {
+                0 => None,
+                o => Some(parse_cell(bytes, o as usize, bw, bh).ok_or_else(|| anyhow!("TMP cell {i} is truncated"))?),
+            };
+            cells.push(cell);
+        }
+        Ok(Self { block_width: bw, block_height: bh, blocks_x: bx, blocks_y: by, cells })
+    }
+
+    pub fn cell(&self, sub_tile: usize) -> Option<&TmpCell> {
+        self.cells.get(sub_tile)?.as_ref()
+    }
+}
+
+fn parse_cell(bytes: &[u8], at: usize, bw: u32, bh: u32) -> Option<TmpCell> {
+    let h = bytes.get(at..at + HEADER_LEN)?;
+    let field = |i: usize| i32_at(h, i * 4);
+    let flags = field(9)? as u32;
+    let pixels = diamond(bytes, at + HEADER_LEN, bw, bh)?;
+    let z = match flags & FLAG_Z {
+        0 => None,
+        _ => Some(diamond(bytes, at + field(3)? as usize, bw, bh)?),
+    };
+
+    let extra = match flags & FLAG_EXTRA {
+        0 => None,
+        _ => {
+            let (ew, eh) = (field(7)?.max(0) as u32, field(8)?.max(0) as u32);
+            let len = (ew * eh) as usize;
+            let read = |offset: i32| bytes.get(at + offset as usize..at + offset as usize + len).map(<[u8]>::to_vec);
+            Some(TmpExtra {
+                x: field(5)?,
+                y: field(6)?,
+                width: ew,
+                height: eh,
+                pixels: read(field(2)?)?,
+                z: if flags & FLAG_Z != 0 { read(field(4)?) } else { None },
+            })
+        }
+    };
+
+    Some(TmpCell {
+        x: field(0)?,
+        y: field(1)?,
+        height: h[40],
+        terrain_type: h[41],
+        ramp_type: h[42],
+        radar_left: [h[43], h[44], h[45]],
+        radar_right: [h[46], h[47], h[48]],
+        pixels,
+        z,
+        extra,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn le(values: &[i32]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+
+    /// A 1x1 template like `clear01.tem`: one 60x30 cell with z data and a 3x2 extra image.
+    fn one_cell_tile() -> Vec<u8> {
+        let mut b = le(&[1, 1, 60, 30, 20]);
+        let (z, extra, extra_z) = (52 + 900, 52 + 1800, 52 + 1806);
+        b.extend(le(&[0, 0, extra, z, extra_z, 5, -10, 3, 2, (FLAG_EXTRA | FLAG_Z) as i32]));
+        b.extend([2, 7, 1, 10, 20, 30, 40, 50, 60, 0, 0, 0]);
+        b.extend((0..900).map(|i| (i % 250 + 1) as u8));
+        b.extend([4; 900]);
+        b.extend([1, 2, 3, 4, 5, 6]);
+        b.extend([9; 6]);
+        b
+    }
+
+    #[test]
+    fn parses_a_one_cell_tile() {
+        let tmp = Tmp::parse(&one_cell_tile()).unwrap();
+        assert_eq!((tmp.blocks_x, tmp.blocks_y, tmp.block_width, tmp.block_height), (1, 1, 60, 30));
+        let cell = tmp.cell(0).unwrap();
+        assert_eq!((cell.height, cell.terrain_type, cell.ramp_type), (2, 7, 1));
+        assert_eq!((cell.radar_left, cell.radar_right), ([10, 20, 30], [40, 50, 60]));
+        assert!(tmp.cell(1).is_none());
+
+        let extra = cell.extra.as_ref().unwrap();
+        assert_eq!((extra.x, extra.y, extra.width, extra.height), (5, -10, 3, 2));
+        assert_eq!(extra.pixels, [1, 2, 3, 4, 5, 6]);
+        assert_eq!(extra.z.as_deref(), Some(&[9u8; 6][..]));
+    }
+
+    #[test]
+    fn unpacks_the_diamond_centered() {
+        let tmp = Tmp::parse(&one_cell_tile()).unwrap();
+        let cell = tmp.cell(0).unwrap();
+        // Row 0 holds the first 4 packed pixels in the middle; row 14 is full width.
+        assert_eq!(&cell.pixels[28..32], &[1, 2, 3, 4]);
+        assert_eq!((cell.pixels[27], cell.pixels[32]), (0, 0));
+        assert!(cell.pixels[14 * 60..15 * 60].iter().all(|&p| p != 0));
+        assert!(cell.pixels[29 * 60..].iter().all(|&p| p == 0));
+        assert_eq!(cell.pixels.iter().filter(|&&p| p != 0).count(), 900);
+        assert_eq!(cell.z.as_ref().unwrap()[14 * 60], 4);
+    }
+
+    #[test]
+    fn rejects_a_huge_template() {
+        assert!(Tmp::parse(&le(&[4096, 4096, 60, 30])).is_err());
+    }
+}
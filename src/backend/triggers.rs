@@ -0,0 +1,588 @@
+use super::houses::House;
+use super::map_document::MapDocument;
+use super::waypoints::Waypoint;
+
+/// `[Triggers]` placeholder for "no linked trigger".
+pub const NONE: &str = "<none>";
+
+/// A map trigger: `[Triggers]` plus its `[Events]` and `[Actions]` lines, which
+/// share the trigger's ID.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trigger {
+    pub id: String,
+    pub house: String,
+    /// Trigger ID this one is linked to, `None` for `<none>`.
+    pub linked: Option<String>,
+    pub name: String,
+    pub disabled: bool,
+    pub easy: bool,
+    pub medium: bool,
+    pub hard: bool,
+    /// Trailing field FinalAlert always writes as `0`; kept as read.
+    pub reserved: String,
+    pub events: Vec<Event>,
+    pub actions: Vec<Action>,
+}
+
+/// One event condition: `type,param type,param[,extra]`. Types the editor
+/// doesn't know are kept with their parameters untouched.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub kind: u32,
+    pub param_type: u32,
+    pub param: String,
+    /// Second parameter, present when `param_type == 2` (e.g. a tech type name).
+    pub extra: Option<String>,
+}
+
+/// One action: `type,p1..p6,waypoint`. All parameters are kept as written;
+/// [`Action::reference`] interprets the ones that point at other map objects.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Action {
+    pub kind: u32,
+    pub params: [String; 6],
+    /// Waypoint in FinalAlert's letter form (`A`, `B`, ..., `AA`); `A` when unused.
+    pub waypoint: String,
+}
+
+/// `[Tags]`: `ID=repeat,name,trigger` binds a trigger to objects and cells.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tag {
+    pub id: String,
+    /// 0 = one time, 1 = one time per object, 2 = repeating.
+    pub repeat: u32,
+    pub name: String,
+    pub trigger: String,
+}
+
+/// `[CellTags]`: `cell=tag`, cell as `y * 1000 + x` like `[Waypoints]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellTag {
+    pub x: i32,
+    pub y: i32,
+    pub tag: String,
+}
+
+/// What an action parameter points at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reference {
+    House(String),
+    Team(String),
+    Trigger(String),
+    Waypoint(u32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceKind {
+    House,
+    Team,
+    Trigger,
+    Waypoint,
+}
+
+/// Everything trigger-related in a map.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TriggerSet {
+    pub triggers: Vec<Trigger>,
+    pub tags: Vec<Tag>,
+    pub cell_tags: Vec<CellTag>,
+    /// Lines that could not be parsed, as `(section, key, value)`; written back unchanged.
+    pub unparsed: Vec<(String, String, String)>,
+}
+
+// Action types whose parameters point at other objects. Only the common ones are
+// listed; any other action keeps its parameters as plain values.
+const HOUSE_ACTIONS: [u32; 10] = [1, 2, 3, 6, 9, 13, 14, 36, 37, 38]; // p2 = house
+const TEAM_ACTIONS: [u32; 3] = [4, 5, 7]; // p2 = team ID
+const TRIGGER_ACTIONS: [u32; 4] = [12, 22, 53, 54]; // p2 = trigger ID
+const WAYPOINT_ACTIONS: [u32; 6] = [8, 17, 18, 41, 42, 48]; // waypoint field
+const TEXT_ACTIONS: [u32; 1] = [11]; // p2 = string table label
+
+// Event types whose parameter points at another object, likewise only the common ones.
+const HOUSE_EVENTS: [u32; 9] = [1, 3, 4, 5, 9, 10, 11, 24, 30]; // param = house
+const TEAM_EVENTS: [u32; 1] = [23]; // param = team ID
+
+/// Display names of the common RA2 event types (index = type ID).
+const EVENT_NAMES: [&str; 33] = [
+    "-No event-", "Entered by...", "Spied upon", "Thieved by...", "Discovered by player",
+    "House discovered...", "Attacked by any house", "Destroyed by any house", "Any event",
+    "Destroyed, units, all...", "Destroyed, buildings, all...", "Destroyed, all...",
+    "Credits exceed...", "Elapsed time...", "Mission timer expired", "Destroyed, buildings, #...",
+    "Destroyed, units, #...", "No factories left", "Civilians evacuated", "Build building type...",
+    "Build unit type...", "Build infantry type...", "Build aircraft type...", "Leaves map (team)...",
+    "Zone entry by...", "Crosses horizontal line...", "Crosses vertical line...", "Global is set...",
+    "Global is cleared...", "Destroyed by anything", "Low power...", "Bridge destroyed",
+    "Building exists...",
+];
+
+/// Display names of the common RA2 action types (index = type ID).
+const ACTION_NAMES: [&str; 58] = [
+    "-No action-", "Winner is...", "Loser is...", "Production begins...", "Create team...",
+    "Destroy team...", "All to hunt...", "Reinforcement (team)...", "Drop zone flare (waypoint)...",
+    "Fire sale...", "Play movie...", "Text trigger...", "Destroy trigger...", "Autocreate begins...",
+    "Change house...", "Allow win", "Reveal all map", "Reveal around waypoint...",
+    "Reveal zone of waypoint...", "Play sound effect...", "Play music theme...", "Play speech...",
+    "Force trigger...", "Timer start", "Timer stop", "Timer extend...", "Timer shorten...",
+    "Timer set...", "Global set...", "Global clear...", "Auto base building...",
+    "Grow shroud one step", "Destroy attached building", "Add 1-time super weapon...",
+    "Add repeating super weapon...", "Preferred target...", "All change house...", "Make ally...",
+    "Make enemy...", "Change zoom level...", "Resize player view...", "Play anim at...",
+    "Do explosion at...", "Create voxel anim...", "Ion storm start...", "Ion storm stop",
+    "Lock input", "Unlock input", "Move camera to waypoint...", "Zoom in", "Zoom out",
+    "Reshroud map", "Change light behavior...", "Enable trigger...", "Disable trigger...",
+    "Create radar event...", "Local set...", "Local clear...",
+];
+
+/// Name of an event type, `None` for IDs the editor has no name for.
+pub fn event_name(kind: u32) -> Option<&'static str> {
+    EVENT_NAMES.get(kind as usize).copied()
+}
+
+pub fn action_name(kind: u32) -> Option<&'static str> {
+    ACTION_NAMES.get(kind as usize).copied()
+}
+
+/// Number of named event/action types (the editor's dropdowns list `0..count`).
+pub fn named_event_count() -> u32 {
+    EVENT_NAMES.len() as u32
+}
+
+pub fn named_action_count() -> u32 {
+    ACTION_NAMES.len() as u32
+}
+
+impl Trigger {
+    pub fn new(id: &str, house: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            house: house.to_string(),
+            linked: None,
+            name: "New trigger".to_string(),
+            disabled: false,
+            easy: true,
+            medium: true,
+            hard: true,
+            reserved: "0".to_string(),
+            events: Vec::new(),
+            actions: Vec::new(),
+        }
+    }
+
+    fn parse(id: &str, value: &str) -> Option<Self> {
+        let f: Vec<&str> = value.split(',').map(str::trim).collect();
+        let [house, linked, name, disabled, easy, medium, hard, rest @ ..] = f.as_slice() else {
+            return None;
+        };
+        let flag = |v: &str| v.parse::<u32>().ok().map(|n| n != 0);
+        Some(Self {
+            id: id.to_string(),
+            house: house.to_string(),
+            linked: (!linked.eq_ignore_ascii_case(NONE)).then(|| linked.to_string()),
+            name: name.to_string(),
+            disabled: flag(disabled)?,
+            easy: flag(easy)?,
+            medium: flag(medium)?,
+            hard: flag(hard)?,
+            reserved: rest.first().unwrap_or(&"0").to_string(),
+            events: Vec::new(),
+            actions: Vec::new(),
+        })
+    }
+
+    fn to_value(&self) -> String {
+        let b = |v: bool| if v { "1" } else { "0" };
+        format!(
+            "{},{},{},{},{},{},{},{}",
+            self.house,
+            self.linked.as_deref().unwrap_or(NONE),
+            self.name,
+            b(self.disabled),
+            b(self.easy),
+            b(self.medium),
+            b(self.hard),
+            self.reserved,
+        )
+    }
+}
+
+impl Event {
+    pub fn new(kind: u32) -> Self {
+        Self { kind, param_type: 0, param: "0".to_string(), extra: None }
+    }
+
+    /// Kind of object `param` points at for this event type.
+    pub fn reference_kind(kind: u32) -> Option<ReferenceKind> {
+        if HOUSE_EVENTS.contains(&kind) {
+            Some(ReferenceKind::House)
+        } else if TEAM_EVENTS.contains(&kind) {
+            Some(ReferenceKind::Team)
+        } else {
+            None
+        }
+    }
+}
+
+impl Action {
+    pub fn new(kind: u32) -> Self {
+        Self { kind, params: std::array::from_fn(|_| "0".to_string()), waypoint: "A".to_string() }
+    }
+
+    /// Kind of object `params[1]` or the waypoint field points at for this action type.
+    pub fn reference_kind(kind: u32) -> Option<ReferenceKind> {
+        if HOUSE_ACTIONS.contains(&kind) {
+            Some(ReferenceKind::House)
+        } else if TEAM_ACTIONS.contains(&kind) {
+            Some(ReferenceKind::Team)
+        } else if TRIGGER_ACTIONS.contains(&kind) {
+            Some(ReferenceKind::Trigger)
+        } else if WAYPOINT_ACTIONS.contains(&kind) {
+            Some(ReferenceKind::Waypoint)
+        } else {
+            None
+        }
+    }
+
+    /// Whether `params[1]` of this action type is a string table label.
+    pub fn takes_text(kind: u32) -> bool {
+        TEXT_ACTIONS.contains(&kind)
+    }
+
+    /// The map object this action refers to, if its type is a known one.
+    pub fn reference(&self) -> Option<Reference> {
+        let p2 = self.params[1].clone();
+        match Self::reference_kind(self.kind)? {
+            ReferenceKind::House => Some(Reference::House(p2)),
+            ReferenceKind::Team => Some(Reference::Team(p2)),
+            ReferenceKind::Trigger => Some(Reference::Trigger(p2)),
+            ReferenceKind::Waypoint => letters_to_waypoint(&self.waypoint).map(Reference::Waypoint),
+        }
+    }
+}
+
+/// Waypoint number → FinalAlert letters (0 = `A`, 25 = `Z`, 26 = `AA`, ...).
+pub fn waypoint_to_letters(n: u32) -> String {
+    let mut n = n as u64 + 1;
+    let mut out = Vec::new();
+    while n > 0 {
+        n -= 1;
+        out.push(b'A' + (n % 26) as u8);
+        n /= 26;
+    }
+    out.reverse();
+    String::from_utf8(out).unwrap_or_default()
+}
+
+/// Inverse of [`waypoint_to_letters`]; `None` for anything but `A`-`Z` letters.
+pub fn letters_to_waypoint(s: &str) -> Option<u32> {
+    let s = s.trim();
+    if s.is_empty() {
+        return None;
+    }
+    let mut n: u32 = 0;
+    for c in s.chars() {
+        let c = c.to_ascii_uppercase();
+        if !c.is_ascii_uppercase() {
+            return None;
+        }
+        n = n.checked_mul(26)?.checked_add(c as u32 - 'A' as u32 + 1)?;
+    }
+    Some(n - 1)
+}
+
+fn parse_events(value: &str) -> Option<Vec<Event>> {
+    let f: Vec<&str> = value.split(',').map(str::trim).collect();
+    let count: usize = f.first()?.parse().ok()?;
+    let mut i = 1;
+    let mut out = Vec::with_capacity(count);
+    for _ in 0..count {
+        let kind = f.get(i)?.parse().ok()?;
+        let param_type: u32 = f.get(i + 1)?.parse().ok()?;
+        let param = f.get(i + 2)?.to_string();
+        i += 3;
+        let extra = if param_type == 2 {
+            i += 1;
+            Some(f.get(i - 1)?.to_string())
+        } else {
+            None
+        };
+        out.push(Event { kind, param_type, param, extra });
+    }
+    (i == f.len()).then_some(out)
+}
+
+fn events_value(events: &[Event]) -> String {
+    let mut out = events.len().to_string();
+    for e in events {
+        out.push_str(&format!(",{},{},{}", e.kind, e.param_type, e.param));
+        if let Some(x) = &e.extra {
+            out.push_str(&format!(",{x}"));
+        }
+    }
+    out
+}
+
+fn parse_actions(value: &str) -> Option<Vec<Action>> {
+    let f: Vec<&str> = value.split(',').map(str::trim).collect();
+    let count: usize = f.first()?.parse().ok()?;
+    if f.len() != 1 + count * 8 {
+        return None;
+    }
+    f[1..]
+        .chunks(8)
+        .map(|a| {
+            Some(Action {
+                kind: a[0].parse().ok()?,
+                params: std::array::from_fn(|i| a[i + 1].to_string()),
+                waypoint: a[7].to_string(),
+            })
+        })
+        .collect()
+}
+
+fn actions_value(actions: &[Action]) -> String {
+    let mut out = actions.len().to_string();
+    for a in actions {
+        out.push_str(&format!(",{},{},{}", a.kind, a.params.join(","), a.waypoint));
+    }
+    out
+}
+
+impl TriggerSet {
+    pub fn from_document(doc: &MapDocument) -> Self {
+        let mut set = Self::default();
+        let entries = |name: &str| -> Vec<(String, String)> {
+            doc.section(name)
+                .map(|s| s.entries().map(|e| (e.key().to_string(), e.value().to_string())).collect())
+                .unwrap_or_default()
+        };
+
+        for (id, value) in entries("Triggers") {
+            match Trigger::parse(&id, &value) {
+                Some(t) => set.triggers.push(t),
+                None => set.keep("Triggers", id, value),
+            }
+        }
+        for (id, value) in entries("Events") {
+            match (set.trigger_index(&id), parse_events(&value)) {
+                (Some(i), Some(events)) => set.triggers[i].events = events,
+                _ => set.keep("Events", id, value),
+            }
+        }
+        for (id, value) in entries("Actions") {
+            match (set.trigger_index(&id), parse_actions(&value)) {
+                (Some(i), Some(actions)) => set.triggers[i].actions = actions,
+                _ => set.keep("Actions", id, value),
+            }
+        }
+        for (id, value) in entries("Tags") {
+            let f: Vec<&str> = value.split(',').map(str::trim).collect();
+            match (f.as_slice(), f.first().and_then(|r| r.parse().ok())) {
+                ([_, name, trigger], Some(repeat)) => set.tags.push(Tag {
+                    id,
+                    repeat,
+                    name: name.to_string(),
+                    trigger: trigger.to_string(),
+                }),
+                _ => set.keep("Tags", id, value),
+            }
+        }
+        for (cell, tag) in entries("CellTags") {
+            match cell.parse::<i32>() {
+                Ok(c) => set.cell_tags.push(CellTag { x: c % 1000, y: c / 1000, tag }),
+                Err(_) => set.keep("CellTags", cell, tag),
+            }
+        }
+        set
+    }
+
+    fn keep(&mut self, section: &str, key: String, value: String) {
+        eprintln!("[backend] Keeping unparsed [{section}] {key}={value}");
+        self.unparsed.push((section.to_string(), key, value));
+    }
+
+    /// Rewrite all five sections from the model.
+    pub fn apply(&self, doc: &mut MapDocument) {
+        // A trigger whose events/actions didn't parse keeps the original line instead.
+        let kept = |section: &str, id: &str| {
+            self.unparsed.iter().any(|(s, k, _)| s == section && k.eq_ignore_ascii_case(id))
+        };
+        let mut lines: Vec<(&str, String, String)> = Vec::new();
+        for t in &self.triggers {
+            lines.push(("Triggers", t.id.clone(), t.to_value()));
+            if !kept("Events", &t.id) {
+                lines.push(("Events", t.id.clone(), events_value(&t.events)));
+            }
+            if !kept("Actions", &t.id) {
+                lines.push(("Actions", t.id.clone(), actions_value(&t.actions)));
+            }
+        }
+        for tag in &self.tags {
+            lines.push(("Tags", tag.id.clone(), format!("{},{},{}", tag.repeat, tag.name, tag.trigger)));
+        }
+        for c in &self.cell_tags {
+            lines.push(("CellTags", (c.y * 1000 + c.x).to_string(), c.tag.clone()));
+        }
+        for (section, key, value) in &self.unparsed {
+            lines.push((section.as_str(), key.clone(), value.clone()));
+        }
+
+        for name in ["Triggers", "Events", "Actions", "Tags", "CellTags"] {
+            let mine: Vec<_> = lines.iter().filter(|(s, ..)| s.eq_ignore_ascii_case(name)).collect();
+            if mine.is_empty() && !doc.has_section(name) {
+                continue;
+            }
+            let section = doc.ensure_section(name);
+            section.clear_entries();
+            for (_, key, value) in mine {
+                section.push(key, value);
+            }
+        }
+    }
+
+    fn trigger_index(&self, id: &str) -> Option<usize> {
+        self.triggers.iter().position(|t| t.id.eq_ignore_ascii_case(id))
+    }
+
+    pub fn trigger(&self, id: &str) -> Option<&Trigger> {
+        self.trigger_index(id).map(|i| &self.triggers[i])
+    }
+
+    pub fn tag(&self, id: &str) -> Option<&Tag> {
+        self.tags.iter().find(|t| t.id.eq_ignore_ascii_case(id))
+    }
+
+    /// Tags that fire `trigger_id`.
+    pub fn tags_for_trigger<'a>(&'a self, trigger_id: &'a str) -> impl Iterator<Item = &'a Tag> + 'a {
+        self.tags.iter().filter(move |t| t.trigger.eq_ignore_ascii_case(trigger_id))
+    }
+
+    /// Lowest free `01000000`-style ID (FinalAlert's numbering) across triggers,
+    /// tags and whatever else `taken` reports (the IDs share one namespace).
+    pub fn next_id(&self, taken: impl Fn(&str) -> bool) -> String {
+        (1_000_000u32..)
+            .map(|n| format!("{n:08}"))
+            .find(|id| self.trigger(id).is_none() && self.tag(id).is_none() && !taken(id))
+            .unwrap_or_default()
+    }
+
+    /// Dangling references, one message each. Houses may be referenced by name or
+    /// by their `[Houses]` index; `teams` are the map's TeamType IDs.
+    pub fn validate(&self, houses: &[House], waypoints: &[Waypoint], teams: &[String]) -> Vec<String> {
+        let house_ok = |h: &str| {
+            houses.iter().any(|x| x.name.eq_ignore_ascii_case(h))
+                || h.parse::<usize>().is_ok_and(|i| i < houses.len())
+        };
+        let mut errors = Vec::new();
+
+        for t in &self.triggers {
+            if !house_ok(&t.house) {
+                errors.push(format!("Trigger {} ({}): unknown house {}", t.id, t.name, t.house));
+            }
+            if let Some(l) = &t.linked
+                && self.trigger(l).is_none()
+            {
+                errors.push(format!("Trigger {} ({}): linked trigger {l} does not exist", t.id, t.name));
+            }
+            for (n, a) in t.actions.iter().enumerate() {
+                let missing = match a.reference() {
+                    Some(Reference::House(h)) if !house_ok(&h) => Some(format!("house {h}")),
+                    Some(Reference::Team(id)) if !teams.iter().any(|x| x.eq_ignore_ascii_case(&id)) => {
+                        Some(format!("team {id}"))
+                    }
+                    Some(Reference::Trigger(id)) if self.trigger(&id).is_none() => Some(format!("trigger {id}")),
+                    Some(Reference::Waypoint(w)) if !waypoints.iter().any(|x| x.number == w) => {
+                        Some(format!("waypoint {w}"))
+                    }
+                    _ => None,
+                };
+                if let Some(m) = missing {
+                    errors.push(format!("Trigger {} ({}): action {} refers to missing {m}", t.id, t.name, n + 1));
+                }
+            }
+        }
+        for tag in &self.tags {
+            if self.trigger(&tag.trigger).is_none() {
+                errors.push(format!("Tag {} ({}): trigger {} does not exist", tag.id, tag.name, tag.trigger));
+            }
+        }
+        for c in &self.cell_tags {
+            if self.tag(&c.tag).is_none() {
+                errors.push(format!("CellTag at {},{}: tag {} does not exist", c.x, c.y, c.tag));
+            }
+        }
+        errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two triggers with every section filled in. Event 999 and action 200 are
+    /// types the editor has no name for.
+    const MISSION: &str = "\
+[Triggers]
+01000000=Americans,<none>,Intro,0,1,1,1,0
+01000001=Soviets,01000000,Strange,0,1,0,1,0
+[Events]
+01000000=1,13,0,5
+01000001=2,999,0,7,19,2,0,HTNK
+[Actions]
+01000000=1,11,4,Txt:Intro,0,0,0,0,A
+01000001=2,200,1,2,3,4,5,6,B,12,0,01000000,0,0,0,0,A
+[Tags]
+01000002=0,Intro 1,01000000
+01000003=2,Strange 1,01000001
+[CellTags]
+20050=01000002
+";
+
+    #[test]
+    fn sample_trigger_sections_round_trip() {
+        let mut doc = MapDocument::load("src/samplemap/sample.map").unwrap();
+        let before = doc.to_string();
+        let set = TriggerSet::from_document(&doc);
+        assert!(set.unparsed.is_empty());
+        set.apply(&mut doc);
+        assert_eq!(doc.to_string(), before);
+    }
+
+    #[test]
+    fn unknown_types_are_kept_raw() {
+        let mut doc = MapDocument::parse(MISSION);
+        let set = TriggerSet::from_document(&doc);
+        assert!(set.unparsed.is_empty());
+        let strange = set.trigger("01000001").unwrap();
+        assert_eq!(strange.linked.as_deref(), Some("01000000"));
+        assert_eq!(strange.events[0], Event { kind: 999, param_type: 0, param: "7".to_string(), extra: None });
+        assert_eq!(strange.events[1].extra.as_deref(), Some("HTNK"));
+        assert_eq!(event_name(999), None);
+        assert_eq!(strange.actions[0].kind, 200);
+        assert_eq!(strange.actions[0].params.join(","), "1,2,3,4,5,6");
+        assert_eq!(strange.actions[0].waypoint, "B");
+        assert_eq!(strange.actions[1].reference(), Some(Reference::Trigger("01000000".to_string())));
+        assert_eq!(set.cell_tags, [CellTag { x: 50, y: 20, tag: "01000002".to_string() }]);
+
+        set.apply(&mut doc);
+        assert_eq!(doc.to_string(), MISSION);
+    }
+
+    #[test]
+    fn validate_reports_dangling_references() {
+        let mut set = TriggerSet::from_document(&MapDocument::parse(MISSION));
+        let houses = [House::new("Americans"), House::new("Soviets")];
+        assert_eq!(set.validate(&houses, &[], &[]), Vec::<String>::new());
+
+        set.tags.retain(|t| t.id != "01000002");
+        set.triggers[1].actions[1].params[1] = "01000009".to_string();
+        let errors = set.validate(&houses[..1], &[], &[]);
+        assert_eq!(
+            errors,
+            [
+                "Trigger 01000001 (Strange): unknown house Soviets",
+                "Trigger 01000001 (Strange): action 2 refers to missing trigger 01000009",
+                "CellTag at 50,20: tag 01000002 does not exist",
+            ]
+        );
+    }
+}
@@ -0,0 +1,207 @@
+//! Software renderer for voxel models, so vehicles can be drawn as sprites
+//! without a GPU.
+//!
+//! Models are turned to the map facing, lit, and projected the way the game
+//! looks at the map: ground axes go to `(1, ½)` and `(-1, ½)` on screen, one
+//! voxel per pixel, and height is foreshortened by a 30° camera. Every voxel
+//! is splatted as a small square into a depth buffer.
+//!
+//! `Voxel::normal` indexes the game's normal tables (36 vectors for TS, 244
+//! for RA2). They aren't bundled yet, so for now each voxel's normal is
+//! estimated from which of its neighbors are empty.
+
+use super::hva::Hva;
+use super::palette::Palette;
+use super::vxl::{Matrix, Vxl, VxlLimb};
+
+/// Light scale for faces turned away from the light, and the extra for facing it.
+const AMBIENT: f32 = 0.6;
+const DIFFUSE: f32 = 0.7;
+/// Screen pixels per unit of height: `√2 · cos 30°`.
+const HEIGHT_SCALE: f32 = 1.2247;
+
+/// Rendered model, `width * height` RGBA with the model's origin at the center.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VoxelImage {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+/// Map facing `facing` (0-255, 0 = north, clockwise) snapped to one of `facings` directions.
+pub fn snap_facing(facing: u8, facings: u32) -> u8 {
+    let step = 256 / facings.clamp(1, 256);
+    (((facing as u32 + step / 2) / step * step) % 256) as u8
+}
+
+/// A voxel after transform: screen position, depth and color.
+struct Splat {
+    x: f32,
+    y: f32,
+    depth: f32,
+    size: f32,
+    rgb: [u8; 3],
+}
+
+/// Render `models` (body, turret, barrel, ...) together at `facing`, using
+/// frame 0 of each model's HVA, or the VXL rest transforms without one.
+pub fn render_voxels(models: &[(&Vxl, Option<&Hva>)], facing: u8, palette: &Palette) -> Option<VoxelImage> {
+    let angle = (facing as f32 - 64.0) / 256.0 * std::f32::consts::TAU;
+    let (sin, cos) = angle.sin_cos();
+    let turn = |v: [f32; 3]| [v[0] * cos - v[1] * sin, v[0] * sin + v[1] * cos, v[2]];
+    let light = normalize([-1.0, -0.5, 1.5]);
+
+    let mut splats = Vec::new();
+    for (vxl, hva) in models {
+        for (i, limb) in vxl.limbs.iter().enumerate() {
+            let mut m = *hva.and_then(|h| h.transform(0, i)).unwrap_or(&limb.transform);
+            for row in &mut m {
+                row[3] *= limb.scale;
+            }
+            let scale = limb.voxel_scale();
+            let size = scale.iter().fold(0.0f32, |a, &b| a.max(b)) * 1.5;
+            let normals = estimate_normals(limb);
+            for (v, n) in limb.voxels.iter().zip(normals) {
+                let p = [v.x, v.y, v.z].map(f32::from);
+                let local = [0, 1, 2].map(|k| limb.min_bounds[k] + (p[k] + 0.5) * scale[k]);
+                let w = turn(apply(&m, local, true));
+                let n = normalize(turn(apply(&m, n, false)));
+                let lit = AMBIENT + DIFFUSE * dot(n, light).max(0.0);
+                let rgb = palette.colors[v.color as usize].map(|c| (c as f32 * lit).min(255.0) as u8);
+                splats.push(Splat {
+                    x: w[0] - w[1],
+                    y: (w[0] + w[1]) * 0.5 - w[2] * HEIGHT_SCALE,
+                    // Toward the camera: down the screen and up.
+                    depth: (w[0] + w[1]) * 0.612 + w[2] * 0.5,
+                    size,
+                    rgb,
+                });
+            }
+        }
+    }
+    if splats.is_empty() {
+        return None;
+    }
+
+    let half_w = splats.iter().map(|s| s.x.abs() + s.size).fold(0.0f32, f32::max).ceil() as i32 + 1;
+    let half_h = splats.iter().map(|s| s.y.abs() + s.size).fold(0.0f32, f32::max).ceil() as i32 + 1;
+    let (width, height) = ((half_w * 2) as usize, (half_h * 2) as usize);
+    let mut rgba = vec![0u8; width * height * 4];
+    let mut depth = vec![f32::MIN; width * height];
+    for s in &splats {
+        let x0 = (s.x - s.size * 0.5).round() as i32 + half_w;
+        let y0 = (s.y - s.size * 0.5).round() as i32 + half_h;
+        let n = s.size.round().max(1.0) as i32;
+        for y in y0.max(0)..(y0 + n).min(height as i32) {
+            for x in x0.max(0)..(x0 + n).min(width as i32) {
+                let at = y as usize * width + x as usize;
+                if s.depth > depth[at] {
+                    depth[at] = s.depth;
+                    rgba[at * 4..at * 4 + 4].copy_from_slice(&[s.rgb[0], s.rgb[1], s.rgb[2], 255]);
+                }
+            }
+        }
+    }
+    Some(VoxelImage { width: width as u32, height: height as u32, rgba })
+}
+
+/// Per-voxel normals pointing away from the filled neighbors.
+fn estimate_normals(limb: &VxlLimb) -> Vec<[f32; 3]> {
+    let [xs, ys, zs] = limb.size.map(|s| s as i32);
+    let index = |x: i32, y: i32, z: i32| (z * ys * xs + y * xs + x) as usize;
+    let mut filled = vec![false; (xs * ys * zs) as usize];
+    for v in &limb.voxels {
+        filled[index(v.x as i32, v.y as i32, v.z as i32)] = true;
+    }
+    let solid = |x: i32, y: i32, z: i32| {
+        (0..xs).contains(&x) && (0..ys).contains(&y) && (0..zs).contains(&z) && filled[index(x, y, z)]
+    };
+    limb.voxels
+        .iter()
+        .map(|v| {
+            let (x, y, z) = (v.x as i32, v.y as i32, v.z as i32);
+            let mut n = [0.0f32; 3];
+            for dz in -1..=1 {
+                for dy in -1..=1 {
+                    for dx in -1..=1 {
+                        if !solid(x + dx, y + dy, z + dz) {
+                            n[0] += dx as f32;
+                            n[1] += dy as f32;
+                            n[2] += dz as f32;
+                        }
+                    }
+                }
+            }
+            if n == [0.0; 3] { [0.0, 0.0, 1.0] } else { normalize(n) }
+        })
+        .collect()
+}
+
+/// `m · v`, with the translation for points and without it for directions.
+fn apply(m: &Matrix, v: [f32; 3], point: bool) -> [f32; 3] {
+    let t = if point { 1.0 } else { 0.0 };
+    m.map(|r| r[0] * v[0] + r[1] * v[1] + r[2] * v[2] + r[3] * t)
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn normalize(v: [f32; 3]) -> [f32; 3] {
+    let len = dot(v, v).sqrt().max(f32::EPSILON);
+    v.map(|c| c / len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::vxl::Voxel;
+
+    const IDENTITY: Matrix = [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0]];
+
+    fn limb(voxels: Vec<Voxel>) -> VxlLimb {
+        VxlLimb {
+            name: "body".to_string(),
+            scale: 1.0,
+            transform: IDENTITY,
+            min_bounds: [0.0; 3],
+            max_bounds: [2.0, 1.0, 1.0],
+            size: [2, 1, 1],
+            normal_type: 4,
+            voxels,
+        }
+    }
+
+    #[test]
+    fn facings_snap_to_the_nearest_direction() {
+        assert_eq!(snap_facing(0, 8), 0);
+        assert_eq!(snap_facing(15, 8), 0);
+        assert_eq!(snap_facing(16, 8), 32);
+        assert_eq!(snap_facing(250, 8), 0);
+        assert_eq!(snap_facing(100, 32), 104);
+        assert_eq!(snap_facing(201, 256), 201);
+    }
+
+    #[test]
+    fn renders_one_voxel_in_its_palette_color() {
+        let mut palette = Palette { colors: [[0; 3]; 256] };
+        palette.colors[5] = [200, 100, 50];
+        let vxl = Vxl { limbs: vec![limb(vec![Voxel { x: 0, y: 0, z: 0, color: 5, normal: 0 }])] };
+        let image = render_voxels(&[(&vxl, None)], 0, &palette).unwrap();
+        assert_eq!(image.rgba.len(), (image.width * image.height * 4) as usize);
+
+        let lit: Vec<&[u8]> = image.rgba.chunks_exact(4).filter(|p| p[3] == 255).collect();
+        assert!(!lit.is_empty());
+        assert!(lit.iter().all(|p| *p == lit[0]));
+        // Lighting scales the color without changing its hue.
+        let [r, g, b] = [lit[0][0], lit[0][1], lit[0][2]].map(f32::from);
+        assert!((r / g - 2.0).abs() < 0.1 && (g / b - 2.0).abs() < 0.1, "{:?}", lit[0]);
+    }
+
+    #[test]
+    fn an_empty_model_renders_nothing() {
+        let palette = Palette { colors: [[0; 3]; 256] };
+        let vxl = Vxl { limbs: vec![limb(Vec::new())] };
+        assert!(render_voxels(&[(&vxl, None)], 0, &palette).is_none());
+    }
+}
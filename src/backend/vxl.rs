@@ -0,0 +1,207 @@
+//! Voxel models (`.vxl`) of Tiberian Sun and RA2.
+//!
+//! ```text
+//! char[16] "Voxel Animation" | u32 palette_count | u32 limb_count | u32 tailer_count | u32 body_size
+//! u8 remap_start | u8 remap_end | 768 bytes palette
+//! limb_count × 28-byte headers: char[16] name | u32 number | u32 × 2 unknown
+//! body_size bytes of span data
+//! limb_count × 92-byte tailers:
+//!   u32 span_start_offset, span_end_offset, span_data_offset (relative to the body)
+//!   f32 scale | f32 transform[3][4] | f32 min_bounds[3], max_bounds[3]
+//!   u8 x_size, y_size, z_size, normal_type
+//! ```
+//!
+//! Each limb is a `x_size × y_size` grid of columns with one i32 start and end
+//! offset per column (-1 = empty). A column is a run of spans:
+//! `u8 skip | u8 count | count × (u8 color, u8 normal) | u8 count`.
+
+use anyhow::{bail, Result};
+
+const HEADER_LEN: usize = 802;
+const LIMB_HEADER_LEN: usize = 28;
+const TAILER_LEN: usize = 92;
+
+/// A 3×4 row-major transform: rotation in the first three columns, translation in the last.
+pub type Matrix = [[f32; 4]; 3];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Voxel {
+    pub x: u8,
+    pub y: u8,
+    pub z: u8,
+    /// Palette index.
+    pub color: u8,
+    /// Index into the normal table named by the limb's `normal_type`.
+    pub normal: u8,
+}
+
+/// One independently animated part of a model (body, turret, barrel, ...).
+#[derive(Debug, Clone, PartialEq)]
+pub struct VxlLimb {
+    pub name: String,
+    /// Multiplier for the translation of HVA transforms.
+    pub scale: f32,
+    /// Rest transform; the model's `.hva` replaces it.
+    pub transform: Matrix,
+    pub min_bounds: [f32; 3],
+    pub max_bounds: [f32; 3],
+    pub size: [u8; 3],
+    /// 2 for TS (36 normals), 4 for RA2 (244 normals).
+    pub normal_type: u8,
+    pub voxels: Vec<Voxel>,
+}
+
+impl VxlLimb {
+    /// Model-space size of one voxel along each axis.
+    pub fn voxel_scale(&self) -> [f32; 3] {
+        [0, 1, 2].map(|i| (self.max_bounds[i] - self.min_bounds[i]) / self.size[i].max(1) as f32)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Vxl {
+    pub limbs: Vec<VxlLimb>,
+}
+
+fn u32_at(b: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(b.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn f32_at(b: &[u8], at: usize) -> Option<f32> {
+    u32_at(b, at).map(f32::from_bits)
+}
+
+/// NUL-terminated name from a fixed 16-byte field.
+pub(crate) fn name_at(b: &[u8], at: usize) -> Option<String> {
+    let raw = b.get(at..at + 16)?;
+    let end = raw.iter().position(|&c| c == 0).unwrap_or(raw.len());
+    Some(String::from_utf8_lossy(&raw[..end]).into_owned())
+}
+
+impl Vxl {
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        if !bytes.starts_with(b"Voxel Animation") || bytes.len() < HEADER_LEN {
+            bail!("not a VXL file");
+        }
+        let (Some(limbs), Some(body_size)) = (u32_at(bytes, 20), u32_at(bytes, 28)) else {
+            bail!("not a VXL file");
+        };
+        let (limbs, body_size) = (limbs as usize, body_size as usize);
+        let body_at = HEADER_LEN + limbs * LIMB_HEADER_LEN;
+        let tailers_at = body_at + body_size;
+        if limbs > 256 || bytes.len() < tailers_at + limbs * TAILER_LEN {
+            bail!("VXL file is truncated");
+        }
+        let body = &bytes[body_at..tailers_at];
+        let mut out = Vec::with_capacity(limbs);
+        for i in 0..limbs {
+            let name = name_at(bytes, HEADER_LEN + i * LIMB_HEADER_LEN).unwrap_or_default();
+            let tailer = &bytes[tailers_at + i * TAILER_LEN..tailers_at + (i + 1) * TAILER_LEN];
+            let limb = parse_limb(name, tailer, body).ok_or_else(|| anyhow::anyhow!("VXL limb {i} is truncated"))?;
+            out.push(limb);
+        }
+        Ok(Self { limbs: out })
+    }
+}
+
+fn parse_limb(name: String, t: &[u8], body: &[u8]) -> Option<VxlLimb> {
+    let f = |i: usize| f32_at(t, 12 + i * 4);
+    let mut transform = [[0.0; 4]; 3];
+    for (r, row) in transform.iter_mut().enumerate() {
+        for (c, v) in row.iter_mut().enumerate() {
+            *v = f(1 + r * 4 + c)?;
+        }
+    }
+    let size = [t[88], t[89], t[90]];
+    let mut limb = VxlLimb {
+        name,
+        scale: f(0)?,
+        transform,
+        min_bounds: [f(13)?, f(14)?, f(15)?],
+        max_bounds: [f(16)?, f(17)?, f(18)?],
+        size,
+        normal_type: t[91],
+        voxels: Vec::new(),
+    };
+
+    let (starts, data) = (u32_at(t, 0)? as usize, u32_at(t, 8)? as usize);
+    let (xs, ys, zs) = (size[0] as usize, size[1] as usize, size[2] as usize);
+    for column in 0..xs * ys {
+        let start = u32_at(body, starts + column * 4)?;
+        if start == u32::MAX {
+            continue;
+        }
+        let (x, y) = ((column % xs) as u8, (column / xs) as u8);
+        let mut pos = data + start as usize;
+        let mut z = 0;
+        while z < zs {
+            z += *body.get(pos)? as usize;
+            let count = *body.get(pos + 1)? as usize;
+            let span = body.get(pos + 2..pos + 2 + count * 2)?;
+            for (k, v) in span.chunks_exact(2).enumerate() {
+                if z + k < zs {
+                    limb.voxels.push(Voxel { x, y, z: (z + k) as u8, color: v[0], normal: v[1] });
+                }
+            }
+            z += count;
+            // Spans end with their count repeated.
+            pos += 3 + count * 2;
+        }
+    }
+    Some(limb)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 1×2×4 limb: column (0, 0) holds one span of two voxels at z 1-2 and
+    /// an empty trailing span; column (0, 1) is empty.
+    fn one_limb_model() -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(&u32::MAX.to_le_bytes());
+        body.extend_from_slice(&9u32.to_le_bytes());
+        body.extend_from_slice(&u32::MAX.to_le_bytes());
+        body.extend_from_slice(&[1, 2, 10, 3, 11, 4, 2, 1, 0, 0]);
+
+        let mut bytes = b"Voxel Animation\0".to_vec();
+        for n in [1u32, 1, 1, body.len() as u32] {
+            bytes.extend_from_slice(&n.to_le_bytes());
+        }
+        bytes.resize(HEADER_LEN, 0);
+        let mut name = b"body".to_vec();
+        name.resize(LIMB_HEADER_LEN, 0);
+        bytes.extend_from_slice(&name);
+        bytes.extend_from_slice(&body);
+
+        for n in [0u32, 8, 16] {
+            bytes.extend_from_slice(&n.to_le_bytes());
+        }
+        let identity = [1.0f32, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+        for f in [1.0f32].iter().chain(&identity).chain(&[0.0, 0.0, 0.0, 1.0, 2.0, 4.0]) {
+            bytes.extend_from_slice(&f.to_le_bytes());
+        }
+        bytes.extend_from_slice(&[1, 2, 4, 4]);
+        bytes
+    }
+
+    #[test]
+    fn parses_a_span_column() {
+        let vxl = Vxl::parse(&one_limb_model()).unwrap();
+        let [limb] = vxl.limbs.as_slice() else { panic!("expected one limb") };
+        assert_eq!(limb.name, "body");
+        assert_eq!((limb.size, limb.normal_type), ([1, 2, 4], 4));
+        assert_eq!(limb.voxel_scale(), [1.0, 1.0, 1.0]);
+        assert_eq!(
+            limb.voxels,
+            [Voxel { x: 0, y: 0, z: 1, color: 10, normal: 3 }, Voxel { x: 0, y: 0, z: 2, color: 11, normal: 4 }]
+        );
+    }
+
+    #[test]
+    fn rejects_a_truncated_model() {
+        let bytes = one_limb_model();
+        assert!(Vxl::parse(&bytes[..bytes.len() - 1]).is_err());
+    }
+}
@@ -0,0 +1,66 @@
+use super::map_document::MapDocument;
+
+/// `[Waypoints]` stores cells as one number: `y * 1000 + x`.
+const CELL_STRIDE: i32 = 1000;
+/// Waypoints 0-7 are the multiplayer start locations (players 1-8).
+pub const START_WAYPOINTS: u32 = 8;
+
+/// A numbered `[Waypoints]` entry on an iso cell. The number is what triggers,
+/// scripts and teams refer to, so the editor never renumbers existing ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Waypoint {
+    pub number: u32,
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Waypoint {
+    pub fn is_start_location(&self) -> bool {
+        self.number < START_WAYPOINTS
+    }
+
+    /// `[Waypoints]` value for this cell.
+    pub fn cell_value(&self) -> i32 {
+        self.y * CELL_STRIDE + self.x
+    }
+}
+
+/// Read `[Waypoints]`, sorted by number. Malformed entries are skipped with a log line.
+pub fn parse_waypoints(doc: &MapDocument) -> Vec<Waypoint> {
+    let Some(section) = doc.section("Waypoints") else {
+        return Vec::new();
+    };
+
+    let mut out: Vec<Waypoint> = Vec::new();
+    for e in section.entries() {
+        let (Ok(number), Ok(cell)) = (e.key().parse::<u32>(), e.value().parse::<i32>()) else {
+            eprintln!("[backend] Ignoring bad waypoint {}={}", e.key(), e.value());
+            continue;
+        };
+        let wp = Waypoint { number, x: cell % CELL_STRIDE, y: cell / CELL_STRIDE };
+        // Duplicate numbers: last one wins, like the game's INI loader.
+        match out.iter_mut().find(|w| w.number == number) {
+            Some(existing) => *existing = wp,
+            None => out.push(wp),
+        }
+    }
+    out.sort_by_key(|w| w.number);
+    out
+}
+
+/// Replace the entries of `[Waypoints]` with `waypoints`, in number order.
+pub fn write_waypoints(doc: &mut MapDocument, waypoints: &[Waypoint]) {
+    let mut sorted = waypoints.to_vec();
+    sorted.sort_by_key(|w| w.number);
+
+    let section = doc.ensure_section("Waypoints");
+    section.clear_entries();
+    for w in sorted {
+        section.push(&w.number.to_string(), &w.cell_value().to_string());
+    }
+}
+
+/// Lowest number `>= from` not used by any waypoint.
+pub fn next_free_waypoint(waypoints: &[Waypoint], from: u32) -> u32 {
+    (from..).find(|n| waypoints.iter().all(|w| w.number != *n)).unwrap_or(from)
+}
@@ -0,0 +1,212 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use std::collections::HashMap;
+
+use crate::backend::{is_multiplayer, AiTriggerCatalog, EditorLayout, LoadAiIni, MapPreview};
+
+#[derive(Default, PartialEq, Clone, Copy)]
+enum StateFilter {
+    #[default]
+    All,
+    Enabled,
+    Disabled,
+    Overrides,
+}
+
+impl StateFilter {
+    const ALL: [StateFilter; 4] = [Self::All, Self::Enabled, Self::Disabled, Self::Overrides];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::All => "All",
+            Self::Enabled => "Enabled",
+            Self::Disabled => "Disabled",
+            Self::Overrides => "Differs from default",
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct AiTriggersPanel {
+    search: String,
+    state: StateFilter,
+    /// Owner house from the catalog; empty = any.
+    house: String,
+}
+
+/// One listed AI trigger.
+struct Row {
+    id: String,
+    name: String,
+    house: String,
+    enabled: bool,
+    is_override: bool,
+}
+
+/// `[AITriggerTypesEnable]` manager: searchable list of the global AI triggers,
+/// named from the loaded `ai.ini`/`aimd.ini`, with bulk enable/disable.
+pub fn ui_ai_triggers(
+    mut ctx: EguiContexts,
+    mut preview: ResMut<MapPreview>,
+    catalog: Res<AiTriggerCatalog>,
+    mut layout: ResMut<EditorLayout>,
+    mut load: EventWriter<LoadAiIni>,
+    mut panel: Local<AiTriggersPanel>,
+) {
+    let ctx = ctx.ctx_mut();
+
+    let Some(doc) = preview.document.as_ref() else {
+        return;
+    };
+    if !layout.show_ai_triggers {
+        return;
+    }
+    let multiplayer = is_multiplayer(doc);
+    // Triggers the map defines itself (`[AITriggerTypes]` in the map) are named too.
+    let local_names: HashMap<String, String> = doc
+        .section("AITriggerTypes")
+        .map(|s| {
+            s.entries()
+                .map(|e| (e.key().to_ascii_uppercase(), e.value().split(',').next().unwrap_or_default().to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let enables = &mut preview.ai_enable;
+    let mut rows: Vec<Row> = catalog
+        .entries
+        .iter()
+        .map(|info| (info.id.clone(), info.name.clone(), info.house.clone()))
+        .chain(
+            enables
+                .entries
+                .iter()
+                .filter(|(id, _)| catalog.get(id).is_none())
+                .map(|(id, _)| {
+                    let name = local_names.get(&id.to_ascii_uppercase()).cloned().unwrap_or_default();
+                    (id.clone(), name, String::new())
+                }),
+        )
+        .map(|(id, name, house)| {
+            let enabled = enables.is_enabled(&id, catalog.default_for(&id, multiplayer));
+            let is_override = enables.explicit(&id).is_some() && catalog.is_override(&id, enabled, multiplayer);
+            Row { id, name, house, enabled, is_override }
+        })
+        .collect();
+
+    let needle = panel.search.trim().to_ascii_lowercase();
+    rows.retain(|r| {
+        (needle.is_empty()
+            || r.id.to_ascii_lowercase().contains(&needle)
+            || r.name.to_ascii_lowercase().contains(&needle))
+            && (panel.house.is_empty() || r.house.eq_ignore_ascii_case(&panel.house))
+            && match panel.state {
+                StateFilter::All => true,
+                StateFilter::Enabled => r.enabled,
+                StateFilter::Disabled => !r.enabled,
+                StateFilter::Overrides => r.is_override,
+            }
+    });
+
+    let mut houses: Vec<&str> = catalog.entries.iter().map(|i| i.house.as_str()).filter(|h| !h.is_empty()).collect();
+    houses.sort_unstable();
+    houses.dedup();
+
+    let mut open = true;
+    egui::Window::new("AI Trigger Enabling")
+        .open(&mut open)
+        .default_size(egui::vec2(620.0, 460.0))
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if catalog.is_empty() {
+                    ui.label(egui::RichText::new("No ai.ini loaded: names and defaults unknown").italics());
+                } else {
+                    let files: Vec<String> = catalog
+                        .sources
+                        .iter()
+                        .filter_map(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+                        .collect();
+                    ui.label(format!("{} AI triggers from {}", catalog.entries.len(), files.join(" + ")));
+                }
+                if ui.button("Load ai.ini…").clicked() {
+                    load.send(LoadAiIni);
+                }
+            });
+            ui.label(if multiplayer {
+                "Game default on this map: skirmish triggers enabled (multiplayer map)"
+            } else {
+                "Game default on this map: all disabled (campaign map)"
+            });
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut panel.search).hint_text("Search ID or name").desired_width(160.0));
+                egui::ComboBox::from_id_source("aitriggers/state")
+                    .selected_text(panel.state.label())
+                    .show_ui(ui, |ui| {
+                        for f in StateFilter::ALL {
+                            ui.selectable_value(&mut panel.state, f, f.label());
+                        }
+                    });
+                let house = if panel.house.is_empty() { "Any house" } else { panel.house.as_str() };
+                egui::ComboBox::from_id_source("aitriggers/house").selected_text(house.to_string()).show_ui(ui, |ui| {
+                    ui.selectable_value(&mut panel.house, String::new(), "Any house");
+                    for h in &houses {
+                        ui.selectable_value(&mut panel.house, h.to_string(), *h);
+                    }
+                });
+            });
+            ui.horizontal(|ui| {
+                ui.label(format!("{} shown:", rows.len()));
+                if ui.button("Enable").clicked() {
+                    rows.iter().for_each(|r| enables.set(&r.id, true));
+                }
+                if ui.button("Disable").clicked() {
+                    rows.iter().for_each(|r| enables.set(&r.id, false));
+                }
+                if ui.button("Reset to default").on_hover_text("Drop the map's own setting").clicked() {
+                    rows.iter().for_each(|r| enables.reset(&r.id));
+                }
+            });
+            ui.separator();
+
+            let row_height = ui.text_style_height(&egui::TextStyle::Body) + 4.0;
+            egui::ScrollArea::vertical().id_source("aitriggers/list").max_height(300.0).show_rows(
+                ui,
+                row_height,
+                rows.len(),
+                |ui, range| {
+                    egui::Grid::new("aitriggers/grid").num_columns(4).striped(true).show(ui, |ui| {
+                        for r in &rows[range] {
+                            let mut on = r.enabled;
+                            if ui.checkbox(&mut on, r.id.as_str()).changed() {
+                                enables.set(&r.id, on);
+                            }
+                            ui.label(r.name.as_str());
+                            ui.label(r.house.as_str());
+                            if r.is_override {
+                                ui.colored_label(egui::Color32::from_rgb(230, 170, 60), "override");
+                            } else {
+                                ui.label("");
+                            }
+                            ui.end_row();
+                        }
+                    });
+                },
+            );
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.add_enabled(
+                    !catalog.is_empty(),
+                    egui::Checkbox::new(&mut enables.save_overrides_only, "Save overrides only"),
+                )
+                .on_hover_text("Leave out entries that match the game default");
+                let saved = enables.saved_entries(&catalog, multiplayer).len();
+                ui.label(format!("{} entries in the map, {saved} will be saved", enables.entries.len()));
+            });
+        });
+    if !open {
+        layout.show_ai_triggers = false;
+    }
+}
@@ -1,7 +1,9 @@
 use bevy::prelude::*;
 use bevy_egui::{egui, EguiContexts};
 
-use crate::backend::{EditorLayout, Node, NodeKind, OpenMap, ProjectState};
+use crate::backend::{is_virtual_path, EditorLayout, Node, NodeKind, OpenMap, ProjectState};
+
+use super::thumbnails::{show_thumbnail, ThumbnailCache};
 
 const INDENT_PER_LEVEL: f32 = 14.0;
 const ROW_HEIGHT: f32 = 22.0;
@@ -12,6 +14,7 @@ pub fn ui_explorer(
     project: Res<ProjectState>,
     mut layout: ResMut<EditorLayout>,
     mut ev_open_map: EventWriter<OpenMap>,
+    mut thumbs: ResMut<ThumbnailCache>,
 ) {
     let ctx = ctx.ctx_mut();
 
@@ -29,7 +32,7 @@ pub fn ui_explorer(
                 egui::ScrollArea::vertical()
                     .auto_shrink([false, false])
                     .show(ui, |ui| {
-                        draw_node(ui, root, 0, &mut layout, &mut ev_open_map);
+                        draw_node(ui, root, 0, &mut layout, &mut ev_open_map, &mut thumbs);
                     });
             } else {
                 ui.label(egui::RichText::new("Open a folder to view files").italics());
@@ -43,6 +46,7 @@ fn draw_node(
     depth: usize,
     layout: &mut EditorLayout,
     ev_open_map: &mut EventWriter<OpenMap>,
+    thumbs: &mut ThumbnailCache,
 ) {
     match &node.kind {
         NodeKind::Folder { children } => {
@@ -108,7 +112,7 @@ fn draw_node(
             // Children
             if opened {
                 for child in children {
-                    draw_node(ui, child, depth + 1, layout, ev_open_map);
+                    draw_node(ui, child, depth + 1, layout, ev_open_map, thumbs);
                 }
             }
         }
@@ -149,12 +153,20 @@ fn draw_node(
                 egui::Color32::from_gray(230),
             );
 
-            if resp.clicked() {
-                if ext.to_ascii_lowercase() == "map" {
+            // Maps inside archives are listed but can't be opened (or saved back).
+            if ext.eq_ignore_ascii_case("map") && !is_virtual_path(path) {
+                if resp.clicked() {
                     ev_open_map.send(OpenMap { path: path.clone() });
                 }
-                // else: intentionally do nothing
+                // Hover: show the map's PreviewPack thumbnail (decoded once, then cached)
+                resp.on_hover_ui(|ui| match thumbs.get(ui.ctx(), path) {
+                    Some(tex) => show_thumbnail(ui, tex, 200.0),
+                    None => {
+                        ui.label(egui::RichText::new("No preview").italics());
+                    }
+                });
             }
+            // else: intentionally do nothing
         }
     }
 }
@@ -0,0 +1,199 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use crate::backend::{
+    is_taken_section_name, AiScripts, EditorLayout, House, MapDocument, MapPreview, ObjectCatalog, StringTable, EDGES,
+};
+
+/// House colors used by the stock maps (`[Colors]` in rules); others can still be typed.
+const COLORS: [&str; 12] = [
+    "Gold", "DarkBlue", "DarkRed", "Grey", "Teal", "Purple", "Purple2", "Purple3",
+    "Orange", "Green", "AlliedLoad", "SovietLoad",
+];
+
+#[derive(Default)]
+pub struct HousesPanel {
+    selected: Option<usize>,
+    new_name: String,
+}
+
+pub fn ui_houses(
+    mut ctx: EguiContexts,
+    mut preview: ResMut<MapPreview>,
+    mut layout: ResMut<EditorLayout>,
+    mut panel: Local<HousesPanel>,
+    catalog: Res<ObjectCatalog>,
+    strings: Res<StringTable>,
+) {
+    let ctx = ctx.ctx_mut();
+
+    if !layout.show_houses {
+        return;
+    }
+    let MapPreview { document: Some(doc), houses, ai, .. } = &mut *preview else {
+        return;
+    };
+    let mut open = true;
+    egui::Window::new("Houses")
+        .open(&mut open)
+        .default_size(egui::vec2(520.0, 360.0))
+        .show(ctx, |ui| {
+            ui.horizontal_top(|ui| {
+                ui.vertical(|ui| {
+                    ui.set_width(160.0);
+                    house_list(ui, houses, &mut panel, doc, ai);
+                });
+                ui.separator();
+                ui.vertical(|ui| match panel.selected {
+                    Some(i) if i < houses.len() => house_editor(ui, houses, i, catalog.rules.as_ref(), &strings),
+                    _ => {
+                        ui.label(egui::RichText::new("Select a house").italics());
+                    }
+                });
+            });
+        });
+    if !open {
+        layout.show_houses = false;
+    }
+}
+
+fn house_list(ui: &mut egui::Ui, houses: &mut Vec<House>, panel: &mut HousesPanel, doc: &MapDocument, ai: &AiScripts) {
+    egui::ScrollArea::vertical().max_height(260.0).show(ui, |ui| {
+        for (i, h) in houses.iter().enumerate() {
+            let label = if h.unknown_allies(houses).is_empty() {
+                egui::RichText::new(&h.name)
+            } else {
+                egui::RichText::new(format!("{} ⚠", h.name)).color(egui::Color32::from_rgb(230, 170, 60))
+            };
+            if ui.selectable_label(panel.selected == Some(i), label).clicked() {
+                panel.selected = Some(i);
+            }
+        }
+    });
+    ui.separator();
+
+    ui.horizontal(|ui| {
+        ui.add(egui::TextEdit::singleline(&mut panel.new_name).desired_width(90.0).hint_text("New house"));
+        let name = panel.new_name.trim().to_string();
+        let exists = houses.iter().any(|h| h.name.eq_ignore_ascii_case(&name));
+        // A house's keys go into `[<Name>]`, so the name can't belong to any other section.
+        let taken = is_taken_section_name(doc, &name) || ai.contains_id(&name);
+        let valid = is_valid_name(&name) && !exists && !taken;
+        let add = ui.add_enabled(valid, egui::Button::new("Add"));
+        let add = if taken { add.on_disabled_hover_text(format!("[{name}] is already another section")) } else { add };
+        if add.clicked() {
+            houses.push(House::new(&name));
+            panel.selected = Some(houses.len() - 1);
+            panel.new_name.clear();
+        }
+    });
+
+    let can_remove = panel.selected.is_some_and(|i| i < houses.len());
+    if ui.add_enabled(can_remove, egui::Button::new("Remove selected")).clicked()
+        && let Some(i) = panel.selected.take()
+    {
+        let removed = houses.remove(i);
+        // Nobody can stay allied with a house that no longer exists.
+        for h in houses.iter_mut() {
+            h.allies.retain(|a| !a.eq_ignore_ascii_case(&removed.name));
+        }
+    }
+}
+
+/// Localized name of country `id` from its rules `UIName=`.
+fn country_name<'a>(rules: &'a MapDocument, strings: &'a StringTable, id: &str) -> Option<&'a str> {
+    strings.get(rules.get(id, "UIName")?)
+}
+
+fn house_editor(ui: &mut egui::Ui, houses: &mut [House], i: usize, rules: Option<&MapDocument>, strings: &StringTable) {
+    let names: Vec<String> = houses.iter().map(|h| h.name.clone()).collect();
+    let unknown: Vec<String> = houses[i].unknown_allies(houses).into_iter().map(String::from).collect();
+    let h = &mut houses[i];
+
+    ui.heading(&h.name);
+    egui::Grid::new("houses/props").num_columns(2).show(ui, |ui| {
+        // Pick from the rules' [Countries] once a game directory is set.
+        ui.label("Country");
+        match rules.filter(|r| r.has_section("Countries")) {
+            Some(rules) => {
+                let label = |id: &str| match country_name(rules, strings, id) {
+                    Some(name) => format!("{id} ({name})"),
+                    None => id.to_string(),
+                };
+                egui::ComboBox::from_id_source("houses/country").selected_text(label(&h.country)).show_ui(ui, |ui| {
+                    for id in rules.section("Countries").into_iter().flat_map(|s| s.entries()).map(|e| e.value().trim()) {
+                        ui.selectable_value(&mut h.country, id.to_string(), label(id));
+                    }
+                });
+            }
+            None => {
+                ui.text_edit_singleline(&mut h.country);
+            }
+        }
+        ui.end_row();
+
+        ui.label("Color");
+        egui::ComboBox::from_id_source("houses/color").selected_text(h.color.as_str()).show_ui(ui, |ui| {
+            for c in COLORS {
+                ui.selectable_value(&mut h.color, c.to_string(), c);
+            }
+        });
+        ui.end_row();
+
+        ui.label("Edge");
+        egui::ComboBox::from_id_source("houses/edge").selected_text(h.edge.as_str()).show_ui(ui, |ui| {
+            for e in EDGES {
+                ui.selectable_value(&mut h.edge, e.to_string(), e);
+            }
+        });
+        ui.end_row();
+
+        ui.label("IQ");
+        ui.add(egui::DragValue::new(&mut h.iq).range(0..=5));
+        ui.end_row();
+
+        ui.label("Credits");
+        ui.add(egui::DragValue::new(&mut h.credits).speed(100).range(0..=i32::MAX));
+        ui.end_row();
+
+        ui.label("Tech level");
+        ui.add(egui::DragValue::new(&mut h.tech_level).range(0..=10));
+        ui.end_row();
+
+        ui.label("Percent built");
+        ui.add(egui::DragValue::new(&mut h.percent_built).range(0..=100));
+        ui.end_row();
+
+        ui.label("Player control");
+        ui.checkbox(&mut h.player_control, "");
+        ui.end_row();
+    });
+
+    ui.add_space(6.0);
+    ui.label("Allies");
+    egui::ScrollArea::vertical().id_source("houses/allies").max_height(120.0).show(ui, |ui| {
+        for name in &names {
+            let mut allied = h.allies.iter().any(|a| a.eq_ignore_ascii_case(name));
+            if ui.checkbox(&mut allied, name).changed() {
+                if allied {
+                    h.allies.push(name.clone());
+                } else {
+                    h.allies.retain(|a| !a.eq_ignore_ascii_case(name));
+                }
+            }
+        }
+    });
+    for name in unknown {
+        ui.horizontal(|ui| {
+            ui.colored_label(egui::Color32::from_rgb(230, 90, 90), format!("Unknown ally: {name}"));
+            if ui.small_button("Drop").clicked() {
+                h.allies.retain(|a| *a != name);
+            }
+        });
+    }
+}
+
+/// House names become section names and list entries: no spaces, commas or brackets.
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && !name.chars().any(|c| c.is_whitespace() || matches!(c, ',' | '[' | ']' | '=' | ';'))
+}
@@ -1,12 +1,25 @@
 use bevy::prelude::*;
 use bevy_egui::{egui, EguiContexts};
-use crate::backend::{PaletteTab, PaletteState, palette_entries};
+use crate::backend::{PaletteTab, PaletteState, ToolState, Tool, MapPreview, ObjectCatalog, StringTable, Vfs, palette_entries};
 
+use super::sprites::{SpriteCache, SpriteKey, VOXEL_FACINGS};
+
+/// Voxel facing shown on palette buttons (south-east, toward the viewer).
+const THUMBNAIL_FACING: usize = VOXEL_FACINGS * 3 / 8;
+
+#[allow(clippy::too_many_arguments)]
 pub fn ui_interact(
     mut ctx: EguiContexts,
     mut palette: ResMut<PaletteState>,
+    mut tool: ResMut<ToolState>,
+    preview: Res<MapPreview>,
+    catalog: Res<ObjectCatalog>,
+    strings: Res<StringTable>,
+    vfs: Res<Vfs>,
+    mut sprites: ResMut<SpriteCache>,
 ) {
     let ctx = ctx.ctx_mut();
+    let theater = preview.header.as_ref().map(|h| h.theater);
 
     egui::TopBottomPanel::bottom("bottom/interact")
         .resizable(true)
@@ -23,11 +36,32 @@ pub fn ui_interact(
             ui.horizontal(|ui| {
                 tab_button(ui, &mut palette.tab, PaletteTab::Resource, "resource");
                 tab_button(ui, &mut palette.tab, PaletteTab::SpawnPoints, "spawn p");
+                tab_button(ui, &mut palette.tab, PaletteTab::Vehicles, "vehicles");
+                tab_button(ui, &mut palette.tab, PaletteTab::Infantry, "infantry");
+                tab_button(ui, &mut palette.tab, PaletteTab::Structures, "structures");
+                tab_button(ui, &mut palette.tab, PaletteTab::Aircraft, "aircraft");
+                tab_button(ui, &mut palette.tab, PaletteTab::Overlays, "overlays");
+                tab_button(ui, &mut palette.tab, PaletteTab::Terrain, "terrain");
+                tab_button(ui, &mut palette.tab, PaletteTab::Smudges, "smudges");
                 ui.add_space(8.0);
 
+                // Owner for placed objects; falls back to the map's first house,
+                // or `Neutral` on maps without a [Houses] list.
+                if !preview.houses.iter().any(|h| h.name == tool.object_house) {
+                    tool.object_house = preview.houses.first().map_or_else(|| "Neutral".to_string(), |h| h.name.clone());
+                }
+                if matches!(tool.current, Tool::Object(_)) {
+                    let current = tool.object_house.clone();
+                    egui::ComboBox::from_id_source("interact/house").selected_text(current).show_ui(ui, |ui| {
+                        for h in &preview.houses {
+                            ui.selectable_value(&mut tool.object_house, h.name.clone(), &h.name);
+                        }
+                    });
+                }
+
                 // (Optional) show current selection
                 if let Some(idx) = palette.selected_idx {
-                    let entries = palette_entries(palette.tab);
+                    let entries = palette_entries(palette.tab, &catalog, &strings, theater);
                     if let Some(e) = entries.get(idx) {
                         ui.label(
                             egui::RichText::new(format!("Selected: {}", e.name))
@@ -44,7 +78,7 @@ pub fn ui_interact(
                 .stroke(egui::Stroke::new(2.0, egui::Color32::from_gray(60)))
                 .inner_margin(egui::Margin::symmetric(10.0, 8.0))
                 .show(ui, |ui| {
-                    let entries = palette_entries(palette.tab);
+                    let entries = palette_entries(palette.tab, &catalog, &strings, theater);
 
                     ui.horizontal_wrapped(|ui| {
                         for (i, item) in entries.iter().enumerate() {
@@ -55,20 +89,45 @@ pub fn ui_interact(
                                 (egui::Color32::from_gray(34), egui::Color32::from_gray(230), egui::Color32::from_gray(80))
                             };
 
-                            let button = egui::Button::new(
-                                egui::RichText::new(format!("{}\n{}", item.emoji, item.name))
-                                    .size(16.0)
-                                    .color(txt)
-                                    .strong()
-                            )
+                            // Game art of catalog entries (SHP frame or rendered voxel) when
+                            // the game files have it, else the emoji.
+                            let entry = item.type_id.as_deref().and_then(|id| catalog.get(id));
+                            let thumbnail = theater.zip(entry).and_then(|(theater, e)| {
+                                let (file, palette) = e.sprite(theater)?;
+                                let frame = if e.voxel { THUMBNAIL_FACING } else { 0 };
+                                sprites.get(ctx, &vfs, &SpriteKey { file, palette, remap: None, frame }).cloned()
+                            });
+                            let button = match &thumbnail {
+                                Some(tex) => egui::Button::image_and_text(
+                                    egui::Image::new(tex).max_size(egui::vec2(64.0, 48.0)),
+                                    egui::RichText::new(&item.name).size(12.0).color(txt).strong(),
+                                ),
+                                None => egui::Button::new(
+                                    egui::RichText::new(format!("{}\n{}", item.emoji, item.name))
+                                        .size(16.0)
+                                        .color(txt)
+                                        .strong()
+                                ),
+                            }
                             .min_size(egui::vec2(80.0, 80.0))
                             .rounding(8.0)
                             .fill(bg)
                             .stroke(egui::Stroke::new(2.0, border));
 
                             let resp = ui.add(button);
-                            if resp.clicked() {
+                            if resp.clicked() && sel {
+                                // Clicking the active entry again drops back to selecting.
+                                palette.selected_idx = None;
+                                tool.current = Tool::Select;
+                            } else if resp.clicked() {
                                 palette.selected_idx = Some(i);
+                                tool.current = item.tool;
+                                if item.tool == Tool::Spawn {
+                                    tool.spawn_slot = i as u32;
+                                }
+                                if let Some(id) = &item.type_id {
+                                    tool.object_type = id.clone();
+                                }
                             }
 
                             ui.add_space(10.0);
@@ -80,10 +139,10 @@ pub fn ui_interact(
 
 fn tab_button(ui: &mut egui::Ui, current: &mut PaletteTab, me: PaletteTab, label: &str) {
     let on = *current == me;
-    let (bg, fg, border) = if on {
-        (egui::Color32::from_gray(230), egui::Color32::BLACK, egui::Color32::WHITE)
+    let (bg, border) = if on {
+        (egui::Color32::from_gray(230), egui::Color32::WHITE)
     } else {
-        (egui::Color32::from_gray(40), egui::Color32::from_gray(220), egui::Color32::from_gray(70))
+        (egui::Color32::from_gray(40), egui::Color32::from_gray(70))
     };
 
     let btn = egui::Button::new(egui::RichText::new(label).strong())
@@ -0,0 +1,56 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use crate::backend::{EditorLayout, LightingMode, MapPreview, WorkspaceSettings};
+
+/// `[Lighting]` editor. Editing a set also switches the workspace preview to it,
+/// so changes show up on the terrain right away.
+pub fn ui_lighting(
+    mut ctx: EguiContexts,
+    mut preview: ResMut<MapPreview>,
+    mut layout: ResMut<EditorLayout>,
+    mut settings: ResMut<WorkspaceSettings>,
+) {
+    let ctx = ctx.ctx_mut();
+
+    if preview.document.is_none() || !layout.show_lighting {
+        return;
+    }
+
+    let modes = [LightingMode::Normal, LightingMode::IonStorm, LightingMode::Dominator];
+    let mut open = true;
+    egui::Window::new("Lighting")
+        .open(&mut open)
+        .resizable(false)
+        .show(ctx, |ui| {
+            let lighting = &mut preview.lighting;
+            egui::Grid::new("lighting/grid").num_columns(4).show(ui, |ui| {
+                ui.label("");
+                for mode in modes {
+                    ui.selectable_value(&mut settings.lighting, mode, mode.label());
+                }
+                ui.end_row();
+
+                let [(_, normal), (_, ion), (_, dominator)] = lighting.sets_mut();
+                let rows = normal.values_mut().into_iter().zip(ion.values_mut()).zip(dominator.values_mut());
+                for (((name, n), (_, i)), (_, d)) in rows {
+                    ui.label(name);
+                    for (mode, value) in modes.into_iter().zip([n, i, d]) {
+                        if ui.add(egui::DragValue::new(value).speed(0.01).range(0.0..=2.0).max_decimals(3)).changed() {
+                            settings.lighting = mode;
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.label("Dominator ambient change rate");
+                ui.add(egui::DragValue::new(&mut lighting.dominator_ambient_change_rate).speed(0.001).range(0.0..=1.0).max_decimals(4));
+            });
+        });
+    if !open {
+        layout.show_lighting = false;
+    }
+}
@@ -0,0 +1,92 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use crate::backend::{stored_digest, EditorLayout, MapPreview};
+
+use super::thumbnails::{show_thumbnail, ThumbnailCache};
+
+pub fn ui_mapinfo(
+    mut ctx: EguiContexts,
+    preview: Res<MapPreview>,
+    mut layout: ResMut<EditorLayout>,
+    mut thumbs: ResMut<ThumbnailCache>,
+) {
+    let ctx = ctx.ctx_mut();
+
+    let (Some(h), Some(doc), Some(path)) = (&preview.header, &preview.document, &preview.path) else {
+        return;
+    };
+    if !layout.show_map_info {
+        return;
+    }
+
+    egui::SidePanel::right("right/mapinfo")
+        .default_width(230.0)
+        .min_width(180.0)
+        .resizable(true)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.heading("MAP INFO");
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.small_button("✖").clicked() {
+                        layout.show_map_info = false;
+                    }
+                });
+            });
+            ui.add_space(6.0);
+
+            let width = ui.available_width();
+            match thumbs.get(ui.ctx(), path) {
+                Some(tex) => show_thumbnail(ui, tex, width),
+                None => {
+                    ui.label(egui::RichText::new("No preview in this map").italics());
+                }
+            }
+            ui.add_space(6.0);
+
+            egui::Grid::new("mapinfo/grid").num_columns(2).striped(true).show(ui, |ui| {
+                let file = std::path::Path::new(path)
+                    .file_name()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                ui.label("File");
+                ui.label(file);
+                ui.end_row();
+
+                ui.label("Name");
+                ui.label(doc.get("Basic", "Name").unwrap_or("—"));
+                ui.end_row();
+
+                ui.label("Theater");
+                ui.label(format!("{:?}", h.theater));
+                ui.end_row();
+
+                ui.label("Size");
+                ui.label(format!("{} × {}", h.width, h.height));
+                ui.end_row();
+
+                ui.label("Players");
+                ui.label(format!(
+                    "{}–{}",
+                    doc.get("Basic", "MinPlayer").unwrap_or("?"),
+                    doc.get("Basic", "MaxPlayer").unwrap_or("?"),
+                ));
+                ui.end_row();
+
+                ui.label("Game mode");
+                ui.label(doc.get("Basic", "GameMode").unwrap_or("—"));
+                ui.end_row();
+
+                ui.label("Digest");
+                match stored_digest(doc) {
+                    Some(d) => {
+                        ui.label("present (kept as-is)").on_hover_text(d);
+                    }
+                    None => {
+                        ui.label("—");
+                    }
+                }
+                ui.end_row();
+            });
+        });
+}
@@ -1,9 +1,17 @@
 use bevy::prelude::*;
 use bevy_egui::{egui, EguiContexts};
 
-use crate::backend::OpenFolder; // event
+use crate::backend::{EditorLayout, MapPreview, OpenFolder, ProjectState, SaveMap, SetGameDir}; // events
 
-pub fn ui_menubar(mut ctx: EguiContexts, mut open_ev: EventWriter<OpenFolder>) {
+pub fn ui_menubar(
+    mut ctx: EguiContexts,
+    mut open_ev: EventWriter<OpenFolder>,
+    mut save_ev: EventWriter<SaveMap>,
+    mut game_ev: EventWriter<SetGameDir>,
+    project: Res<ProjectState>,
+    mut layout: ResMut<EditorLayout>,
+    preview: Res<MapPreview>,
+) {
     let ctx = ctx.ctx_mut();
 
     egui::TopBottomPanel::top("menubar").exact_height(28.0).show(ctx, |ui| {
@@ -21,6 +29,50 @@ pub fn ui_menubar(mut ctx: EguiContexts, mut open_ev: EventWriter<OpenFolder>) {
                     open_ev.send(OpenFolder::Close);
                     ui.close_menu();
                 }
+                ui.separator();
+                let hint = project.game_dir.as_ref().map_or("not set".to_string(), |d| d.display().to_string());
+                if ui.button("Set Game Directory…").on_hover_text(hint).clicked() {
+                    game_ev.send(SetGameDir);
+                    ui.close_menu();
+                }
+            });
+
+            // Map menu (needs an open map)
+            let has_map = preview.document.is_some();
+            ui.menu_button("Map", |ui| {
+                if ui.add_enabled(has_map, egui::Button::new("Properties…")).clicked() {
+                    layout.show_map_properties = true;
+                    ui.close_menu();
+                }
+                ui.separator();
+                if ui.add_enabled(has_map, egui::Button::new("Save Map")).clicked() {
+                    save_ev.send(SaveMap::Save);
+                    ui.close_menu();
+                }
+                if ui.add_enabled(has_map, egui::Button::new("Save Map As…")).clicked() {
+                    save_ev.send(SaveMap::SaveAs);
+                    ui.close_menu();
+                }
+            });
+
+            // View menu: real panel toggles first, placeholders below
+            ui.menu_button("View", |ui| {
+                ui.checkbox(&mut layout.show_map_info, "Map Info");
+                ui.add_enabled(has_map, egui::Checkbox::new(&mut layout.show_houses, "Houses"));
+                ui.add_enabled(has_map, egui::Checkbox::new(&mut layout.show_special_flags, "Special Flags"));
+                ui.add_enabled(has_map, egui::Checkbox::new(&mut layout.show_lighting, "Lighting"));
+                ui.add_enabled(has_map, egui::Checkbox::new(&mut layout.show_triggers, "Triggers"));
+                ui.add_enabled(has_map, egui::Checkbox::new(&mut layout.show_ai_scripts, "Teams & Scripts"));
+                ui.add_enabled(has_map, egui::Checkbox::new(&mut layout.show_ai_triggers, "AI Trigger Enabling"));
+                ui.add_enabled(has_map, egui::Checkbox::new(&mut layout.show_rules_overrides, "Rules Overrides"));
+                ui.separator();
+                for (label, id) in [("Toggle Sidebar","view.sidebar"),("Toggle Status Bar","view.statusbar"),
+                                    ("Zoom In","view.zoomin"),("Zoom Out","view.zoomout"),("Reset Zoom","view.resetzoom")] {
+                    if ui.button(label).clicked() {
+                        println!("[menu] View -> {}", id);
+                        ui.close_menu();
+                    }
+                }
             });
 
             // (Rest unchanged)
@@ -41,9 +93,6 @@ pub fn ui_menubar(mut ctx: EguiContexts, mut open_ev: EventWriter<OpenFolder>) {
             menu("Selection", &[("Select All","sel.all"),("Expand Selection","sel.expand"),
                                 ("Shrink Selection","sel.shrink")]);
 
-            menu("View", &[("Toggle Sidebar","view.sidebar"),("Toggle Status Bar","view.statusbar"),
-                           ("Zoom In","view.zoomin"),("Zoom Out","view.zoomout"),("Reset Zoom","view.resetzoom")]);
-
             menu("Go", &[("Go to File…","go.file"),("Go to Line…","go.line"),
                          ("Back","go.back"),("Forward","go.forward")]);
 
@@ -5,21 +5,47 @@ mod workspace;
 mod statusbar;
 mod explorer;
 mod interact;
+mod mapinfo;
+mod thumbnails;
+mod sprites;
+mod tiles;
+mod houses;
+mod properties;
+mod special_flags;
+mod lighting;
+mod triggers;
+mod teams;
+mod ai_triggers;
+mod objects;
+mod rules_overrides;
 
 pub struct EditorUiPlugin;
 
 impl Plugin for EditorUiPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            (
-                menubar::ui_menubar,     // top
-                explorer::ui_explorer,   // left
-                interact::ui_interact,   // bottom (your red area)
-                statusbar::ui_statusbar, // bottom status line
-                workspace::ui_workspace, // central (must be last)
-            )
-            .chain(),
-        );
+        app.init_resource::<thumbnails::ThumbnailCache>()
+            .init_resource::<sprites::SpriteCache>()
+            .init_resource::<tiles::TileAtlas>()
+            .add_systems(
+                Update,
+                (
+                    menubar::ui_menubar,     // top
+                    explorer::ui_explorer,   // left
+                    interact::ui_interact,   // bottom (your red area)
+                    statusbar::ui_statusbar, // bottom status line
+                    mapinfo::ui_mapinfo,     // right (when a map is open)
+                    houses::ui_houses,       // floating window
+                    properties::ui_properties, // Map → Properties… dialog
+                    special_flags::ui_special_flags,
+                    lighting::ui_lighting,
+                    triggers::ui_triggers,
+                    teams::ui_teams,
+                    ai_triggers::ui_ai_triggers,
+                    objects::ui_objects,
+                    rules_overrides::ui_rules_overrides,
+                    workspace::ui_workspace, // central (must be last)
+                )
+                    .chain(),
+            );
     }
 }
@@ -0,0 +1,116 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use crate::backend::{EditorObjects, MapObject, MapPreview, ObjectKind, Tool, ToolState, WorkspaceSettings, SUB_CELLS};
+
+/// Properties of the objects on the selected cell, edited in place.
+pub fn ui_objects(
+    mut ctx: EguiContexts,
+    preview: Res<MapPreview>,
+    settings: Res<WorkspaceSettings>,
+    tool: Res<ToolState>,
+    mut objs: ResMut<EditorObjects>,
+) {
+    let ctx = ctx.ctx_mut();
+
+    let Some((sx, sy)) = settings.selected else {
+        return;
+    };
+    if preview.document.is_none() || tool.current != Tool::Select || objs.objects_at(sx, sy).next().is_none() {
+        return;
+    }
+
+    let houses: Vec<String> = preview.houses.iter().map(|h| h.name.clone()).collect();
+    let tags: Vec<(String, String)> = preview.triggers.tags.iter().map(|t| (t.id.clone(), t.name.clone())).collect();
+
+    egui::Window::new("Objects")
+        .id(egui::Id::new("objects/window"))
+        .default_pos(egui::pos2(260.0, 80.0))
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label(format!("Cell {sx}, {sy}"));
+            // Edit copies so the resource is only marked changed by real edits.
+            let mut remove = None;
+            let indices: Vec<usize> =
+                objs.objects.iter().enumerate().filter(|(_, o)| (o.x, o.y) == (sx, sy)).map(|(i, _)| i).collect();
+            for i in indices {
+                let mut o = objs.objects[i].clone();
+                ui.separator();
+                egui::Grid::new(("objects/grid", i)).num_columns(2).show(ui, |ui| {
+                    object_props(ui, i, &mut o, &houses, &tags);
+                });
+                if ui.small_button("Delete").clicked() {
+                    remove = Some(i);
+                }
+                if o != objs.objects[i] {
+                    objs.objects[i] = o;
+                }
+            }
+            if let Some(i) = remove {
+                objs.objects.remove(i);
+            }
+        });
+}
+
+fn object_props(ui: &mut egui::Ui, i: usize, o: &mut MapObject, houses: &[String], tags: &[(String, String)]) {
+    ui.label(o.kind.label());
+    if ui.add(egui::TextEdit::singleline(&mut o.type_id).desired_width(100.0)).changed() {
+        o.type_id.retain(|c| c != ',');
+    }
+    ui.end_row();
+
+    ui.label("House");
+    egui::ComboBox::from_id_source(("objects/house", i)).selected_text(o.house.as_str()).show_ui(ui, |ui| {
+        for h in houses {
+            ui.selectable_value(&mut o.house, h.clone(), h);
+        }
+    });
+    ui.end_row();
+
+    ui.label("Health");
+    ui.add(egui::DragValue::new(&mut o.health).range(0..=256));
+    ui.end_row();
+
+    ui.label("Facing");
+    ui.add(egui::DragValue::new(&mut o.facing).range(0..=255));
+    ui.end_row();
+
+    if o.kind != ObjectKind::Structure {
+        ui.label("Mission");
+        egui::ComboBox::from_id_source(("objects/mission", i)).selected_text(o.mission.as_str()).show_ui(ui, |ui| {
+            for m in o.kind.missions() {
+                ui.selectable_value(&mut o.mission, m.to_string(), *m);
+            }
+        });
+        ui.end_row();
+
+        ui.label("Veterancy");
+        ui.add(egui::DragValue::new(&mut o.veteran).range(0..=200));
+        ui.end_row();
+
+        ui.label("Group");
+        ui.add(egui::DragValue::new(&mut o.group).range(-1..=99));
+        ui.end_row();
+    }
+
+    if o.kind == ObjectKind::Infantry {
+        ui.label("Sub-cell");
+        egui::ComboBox::from_id_source(("objects/subcell", i)).selected_text(o.sub_cell.to_string()).show_ui(ui, |ui| {
+            ui.selectable_value(&mut o.sub_cell, 0, "0 (center)");
+            for s in SUB_CELLS {
+                ui.selectable_value(&mut o.sub_cell, s, s.to_string());
+            }
+        });
+        ui.end_row();
+    }
+
+    ui.label("Tag");
+    let text = o.tag.clone().unwrap_or_else(|| "None".to_string());
+    egui::ComboBox::from_id_source(("objects/tag", i)).selected_text(text).show_ui(ui, |ui| {
+        ui.selectable_value(&mut o.tag, None, "None");
+        for (id, name) in tags {
+            ui.selectable_value(&mut o.tag, Some(id.clone()), format!("{id}  {name}"));
+        }
+    });
+    ui.end_row();
+}
@@ -0,0 +1,148 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use crate::backend::{BasicProperties, EditorLayout, EditorObjects, MapPreview};
+
+/// Game modes offered as checkboxes; `[Basic] GameMode` is a comma list of these.
+const GAME_MODES: [&str; 5] = ["standard", "meatgrind", "navalwar", "nukewar", "teamgame"];
+
+/// Map → Properties…: edits a draft copy of `[Basic]`; OK validates and writes it
+/// into the open document, Cancel throws it away. The draft belongs to the map
+/// it was read from, so opening another map starts over.
+pub fn ui_properties(
+    mut ctx: EguiContexts,
+    mut preview: ResMut<MapPreview>,
+    mut layout: ResMut<EditorLayout>,
+    objs: Res<EditorObjects>,
+    mut draft: Local<Option<(Option<String>, BasicProperties)>>,
+) {
+    let ctx = ctx.ctx_mut();
+
+    let path = preview.path.clone();
+    if draft.as_ref().is_some_and(|(from, _)| *from != path) {
+        *draft = None;
+    }
+    let Some(doc) = preview.document.as_mut() else {
+        *draft = None;
+        return;
+    };
+    if !layout.show_map_properties {
+        *draft = None;
+        return;
+    }
+    let (_, p) = draft.get_or_insert_with(|| (path, BasicProperties::from_document(doc)));
+    let starting_points = objs.waypoints.iter().filter(|w| w.is_start_location()).count();
+    let errors = p.validate(starting_points);
+
+    let mut open = true;
+    let mut close = false;
+    egui::Window::new("Map Properties")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            egui::Grid::new("props/basic").num_columns(2).spacing([12.0, 6.0]).show(ui, |ui| {
+                ui.label("Name");
+                ui.text_edit_singleline(&mut p.name);
+                ui.end_row();
+
+                ui.label("Game modes");
+                ui.horizontal_wrapped(|ui| {
+                    for mode in GAME_MODES {
+                        let mut on = p.game_mode.split(',').any(|m| m.trim().eq_ignore_ascii_case(mode));
+                        if ui.checkbox(&mut on, mode).changed() {
+                            toggle_mode(&mut p.game_mode, mode, on);
+                        }
+                    }
+                });
+                ui.end_row();
+
+                ui.label("Players");
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut p.min_player).range(1..=8).prefix("min "));
+                    ui.add(egui::DragValue::new(&mut p.max_player).range(1..=8).prefix("max "));
+                    ui.label(format!("({starting_points} start locations)"));
+                });
+                ui.end_row();
+
+                ui.label("INI format");
+                ui.add(egui::DragValue::new(&mut p.new_ini_format).range(0..=4));
+                ui.end_row();
+
+                ui.label("Percent");
+                ui.add(egui::DragValue::new(&mut p.percent).range(0..=100).suffix(" %"));
+                ui.end_row();
+
+                ui.label("Init time");
+                ui.add(egui::DragValue::new(&mut p.init_time).range(0..=i32::MAX));
+                ui.end_row();
+
+                ui.label("Next scenario");
+                ui.text_edit_singleline(&mut p.next_scenario);
+                ui.end_row();
+
+                ui.label("Carry over");
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut p.carry_over_cap).range(-1..=i32::MAX).prefix("cap "));
+                    ui.add(egui::DragValue::new(&mut p.carry_over_money).range(0.0..=1.0).speed(0.01).prefix("money "));
+                });
+                ui.end_row();
+            });
+
+            ui.separator();
+            egui::Grid::new("props/flags").num_columns(3).show(ui, |ui| {
+                let flags: [(&str, &mut bool); 14] = [
+                    ("Multiplayer only", &mut p.multiplayer_only),
+                    ("Official", &mut p.official),
+                    ("End of game", &mut p.end_of_game),
+                    ("One time only", &mut p.one_time_only),
+                    ("Skip score", &mut p.skip_score),
+                    ("Skip map select", &mut p.skip_map_select),
+                    ("Free radar", &mut p.free_radar),
+                    ("Train crate", &mut p.train_crate),
+                    ("Truck crate", &mut p.truck_crate),
+                    ("Ore growth", &mut p.tiberium_growth),
+                    ("Vein growth", &mut p.vein_growth),
+                    ("Ice growth", &mut p.ice_growth),
+                    ("Ore → visceroid", &mut p.tiberium_death_to_visceroid),
+                    ("Ignore global AI", &mut p.ignore_global_ai_triggers),
+                ];
+                for (i, (label, value)) in flags.into_iter().enumerate() {
+                    ui.checkbox(value, label);
+                    if i % 3 == 2 {
+                        ui.end_row();
+                    }
+                }
+            });
+
+            for e in &errors {
+                ui.colored_label(egui::Color32::from_rgb(230, 90, 90), e);
+            }
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.add_enabled(errors.is_empty(), egui::Button::new("OK")).clicked() {
+                    p.apply(doc);
+                    println!("[ui] Applied [Basic] properties");
+                    close = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    close = true;
+                }
+            });
+        });
+
+    if !open || close {
+        layout.show_map_properties = false;
+        *draft = None;
+    }
+}
+
+fn toggle_mode(list: &mut String, mode: &str, on: bool) {
+    let mut modes: Vec<&str> = list.split(',').map(str::trim).filter(|m| !m.is_empty()).collect();
+    modes.retain(|m| !m.eq_ignore_ascii_case(mode));
+    if on {
+        modes.push(mode);
+    }
+    *list = modes.join(",");
+}
@@ -0,0 +1,113 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use crate::backend::{rules_overrides, EditorLayout, MapDocument, MapPreview, ObjectCatalog, ResetRulesKey};
+
+#[derive(Default)]
+pub struct RulesOverridesPanel {
+    /// Selected section name.
+    section: Option<String>,
+    /// Also list keys that repeat the global value.
+    show_redundant: bool,
+}
+
+const CHANGED: egui::Color32 = egui::Color32::from_rgb(230, 170, 60);
+const ADDED: egui::Color32 = egui::Color32::from_rgb(120, 200, 120);
+
+/// Read-only diff of the rules sections the open map overrides, against the
+/// game directory's rules.ini, with a per-key reset.
+pub fn ui_rules_overrides(
+    mut ctx: EguiContexts,
+    preview: Res<MapPreview>,
+    catalog: Res<ObjectCatalog>,
+    mut layout: ResMut<EditorLayout>,
+    mut reset: EventWriter<ResetRulesKey>,
+    mut panel: Local<RulesOverridesPanel>,
+) {
+    let ctx = ctx.ctx_mut();
+
+    let Some(doc) = preview.document.as_ref() else {
+        return;
+    };
+    if !layout.show_rules_overrides {
+        return;
+    }
+    let empty = MapDocument::default();
+    let overrides = rules_overrides(catalog.rules.as_ref().unwrap_or(&empty), doc);
+
+    let mut open = true;
+    egui::Window::new("Rules Overrides")
+        .open(&mut open)
+        .default_size(egui::vec2(620.0, 420.0))
+        .show(ctx, |ui| {
+            match catalog.sources.first().and_then(|p| p.file_name()) {
+                Some(name) => ui.label(format!("{} sections changed from {}", overrides.len(), name.to_string_lossy())),
+                None => ui.label(
+                    egui::RichText::new("No game directory set: every key is shown as new (Folder → Set Game Directory…)")
+                        .italics(),
+                ),
+            };
+            ui.checkbox(&mut panel.show_redundant, "Show keys that repeat the global value");
+            ui.separator();
+
+            ui.horizontal_top(|ui| {
+                ui.vertical(|ui| {
+                    ui.set_width(200.0);
+                    egui::ScrollArea::vertical().id_source("rules/sections").max_height(320.0).show(ui, |ui| {
+                        for o in &overrides {
+                            let kind = catalog.get(&o.section).map(|e| format!("  {:?}", e.kind)).unwrap_or_default();
+                            let text = egui::RichText::new(format!("{}{kind}", o.section));
+                            let text = if o.new_section { text.color(ADDED) } else { text };
+                            let sel = panel.section.as_deref() == Some(o.section.as_str());
+                            if ui.selectable_label(sel, text).clicked() {
+                                panel.section = Some(o.section.clone());
+                            }
+                        }
+                    });
+                });
+                ui.separator();
+
+                let Some(o) = panel.section.as_deref().and_then(|s| overrides.iter().find(|o| o.section == s)) else {
+                    ui.label(egui::RichText::new("Nothing selected").italics());
+                    return;
+                };
+                ui.vertical(|ui| {
+                    if let Some(e) = catalog.get(&o.section) {
+                        ui.label(format!("{:?} {}", e.kind, e.display_name()));
+                    }
+                    if o.new_section {
+                        ui.colored_label(ADDED, "New type: not in the global rules");
+                    }
+                    egui::ScrollArea::vertical().id_source("rules/keys").max_height(300.0).show(ui, |ui| {
+                        egui::Grid::new("rules/diff").num_columns(4).striped(true).show(ui, |ui| {
+                            ui.strong("Key");
+                            ui.strong("Global");
+                            ui.strong("Map");
+                            ui.label("");
+                            ui.end_row();
+                            for k in o.keys.iter().filter(|k| panel.show_redundant || !k.is_redundant()) {
+                                ui.label(k.key.as_str());
+                                match &k.default {
+                                    Some(d) => ui.label(d.as_str()),
+                                    None => ui.label(egui::RichText::new("—").weak()),
+                                };
+                                let color = if k.default.is_none() { ADDED } else { CHANGED };
+                                if k.is_redundant() {
+                                    ui.label(k.value.as_str());
+                                } else {
+                                    ui.colored_label(color, k.value.as_str());
+                                }
+                                if ui.small_button("Reset").on_hover_text("Remove the key from the map").clicked() {
+                                    reset.send(ResetRulesKey { section: o.section.clone(), key: k.key.clone() });
+                                }
+                                ui.end_row();
+                            }
+                        });
+                    });
+                });
+            });
+        });
+    if !open {
+        layout.show_rules_overrides = false;
+    }
+}
@@ -0,0 +1,35 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use crate::backend::{EditorLayout, MapPreview};
+
+/// `[SpecialFlags]` editor: one checkbox per flag, edited live on the open map.
+pub fn ui_special_flags(
+    mut ctx: EguiContexts,
+    mut preview: ResMut<MapPreview>,
+    mut layout: ResMut<EditorLayout>,
+) {
+    let ctx = ctx.ctx_mut();
+
+    if preview.document.is_none() || !layout.show_special_flags {
+        return;
+    }
+
+    let mut open = true;
+    egui::Window::new("Special Flags")
+        .open(&mut open)
+        .resizable(false)
+        .show(ctx, |ui| {
+            egui::Grid::new("specialflags/grid").num_columns(2).show(ui, |ui| {
+                for (i, (key, value)) in preview.special_flags.flags_mut().into_iter().enumerate() {
+                    ui.checkbox(value, key);
+                    if i % 2 == 1 {
+                        ui.end_row();
+                    }
+                }
+            });
+        });
+    if !open {
+        layout.show_special_flags = false;
+    }
+}
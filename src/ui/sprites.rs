@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use bevy_egui::egui;
+
+use crate::backend::{render_voxels, Hva, Palette, Shp, Vfs, Vxl};
+
+/// Facings voxel models are rendered at.
+pub const VOXEL_FACINGS: usize = 32;
+
+/// One drawable: a frame of an SHP in a palette, optionally in a house color.
+/// For `.vxl` files `frame` is the facing, one of `VOXEL_FACINGS` clockwise from north.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SpriteKey {
+    pub file: String,
+    pub palette: String,
+    pub remap: Option<[u8; 3]>,
+    pub frame: usize,
+}
+
+/// Decoded SHP frames and rendered voxel models as textures, read from the
+/// game files on first use. `None` remembers sprites that are missing or
+/// broken, so each is tried once.
+#[derive(Resource, Default)]
+pub struct SpriteCache {
+    shps: HashMap<String, Option<Shp>>,
+    voxels: HashMap<String, Option<(Vxl, Option<Hva>)>>,
+    palettes: HashMap<String, Option<Palette>>,
+    textures: HashMap<SpriteKey, Option<egui::TextureHandle>>,
+}
+
+impl SpriteCache {
+    /// Forget everything (the game directory changed).
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn get(&mut self, ctx: &egui::Context, vfs: &Vfs, key: &SpriteKey) -> Option<&egui::TextureHandle> {
+        if !self.textures.contains_key(key) {
+            let texture = self.decode(ctx, vfs, key);
+            self.textures.insert(key.clone(), texture);
+        }
+        self.textures.get(key)?.as_ref()
+    }
+
+    fn decode(&mut self, ctx: &egui::Context, vfs: &Vfs, key: &SpriteKey) -> Option<egui::TextureHandle> {
+        let palette = self
+            .palettes
+            .entry(key.palette.clone())
+            .or_insert_with(|| read(vfs, &key.palette, Palette::parse))
+            .as_ref()?;
+        let palette = match key.remap {
+            Some(color) => palette.remapped(color),
+            None => palette.clone(),
+        };
+        let (size, rgba) = match key.file.strip_suffix(".vxl") {
+            Some(stem) => self.render_voxel(vfs, stem, key.frame, &palette)?,
+            None => {
+                let shp = self.shps.entry(key.file.clone()).or_insert_with(|| read(vfs, &key.file, Shp::parse)).as_ref()?;
+                ([shp.width, shp.height], shp.frame_rgba(key.frame, &palette)?)
+            }
+        };
+        let image = egui::ColorImage::from_rgba_unmultiplied([size[0] as usize, size[1] as usize], &rgba);
+        let name = format!("sprite:{}:{}:{:?}", key.file, key.frame, key.remap);
+        Some(ctx.load_texture(name, image, egui::TextureOptions::NEAREST))
+    }
+}
+
+impl SpriteCache {
+    /// Render model `stem` with its turret and barrel (`{stem}tur`, `{stem}barl`)
+    /// when the game has them.
+    fn render_voxel(&mut self, vfs: &Vfs, stem: &str, facing: usize, palette: &Palette) -> Option<([u32; 2], Vec<u8>)> {
+        const PARTS: [&str; 3] = ["", "tur", "barl"];
+        for part in PARTS {
+            self.voxels.entry(format!("{stem}{part}")).or_insert_with_key(|name| {
+                let vxl = read(vfs, &format!("{name}.vxl"), Vxl::parse)?;
+                Some((vxl, read(vfs, &format!("{name}.hva"), Hva::parse)))
+            });
+        }
+        self.voxels.get(stem)?.as_ref()?;
+        let models: Vec<_> = PARTS
+            .iter()
+            .filter_map(|part| self.voxels.get(&format!("{stem}{part}"))?.as_ref())
+            .map(|(vxl, hva)| (vxl, hva.as_ref()))
+            .collect();
+        let facing = (facing % VOXEL_FACINGS * 256 / VOXEL_FACINGS) as u8;
+        let image = render_voxels(&models, facing, palette)?;
+        Some(([image.width, image.height], image.rgba))
+    }
+}
+
+/// Parse `name` from the game files; missing files are silent, broken ones logged.
+fn read<T>(vfs: &Vfs, name: &str, parse: fn(&[u8]) -> anyhow::Result<T>) -> Option<T> {
+    match vfs.read(name)?.and_then(|(_, bytes)| parse(&bytes)) {
+        Ok(v) => Some(v),
+        Err(e) => {
+            eprintln!("[ui] Can't read {name}: {e}");
+            None
+        }
+    }
+}
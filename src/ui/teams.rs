@@ -0,0 +1,304 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use crate::backend::{
+    is_known_type, letters_to_waypoint, waypoint_to_letters, EditorLayout, EditorObjects, MapPreview, ObjectCatalog,
+    ScriptType, TaskForce, TeamType,
+};
+
+#[derive(Default, PartialEq, Clone, Copy)]
+enum Tab {
+    #[default]
+    Teams,
+    Scripts,
+    TaskForces,
+}
+
+#[derive(Default)]
+pub struct TeamsPanel {
+    tab: Tab,
+    /// Selected ID per tab.
+    team: Option<String>,
+    script: Option<String>,
+    task_force: Option<String>,
+}
+
+/// Names shown next to IDs in the pickers.
+struct Lists {
+    houses: Vec<String>,
+    scripts: Vec<(String, String)>,
+    task_forces: Vec<(String, String)>,
+    tags: Vec<(String, String)>,
+}
+
+/// `[TeamTypes]`, `[ScriptTypes]` and `[TaskForces]` editor, edited live on the open map.
+pub fn ui_teams(
+    mut ctx: EguiContexts,
+    mut preview: ResMut<MapPreview>,
+    objs: Res<EditorObjects>,
+    catalog: Res<ObjectCatalog>,
+    mut layout: ResMut<EditorLayout>,
+    mut panel: Local<TeamsPanel>,
+) {
+    let ctx = ctx.ctx_mut();
+
+    let Some(doc) = preview.document.as_ref() else {
+        return;
+    };
+    if !layout.show_ai_scripts {
+        return;
+    }
+
+    // The loaded catalog already includes the map's own types; the stock list
+    // only stands in when no game directory is set.
+    let errors = preview.ai.validate(&preview.houses, &objs.waypoints, &preview.triggers, |t| {
+        if catalog.is_empty() { is_known_type(doc, t) } else { catalog.get(t).is_some() }
+    });
+    let new_id = preview.next_id();
+    let lists = Lists {
+        houses: preview.houses.iter().map(|h| h.name.clone()).collect(),
+        scripts: preview.ai.scripts.iter().map(|s| (s.id.clone(), s.name.clone())).collect(),
+        task_forces: preview.ai.task_forces.iter().map(|t| (t.id.clone(), t.name.clone())).collect(),
+        tags: preview.triggers.tags.iter().map(|t| (t.id.clone(), t.name.clone())).collect(),
+    };
+
+    let ai = &mut preview.ai;
+    let mut open = true;
+    egui::Window::new("Teams & Scripts")
+        .open(&mut open)
+        .default_size(egui::vec2(640.0, 420.0))
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut panel.tab, Tab::Teams, format!("TeamTypes ({})", ai.teams.len()));
+                ui.selectable_value(&mut panel.tab, Tab::Scripts, format!("ScriptTypes ({})", ai.scripts.len()));
+                ui.selectable_value(
+                    &mut panel.tab,
+                    Tab::TaskForces,
+                    format!("TaskForces ({})", ai.task_forces.len()),
+                );
+            });
+            ui.separator();
+
+            ui.horizontal_top(|ui| match panel.tab {
+                Tab::Teams => {
+                    let house = lists.houses.first().cloned().unwrap_or_else(|| "Neutral".to_string());
+                    let new = || TeamType::new(&new_id, &house);
+                    if let Some(i) = id_list(ui, "teams", &mut ai.teams, &mut panel.team, new, |t| (&t.id, &t.name)) {
+                        team_editor(ui, &mut ai.teams[i], &lists, &objs);
+                    }
+                }
+                Tab::Scripts => {
+                    let new = || ScriptType::new(&new_id);
+                    if let Some(i) =
+                        id_list(ui, "scripts", &mut ai.scripts, &mut panel.script, new, |s| (&s.id, &s.name))
+                    {
+                        script_editor(ui, &mut ai.scripts[i]);
+                    }
+                }
+                Tab::TaskForces => {
+                    let new = || TaskForce::new(&new_id);
+                    if let Some(i) =
+                        id_list(ui, "taskforces", &mut ai.task_forces, &mut panel.task_force, new, |t| (&t.id, &t.name))
+                    {
+                        task_force_editor(ui, &mut ai.task_forces[i]);
+                    }
+                }
+            });
+
+            if !errors.is_empty() {
+                ui.separator();
+                egui::ScrollArea::vertical().id_source("teams/errors").max_height(80.0).show(ui, |ui| {
+                    for e in &errors {
+                        ui.colored_label(egui::Color32::from_rgb(230, 170, 60), format!("⚠ {e}"));
+                    }
+                });
+            }
+        });
+    if !open {
+        layout.show_ai_scripts = false;
+    }
+}
+
+/// List with New/Delete on the left; returns the index of the selected item.
+fn id_list<T>(
+    ui: &mut egui::Ui,
+    id: &str,
+    items: &mut Vec<T>,
+    selected: &mut Option<String>,
+    new: impl FnOnce() -> T,
+    label: impl Fn(&T) -> (&String, &String),
+) -> Option<usize> {
+    ui.vertical(|ui| {
+        ui.set_width(180.0);
+        egui::ScrollArea::vertical().id_source(("teams/list", id)).max_height(280.0).show(ui, |ui| {
+            for item in items.iter() {
+                let (item_id, name) = label(item);
+                let sel = selected.as_deref() == Some(item_id.as_str());
+                if ui.selectable_label(sel, format!("{item_id}  {name}")).clicked() {
+                    *selected = Some(item_id.clone());
+                }
+            }
+        });
+        ui.separator();
+        ui.horizontal(|ui| {
+            if ui.button("New").clicked() {
+                let item = new();
+                *selected = Some(label(&item).0.clone());
+                items.push(item);
+            }
+            if ui.add_enabled(selected.is_some(), egui::Button::new("Delete")).clicked()
+                && let Some(gone) = selected.take()
+            {
+                items.retain(|item| *label(item).0 != gone);
+            }
+        });
+    });
+    ui.separator();
+
+    let index = selected.as_deref().and_then(|s| items.iter().position(|item| label(item).0 == s));
+    if index.is_none() {
+        ui.label(egui::RichText::new("Nothing selected").italics());
+    }
+    index
+}
+
+fn pick(ui: &mut egui::Ui, id: &str, value: &mut String, choices: &[(String, String)]) {
+    let text = choices
+        .iter()
+        .find(|(v, _)| v == value)
+        .map_or_else(|| format!("{value} (missing)"), |(v, name)| format!("{v}  {name}"));
+    egui::ComboBox::from_id_source(("teams/pick", id)).width(220.0).selected_text(text).show_ui(ui, |ui| {
+        for (v, name) in choices {
+            ui.selectable_value(value, v.clone(), format!("{v}  {name}"));
+        }
+    });
+}
+
+fn team_editor(ui: &mut egui::Ui, t: &mut TeamType, lists: &Lists, objs: &EditorObjects) {
+    egui::ScrollArea::vertical().id_source("teams/team").show(ui, |ui| {
+        egui::Grid::new("teams/team/props").num_columns(2).show(ui, |ui| {
+            ui.label("Name");
+            ui.text_edit_singleline(&mut t.name);
+            ui.end_row();
+
+            ui.label("House");
+            egui::ComboBox::from_id_source("teams/house").selected_text(t.house.as_str()).show_ui(ui, |ui| {
+                for h in &lists.houses {
+                    ui.selectable_value(&mut t.house, h.clone(), h);
+                }
+            });
+            ui.end_row();
+
+            ui.label("Script");
+            pick(ui, "script", &mut t.script, &lists.scripts);
+            ui.end_row();
+
+            ui.label("Task force");
+            pick(ui, "taskforce", &mut t.task_force, &lists.task_forces);
+            ui.end_row();
+
+            ui.label("Waypoint");
+            let text = letters_to_waypoint(&t.waypoint).map_or_else(|| t.waypoint.clone(), |n| format!("Waypoint {n}"));
+            egui::ComboBox::from_id_source("teams/waypoint").selected_text(text).show_ui(ui, |ui| {
+                for w in &objs.waypoints {
+                    ui.selectable_value(&mut t.waypoint, waypoint_to_letters(w.number), format!("Waypoint {}", w.number));
+                }
+            });
+            ui.end_row();
+
+            ui.label("Tag");
+            let text = t.tag.clone().unwrap_or_else(|| "<none>".to_string());
+            egui::ComboBox::from_id_source("teams/tag").selected_text(text).show_ui(ui, |ui| {
+                ui.selectable_value(&mut t.tag, None, "<none>");
+                for (id, name) in &lists.tags {
+                    ui.selectable_value(&mut t.tag, Some(id.clone()), format!("{id}  {name}"));
+                }
+            });
+            ui.end_row();
+
+            for (label, value) in [
+                ("Priority", &mut t.priority),
+                ("Max", &mut t.max),
+                ("Tech level", &mut t.tech_level),
+                ("Veteran level", &mut t.veteran_level),
+                ("Group", &mut t.group),
+            ] {
+                ui.label(label);
+                ui.add(egui::DragValue::new(value));
+                ui.end_row();
+            }
+        });
+        ui.separator();
+        egui::Grid::new("teams/team/flags").num_columns(2).show(ui, |ui| {
+            for (i, (key, value)) in t.flags_mut().into_iter().enumerate() {
+                ui.checkbox(value, key);
+                if i % 2 == 1 {
+                    ui.end_row();
+                }
+            }
+        });
+    });
+}
+
+fn script_editor(ui: &mut egui::Ui, s: &mut ScriptType) {
+    ui.vertical(|ui| {
+        ui.horizontal(|ui| {
+            ui.label("Name");
+            ui.text_edit_singleline(&mut s.name);
+        });
+        ui.horizontal(|ui| {
+            ui.strong("Actions");
+            if ui.small_button("+").clicked() {
+                s.actions.push((0, "0".to_string()));
+            }
+        });
+        let mut remove = None;
+        for (i, (action, param)) in s.actions.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(format!("{i}."));
+                ui.add(egui::DragValue::new(action).prefix("action "));
+                ui.add(egui::TextEdit::singleline(param).desired_width(80.0));
+                if ui.small_button("✖").clicked() {
+                    remove = Some(i);
+                }
+            });
+        }
+        if let Some(i) = remove {
+            s.actions.remove(i);
+        }
+    });
+}
+
+fn task_force_editor(ui: &mut egui::Ui, tf: &mut TaskForce) {
+    ui.vertical(|ui| {
+        ui.horizontal(|ui| {
+            ui.label("Name");
+            ui.text_edit_singleline(&mut tf.name);
+        });
+        ui.horizontal(|ui| {
+            ui.label("Group");
+            ui.add(egui::DragValue::new(&mut tf.group));
+        });
+        ui.horizontal(|ui| {
+            ui.strong("Members");
+            if ui.small_button("+").clicked() {
+                tf.members.push((1, "E1".to_string()));
+            }
+        });
+        let mut remove = None;
+        for (i, (count, kind)) in tf.members.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(count).range(1..=100).suffix("×"));
+                if ui.add(egui::TextEdit::singleline(kind).desired_width(100.0)).changed() {
+                    kind.retain(|c| c != ',');
+                }
+                if ui.small_button("✖").clicked() {
+                    remove = Some(i);
+                }
+            });
+        }
+        if let Some(i) = remove {
+            tf.members.remove(i);
+        }
+    });
+}
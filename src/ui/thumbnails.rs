@@ -0,0 +1,41 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use bevy_egui::egui;
+
+use crate::backend::MapThumbnail;
+
+/// Decoded `[PreviewPack]` textures, keyed by map path. Each map is read at most
+/// once; `None` remembers maps without (or with a broken) preview.
+#[derive(Resource, Default)]
+pub struct ThumbnailCache {
+    textures: HashMap<String, Option<egui::TextureHandle>>,
+}
+
+impl ThumbnailCache {
+    pub fn get(&mut self, ctx: &egui::Context, path: &str) -> Option<&egui::TextureHandle> {
+        self.textures
+            .entry(path.to_string())
+            .or_insert_with(|| match MapThumbnail::load(path) {
+                Ok(Some(t)) => Some(to_texture(ctx, path, &t)),
+                Ok(None) => None,
+                Err(e) => {
+                    eprintln!("[ui] No preview for {path}: {e}");
+                    None
+                }
+            })
+            .as_ref()
+    }
+}
+
+fn to_texture(ctx: &egui::Context, path: &str, t: &MapThumbnail) -> egui::TextureHandle {
+    let image = egui::ColorImage::from_rgb([t.width, t.height], &t.rgb);
+    ctx.load_texture(format!("thumb:{path}"), image, egui::TextureOptions::NEAREST)
+}
+
+/// Thumbnail at a fixed width, keeping the map's aspect ratio.
+pub fn show_thumbnail(ui: &mut egui::Ui, tex: &egui::TextureHandle, width: f32) {
+    let [w, h] = tex.size();
+    let size = egui::vec2(width, width * h as f32 / w.max(1) as f32);
+    ui.image((tex.id(), size));
+}
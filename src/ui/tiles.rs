@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use bevy_egui::egui;
+
+use crate::backend::{Palette, TileLibrary, Vfs};
+
+/// Side of one atlas page in pixels.
+const PAGE_SIZE: usize = 2048;
+/// Gap between packed images so nearest sampling never picks up a neighbor.
+const PADDING: usize = 1;
+
+/// An image packed into the atlas.
+#[derive(Debug, Clone, Copy)]
+pub struct AtlasRect {
+    pub page: usize,
+    pub uv: egui::Rect,
+}
+
+/// One tile cell in the atlas. Offsets are in cell widths from the top-left
+/// corner of the cell's diamond, so they scale with the zoom.
+#[derive(Debug, Clone, Copy)]
+pub struct AtlasTile {
+    pub diamond: AtlasRect,
+    /// Cliff faces and other parts that stick out of the diamond.
+    pub extra: Option<(egui::Rect, AtlasRect)>,
+}
+
+struct Page {
+    texture: egui::TextureHandle,
+    cursor: [usize; 2],
+    row_height: usize,
+}
+
+/// Terrain tiles of the open theater packed into a few large textures, so the
+/// whole map draws in a handful of meshes. Cells are added on first use and
+/// `None` remembers the ones that can't be drawn.
+#[derive(Resource, Default)]
+pub struct TileAtlas {
+    palette_name: String,
+    palette: Option<Palette>,
+    pages: Vec<Page>,
+    tiles: HashMap<(usize, u8), Option<AtlasTile>>,
+}
+
+impl TileAtlas {
+    /// Forget everything (the game directory or theater changed).
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Use the theater palette `name`, starting over if it differs from the current one.
+    pub fn set_palette(&mut self, vfs: &Vfs, name: &str) {
+        if self.palette_name == name {
+            return;
+        }
+        self.clear();
+        self.palette_name = name.to_string();
+        self.palette = match vfs.read(name).map(|r| r.and_then(|(_, bytes)| Palette::parse(&bytes))) {
+            Some(Ok(p)) => Some(p),
+            None => None,
+            Some(Err(e)) => {
+                eprintln!("[ui] Can't read {name}: {e}");
+                None
+            }
+        };
+    }
+
+    pub fn texture(&self, page: usize) -> Option<egui::TextureId> {
+        self.pages.get(page).map(|p| p.texture.id())
+    }
+
+    pub fn get(
+        &mut self,
+        ctx: &egui::Context,
+        vfs: &Vfs,
+        library: &mut TileLibrary,
+        tile: usize,
+        sub_tile: u8,
+    ) -> Option<AtlasTile> {
+        if let Some(t) = self.tiles.get(&(tile, sub_tile)) {
+            return *t;
+        }
+        let packed = self.pack(ctx, vfs, library, tile, sub_tile);
+        self.tiles.insert((tile, sub_tile), packed);
+        packed
+    }
+
+    fn pack(
+        &mut self,
+        ctx: &egui::Context,
+        vfs: &Vfs,
+        library: &mut TileLibrary,
+        tile: usize,
+        sub_tile: u8,
+    ) -> Option<AtlasTile> {
+        let palette = self.palette.clone()?;
+        let tmp = library.tmp(vfs, tile)?;
+        let (bw, bh) = (tmp.block_width, tmp.block_height);
+        let cell = tmp.cell(sub_tile as usize)?.clone();
+        let diamond = self.upload(ctx, &palette, bw, bh, &cell.pixels)?;
+        let extra = match &cell.extra {
+            Some(e) if e.width > 0 && e.height > 0 => {
+                let rect = self.upload(ctx, &palette, e.width, e.height, &e.pixels)?;
+                let scale = bw as f32;
+                let min = egui::pos2((e.x - cell.x) as f32 / scale, (e.y - cell.y) as f32 / scale);
+                let size = egui::vec2(e.width as f32 / scale, e.height as f32 / scale);
+                Some((egui::Rect::from_min_size(min, size), rect))
+            }
+            _ => None,
+        };
+        Some(AtlasTile { diamond, extra })
+    }
+
+    /// Copy a `width * height` palette image into free atlas space.
+    fn upload(&mut self, ctx: &egui::Context, palette: &Palette, width: u32, height: u32, pixels: &[u8]) -> Option<AtlasRect> {
+        let (w, h) = (width as usize, height as usize);
+        let (page, [x, y]) = self.alloc(ctx, w, h)?;
+        let rgba: Vec<u8> = pixels.iter().flat_map(|&i| palette.rgba(i)).collect();
+        let image = egui::ColorImage::from_rgba_unmultiplied([w, h], &rgba);
+        self.pages[page].texture.set_partial([x, y], image, egui::TextureOptions::NEAREST);
+        let size = PAGE_SIZE as f32;
+        let uv = egui::Rect::from_min_size(egui::pos2(x as f32 / size, y as f32 / size), egui::vec2(w as f32 / size, h as f32 / size));
+        Some(AtlasRect { page, uv })
+    }
+
+    /// Shelf packing: fill rows left to right, then open a new page.
+    fn alloc(&mut self, ctx: &egui::Context, w: usize, h: usize) -> Option<(usize, [usize; 2])> {
+        if w == 0 || h == 0 || w > PAGE_SIZE || h > PAGE_SIZE {
+            return None;
+        }
+        if let Some(p) = self.pages.last_mut() {
+            if p.cursor[0] + w > PAGE_SIZE {
+                p.cursor = [0, p.cursor[1] + p.row_height];
+                p.row_height = 0;
+            }
+            if p.cursor[1] + h <= PAGE_SIZE {
+                let at = p.cursor;
+                p.cursor[0] += w + PADDING;
+                p.row_height = p.row_height.max(h + PADDING);
+                return Some((self.pages.len() - 1, at));
+            }
+        }
+        let blank = egui::ColorImage::new([PAGE_SIZE, PAGE_SIZE], egui::Color32::TRANSPARENT);
+        let name = format!("tile-atlas:{}:{}", self.palette_name, self.pages.len());
+        let texture = ctx.load_texture(name, blank, egui::TextureOptions::NEAREST);
+        self.pages.push(Page { texture, cursor: [w + PADDING, 0], row_height: h + PADDING });
+        Some((self.pages.len() - 1, [0, 0]))
+    }
+}
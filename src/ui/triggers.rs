@@ -0,0 +1,347 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use crate::backend::{
+    action_name, event_name, letters_to_waypoint, named_action_count, named_event_count, waypoint_to_letters,
+    Action, EditorLayout, EditorObjects, Event, MapPreview, ReferenceKind, StringTable, Trigger, TriggerEdit,
+    WorkspaceSettings,
+};
+
+#[derive(Default)]
+pub struct TriggerPanel {
+    selected: Option<String>,
+    /// Trigger count when "New" was clicked; the newest trigger is selected once
+    /// the backend has added it.
+    select_new: Option<usize>,
+}
+
+/// Trigger editor. Works on a copy of the selected trigger and sends the result
+/// back as `TriggerEdit::Update`, so the backend stays the only writer.
+#[allow(clippy::too_many_arguments)]
+pub fn ui_triggers(
+    mut ctx: EguiContexts,
+    preview: Res<MapPreview>,
+    objs: Res<EditorObjects>,
+    mut layout: ResMut<EditorLayout>,
+    mut settings: ResMut<WorkspaceSettings>,
+    mut edits: EventWriter<TriggerEdit>,
+    mut panel: Local<TriggerPanel>,
+    strings: Res<StringTable>,
+) {
+    let ctx = ctx.ctx_mut();
+
+    settings.highlight = None;
+    if preview.document.is_none() || !layout.show_triggers {
+        return;
+    }
+
+    let set = &preview.triggers;
+    if panel.select_new.is_some_and(|n| set.triggers.len() > n) {
+        panel.selected = set.triggers.last().map(|t| t.id.clone());
+        panel.select_new = None;
+    }
+
+    let houses: Vec<String> = preview.houses.iter().map(|h| h.name.clone()).collect();
+    let teams: Vec<(String, String)> = preview.ai.teams.iter().map(|t| (t.id.clone(), t.name.clone())).collect();
+    let trigger_ids: Vec<(String, String)> = set.triggers.iter().map(|t| (t.id.clone(), t.name.clone())).collect();
+    let refs = Lists { houses: &houses, teams: &teams, triggers: &trigger_ids, objs: &objs, strings: &strings };
+    let errors = set.validate(&preview.houses, &objs.waypoints, &preview.ai.team_ids());
+
+    let mut open = true;
+    egui::Window::new("Triggers")
+        .open(&mut open)
+        .default_size(egui::vec2(720.0, 420.0))
+        .show(ctx, |ui| {
+            ui.horizontal_top(|ui| {
+                // --- trigger list ---
+                ui.vertical(|ui| {
+                    ui.set_width(180.0);
+                    egui::ScrollArea::vertical().id_source("triggers/list").max_height(320.0).show(ui, |ui| {
+                        for t in &set.triggers {
+                            let sel = panel.selected.as_deref() == Some(t.id.as_str());
+                            if ui.selectable_label(sel, format!("{}  {}", t.id, t.name)).clicked() {
+                                panel.selected = Some(t.id.clone());
+                            }
+                        }
+                    });
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        if ui.button("New").clicked() {
+                            let house = houses.first().cloned().unwrap_or_else(|| "Neutral".to_string());
+                            edits.send(TriggerEdit::Add { house });
+                            panel.select_new = Some(set.triggers.len());
+                        }
+                        if ui.add_enabled(panel.selected.is_some(), egui::Button::new("Delete")).clicked()
+                            && let Some(id) = panel.selected.take()
+                        {
+                            edits.send(TriggerEdit::Remove { id });
+                        }
+                    });
+                });
+                ui.separator();
+
+                // --- selected trigger ---
+                ui.vertical(|ui| {
+                    let Some(original) = panel.selected.as_deref().and_then(|id| set.trigger(id)) else {
+                        ui.label(egui::RichText::new("Select a trigger").italics());
+                        return;
+                    };
+                    let mut t = original.clone();
+                    egui::ScrollArea::vertical().id_source("triggers/edit").show(ui, |ui| {
+                        trigger_props(ui, &mut t, &refs);
+                        ui.separator();
+                        event_list(ui, &mut t.events, &refs);
+                        ui.separator();
+                        if let Some(cell) = action_list(ui, &mut t.actions, &refs) {
+                            settings.highlight = Some(cell);
+                        }
+                    });
+                    if t != *original {
+                        edits.send(TriggerEdit::Update(t));
+                    }
+                });
+            });
+            if !errors.is_empty() {
+                ui.separator();
+                egui::ScrollArea::vertical().id_source("triggers/errors").max_height(80.0).show(ui, |ui| {
+                    for e in &errors {
+                        ui.colored_label(egui::Color32::from_rgb(230, 170, 60), format!("⚠ {e}"));
+                    }
+                });
+            }
+        });
+    if !open {
+        layout.show_triggers = false;
+    }
+}
+
+/// Choices for the reference dropdowns.
+struct Lists<'a> {
+    houses: &'a [String],
+    /// `(id, name)` of every TeamType.
+    teams: &'a [(String, String)],
+    /// `(id, name)` of every trigger.
+    triggers: &'a [(String, String)],
+    objs: &'a EditorObjects,
+    strings: &'a StringTable,
+}
+
+fn trigger_props(ui: &mut egui::Ui, t: &mut Trigger, refs: &Lists) {
+    egui::Grid::new("triggers/props").num_columns(2).show(ui, |ui| {
+        ui.label("Name");
+        if ui.text_edit_singleline(&mut t.name).changed() {
+            t.name.retain(|c| c != ','); // the name is one field of a comma list
+        }
+        ui.end_row();
+
+        ui.label("House");
+        egui::ComboBox::from_id_source("triggers/house").selected_text(t.house.as_str()).show_ui(ui, |ui| {
+            for h in refs.houses {
+                ui.selectable_value(&mut t.house, h.clone(), h);
+            }
+        });
+        ui.end_row();
+
+        ui.label("Linked");
+        let linked = t.linked.clone().unwrap_or_else(|| "<none>".to_string());
+        egui::ComboBox::from_id_source("triggers/linked").selected_text(linked).show_ui(ui, |ui| {
+            ui.selectable_value(&mut t.linked, None, "<none>");
+            for (id, name) in refs.triggers.iter().filter(|(id, _)| *id != t.id) {
+                ui.selectable_value(&mut t.linked, Some(id.clone()), format!("{id}  {name}"));
+            }
+        });
+        ui.end_row();
+
+        ui.label("Flags");
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut t.disabled, "Disabled");
+            ui.checkbox(&mut t.easy, "Easy");
+            ui.checkbox(&mut t.medium, "Medium");
+            ui.checkbox(&mut t.hard, "Hard");
+        });
+        ui.end_row();
+    });
+}
+
+fn kind_label(name: Option<&str>, kind: u32) -> String {
+    match name {
+        Some(n) => format!("{kind}: {n}"),
+        None => format!("{kind}: (unknown)"),
+    }
+}
+
+fn event_list(ui: &mut egui::Ui, events: &mut Vec<Event>, refs: &Lists) {
+    ui.horizontal(|ui| {
+        ui.strong("Events");
+        if ui.small_button("+").clicked() {
+            events.push(Event::new(13));
+        }
+    });
+    let mut remove = None;
+    for (i, e) in events.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_source(("triggers/event", i))
+                .width(200.0)
+                .selected_text(kind_label(event_name(e.kind), e.kind))
+                .show_ui(ui, |ui| {
+                    for k in 0..named_event_count() {
+                        ui.selectable_value(&mut e.kind, k, kind_label(event_name(k), k));
+                    }
+                });
+            ui.add(egui::DragValue::new(&mut e.param_type).range(0..=2).prefix("type "));
+            match Event::reference_kind(e.kind) {
+                Some(kind) => reference_param(ui, ("event", i), kind, &mut e.param, refs),
+                None => {
+                    ui.add(egui::TextEdit::singleline(&mut e.param).desired_width(80.0));
+                }
+            }
+            // Parameter type 2 carries a second value (usually an object type name).
+            match (&mut e.extra, e.param_type == 2) {
+                (Some(x), true) => {
+                    ui.add(egui::TextEdit::singleline(x).desired_width(80.0));
+                }
+                (None, true) => e.extra = Some(String::new()),
+                (Some(_), false) => e.extra = None,
+                (None, false) => {}
+            }
+            if ui.small_button("✖").clicked() {
+                remove = Some(i);
+            }
+        });
+    }
+    if let Some(i) = remove {
+        events.remove(i);
+    }
+}
+
+/// Returns the cell of the waypoint the user is pointing at, for the workspace highlight.
+fn action_list(ui: &mut egui::Ui, actions: &mut Vec<Action>, refs: &Lists) -> Option<(i32, i32)> {
+    ui.horizontal(|ui| {
+        ui.strong("Actions");
+        if ui.small_button("+").clicked() {
+            actions.push(Action::new(11));
+        }
+    });
+    let mut remove = None;
+    let mut highlight = None;
+    for (i, a) in actions.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_source(("triggers/action", i))
+                .width(200.0)
+                .selected_text(kind_label(action_name(a.kind), a.kind))
+                .show_ui(ui, |ui| {
+                    for k in 0..named_action_count() {
+                        ui.selectable_value(&mut a.kind, k, kind_label(action_name(k), k));
+                    }
+                });
+
+            match Action::reference_kind(a.kind) {
+                Some(ReferenceKind::Waypoint) => {
+                    if let Some(cell) = waypoint_param(ui, i, &mut a.waypoint, refs.objs) {
+                        highlight = Some(cell);
+                    }
+                }
+                Some(kind) => reference_param(ui, ("action", i), kind, &mut a.params[1], refs),
+                None => {
+                    for p in &mut a.params {
+                        ui.add(egui::TextEdit::singleline(p).desired_width(48.0));
+                    }
+                    if Action::takes_text(a.kind) {
+                        text_preview(ui, &a.params[1], refs.strings);
+                    }
+                }
+            }
+            if ui.small_button("✖").clicked() {
+                remove = Some(i);
+            }
+        });
+    }
+    if let Some(i) = remove {
+        actions.remove(i);
+    }
+    highlight
+}
+
+/// Dropdown for an event or action parameter that points at `kind`. Waypoints
+/// are numbers here; the waypoint field of actions uses [`waypoint_param`].
+fn reference_param(ui: &mut egui::Ui, id: (&str, usize), kind: ReferenceKind, value: &mut String, refs: &Lists) {
+    match kind {
+        ReferenceKind::House => house_param(ui, id, value, refs.houses),
+        ReferenceKind::Team => pick_param(
+            ui,
+            id,
+            value,
+            refs.teams.iter().map(|(id, name)| (id.clone(), format!("{id}  {name}"))),
+        ),
+        ReferenceKind::Trigger => pick_param(
+            ui,
+            id,
+            value,
+            refs.triggers.iter().map(|(id, name)| (id.clone(), format!("{id}  {name}"))),
+        ),
+        ReferenceKind::Waypoint => pick_param(
+            ui,
+            id,
+            value,
+            refs.objs.waypoints.iter().map(|w| (w.number.to_string(), format!("Waypoint {}", w.number))),
+        ),
+    }
+}
+
+/// House parameters are stored as the `[Houses]` index.
+fn house_param(ui: &mut egui::Ui, id: (&str, usize), value: &mut String, houses: &[String]) {
+    let current = value.parse::<usize>().ok().and_then(|n| houses.get(n)).cloned().unwrap_or_else(|| value.clone());
+    egui::ComboBox::from_id_source(("triggers/house", id)).selected_text(current).show_ui(ui, |ui| {
+        for (n, h) in houses.iter().enumerate() {
+            ui.selectable_value(value, n.to_string(), h);
+        }
+    });
+}
+
+/// The localized text behind a string table label, first line only (all of it on hover).
+fn text_preview(ui: &mut egui::Ui, label: &str, strings: &StringTable) {
+    match strings.get(label) {
+        Some(text) => {
+            let line = text.lines().next().unwrap_or_default();
+            ui.label(egui::RichText::new(format!("“{line}”")).weak()).on_hover_text(text);
+        }
+        // Without string tables loaded every label would look missing.
+        None if !strings.strings.is_empty() => {
+            ui.colored_label(egui::Color32::from_rgb(230, 90, 90), "unknown label");
+        }
+        None => {}
+    }
+}
+
+fn pick_param(
+    ui: &mut egui::Ui,
+    id: (&str, usize),
+    value: &mut String,
+    choices: impl Iterator<Item = (String, String)>,
+) {
+    egui::ComboBox::from_id_source(("triggers/pick", id)).selected_text(value.as_str()).show_ui(ui, |ui| {
+        for (v, label) in choices {
+            ui.selectable_value(value, v, label);
+        }
+    });
+}
+
+fn waypoint_param(ui: &mut egui::Ui, i: usize, letters: &mut String, objs: &EditorObjects) -> Option<(i32, i32)> {
+    let cell_of = |n: u32| objs.waypoints.iter().find(|w| w.number == n).map(|w| (w.x, w.y));
+    let current = letters_to_waypoint(letters);
+    let text = current.map_or_else(|| letters.clone(), |n| format!("Waypoint {n}"));
+
+    let mut hovered = None;
+    let resp = egui::ComboBox::from_id_source(("triggers/waypoint", i)).selected_text(text).show_ui(ui, |ui| {
+        for w in &objs.waypoints {
+            let r = ui.selectable_value(letters, waypoint_to_letters(w.number), format!("Waypoint {}", w.number));
+            if r.hovered() {
+                hovered = Some((w.x, w.y));
+            }
+        }
+    });
+    if current.is_some_and(|n| cell_of(n).is_none()) {
+        ui.colored_label(egui::Color32::from_rgb(230, 90, 90), "missing");
+    }
+    hovered.or_else(|| resp.response.hovered().then(|| current.and_then(cell_of)).flatten())
+}
@@ -1,6 +1,13 @@
 use bevy_egui::{egui, EguiContexts};
-use crate::backend::{MapPreview, MapView, WorkspaceSettings, ToolState, EditorObjects, Tool, theater_color};
+use crate::backend::{MapPreview, MapView, WorkspaceSettings, ToolState, EditorObjects, Tool, Placement, IsoCell, IsoMapGrid, LightingMode, Waypoint, MapObject, ObjectKind, ObjectCatalog, CatalogEntry, CatalogKind, Overlay, House, Smudge, TerrainObject, TileLibrary, Vfs, theater_color, placement_color, kind_color, house_color, next_free_waypoint, snap_facing, START_WAYPOINTS};
 
+use super::sprites::{SpriteCache, SpriteKey, VOXEL_FACINGS};
+use super::tiles::{AtlasRect, TileAtlas};
+
+/// Width in pixels of a cell in the game's own art; sprites scale from it.
+const GAME_TILE_W: f32 = 60.0;
+
+#[allow(clippy::too_many_arguments)]
 pub fn ui_workspace(
     mut ctx: EguiContexts,
     preview: bevy::prelude::Res<MapPreview>,
@@ -8,8 +15,17 @@ pub fn ui_workspace(
     mut settings: bevy::prelude::ResMut<WorkspaceSettings>,
     tool: bevy::prelude::ResMut<ToolState>,
     mut objs: bevy::prelude::ResMut<EditorObjects>,
+    catalog: bevy::prelude::Res<ObjectCatalog>,
+    vfs: bevy::prelude::Res<Vfs>,
+    mut sprites: bevy::prelude::ResMut<SpriteCache>,
+    mut tiles: bevy::prelude::ResMut<TileLibrary>,
+    mut atlas: bevy::prelude::ResMut<TileAtlas>,
 ) {
     let ctx = ctx.ctx_mut();
+    if bevy::prelude::DetectChanges::is_changed(&vfs) {
+        sprites.clear();
+        atlas.clear();
+    }
 
     egui::CentralPanel::default()
         .frame(egui::Frame::default().fill(egui::Color32::BLACK))
@@ -53,18 +69,18 @@ pub fn ui_workspace(
                 && ui.input(|i| i.pointer.button_released(egui::PointerButton::Primary))
                 && response.hovered();
 
-            if let Some(h) = &preview.header {
-                // Geometry
+            if let (Some(h), Some(grid)) = (&preview.header, &preview.iso) {
+                // Geometry: iso cells span 0..dim on both axes; the [Map] Size
+                // rectangle (h.width cells across) fills the panel width at zoom 1.
+                let dim = grid.dim();
                 let panel_w = rect.width();
-                let w_tiles = h.width.max(1) as f32;
-                let h_tiles = h.height.max(1) as f32;
-                let base_tile_w = (panel_w / (w_tiles + h_tiles)) * 2.0;
+                let base_tile_w = panel_w / h.width.max(1) as f32;
                 let tile_w = base_tile_w * view.zoom;
                 let tile_h = tile_w * 0.5;
 
                 // True centering on tile-space midpoint + pan
-                let cx = w_tiles * 0.5;
-                let cy = h_tiles * 0.5;
+                let cx = dim as f32 * 0.5;
+                let cy = dim as f32 * 0.5;
                 let center_offset = egui::vec2(
                     (cx - cy) * (tile_w * 0.5),
                     (cx + cy) * (tile_h * 0.5),
@@ -74,41 +90,227 @@ pub fn ui_workspace(
                     rect.center().y - center_offset.y + view.offset.y,
                 );
 
-                // Fill diamond
+                // Terrain: each IsoMapPack5 cell's TMP tile from the atlas, raised by its
+                // height and tinted by the previewed [Lighting] set. Cells without a
+                // tile (no game directory, missing file) fall back to a flat diamond
+                // in the theater color, lighter with height. Quads are batched into one
+                // mesh per run of the same texture, so cells stay back to front even
+                // when their tiles sit on different atlas pages.
                 let bg = theater_color(h.theater);
-                let left   = cell_to_screen(0.0, 0.0,          tile_w, tile_h, origin);
-                let top    = cell_to_screen(w_tiles, 0.0,      tile_w, tile_h, origin);
-                let right  = cell_to_screen(w_tiles, h_tiles,  tile_w, tile_h, origin);
-                let bottom = cell_to_screen(0.0,     h_tiles,  tile_w, tile_h, origin);
-                painter.add(egui::Shape::convex_polygon(
-                    vec![left, top, right, bottom],
-                    bg,
-                    egui::Stroke::NONE,
-                ));
+                let light = preview.lighting.for_mode(settings.lighting);
+                let tiles_ready = tiles.tiles.as_ref().is_some_and(|t| t.theater == h.theater);
+                if tiles_ready && let Some(palette) = h.theater.iso_palette() {
+                    atlas.set_palette(&vfs, &palette);
+                }
+                let clip = painter.clip_rect();
+                let mut batches: Vec<egui::Mesh> = Vec::new();
+                for c in visible_cells(grid, clip, origin, tile_w, tile_h) {
+                    let tile = match tiles_ready {
+                        true => atlas.get(ctx, &vfs, &mut tiles, c.tile_index() as usize, c.sub_tile),
+                        false => None,
+                    };
+                    let Some(tile) = tile else {
+                        let shaded = height_shade(bg, c.height);
+                        let color = light.map_or(shaded, |l| l.tint(shaded, c.height));
+                        let d = diamond_points(c.x as f32 + 0.5, c.y as f32 + 0.5, tile_w, tile_h, origin);
+                        if clip.intersects(egui::Rect::from_points(&d)) {
+                            let flat = batch(&mut batches, egui::TextureId::default());
+                            let flat = &mut batches[flat];
+                            let i = flat.vertices.len() as u32;
+                            for p in d {
+                                flat.colored_vertex(p, color);
+                            }
+                            flat.add_triangle(i, i + 1, i + 2);
+                            flat.add_triangle(i, i + 2, i + 3);
+                        }
+                        continue;
+                    };
+                    let color = light.map_or(egui::Color32::WHITE, |l| l.tint(egui::Color32::WHITE, c.height));
+                    let center = cell_to_screen(c.x as f32 + 0.5, c.y as f32 + 0.5, tile_w, tile_h, origin);
+                    let top_left = center - egui::vec2(tile_w * 0.5, tile_h * 0.5 + c.height as f32 * tile_h * 0.5);
+                    let diamond = egui::Rect::from_min_size(top_left, egui::vec2(tile_w, tile_h));
+                    add_quad(&mut batches, &atlas, clip, tile.diamond, diamond, color);
+                    if let Some((offset, rect)) = tile.extra {
+                        let at = egui::Rect::from_min_size(top_left + offset.min.to_vec2() * tile_w, offset.size() * tile_w);
+                        add_quad(&mut batches, &atlas, clip, rect, at, color);
+                    }
+                }
+                for mesh in batches {
+                    painter.add(egui::Shape::mesh(mesh));
+                }
 
                 // Optional grid
                 if settings.show_grid {
-                    draw_iso_grid(&painter, origin, h.width, h.height, tile_w, tile_h);
+                    draw_iso_grid(&painter, origin, grid, tile_w, tile_h);
                 }
 
                 // Click behavior
-                if left_clicked {
-                    if let Some(cursor) = ui.input(|i| i.pointer.hover_pos()) {
-                        if let Some((cx, cy)) = pick_cell(cursor, origin, tile_w, tile_h, h.width, h.height) {
-                            match tool.current {
-                                Tool::Select => settings.selected = Some((cx, cy)),
-                                Tool::Spawn | Tool::Resource | Tool::Unit => {
-                                    objs.items.push(crate::backend::Placement { kind: tool.current, x: cx, y: cy });
+                if left_clicked
+                    && let Some(cursor) = ui.input(|i| i.pointer.hover_pos())
+                    && let Some((cx, cy)) = pick_cell(cursor, origin, tile_w, tile_h, dim, dim)
+                    && grid.contains(cx, cy)
+                {
+                    match tool.current {
+                        Tool::Select => settings.selected = Some((cx, cy)),
+                        // One overlay per cell: ore, gems and other overlays replace
+                        // whatever was placed there before.
+                        Tool::Resource(kind) => {
+                            objs.items.retain(|p| (p.x, p.y) != (cx, cy));
+                            let overlay = Some(kind.overlay());
+                            objs.items.push(Placement { kind: tool.current, x: cx, y: cy, overlay });
+                            settings.selected = Some((cx, cy));
+                        }
+                        Tool::Overlay(id) => {
+                            objs.items.retain(|p| (p.x, p.y) != (cx, cy));
+                            let overlay = Some(Overlay { id, data: 0 });
+                            objs.items.push(Placement { kind: tool.current, x: cx, y: cy, overlay });
+                            settings.selected = Some((cx, cy));
+                        }
+                        Tool::Terrain => {
+                            if !tool.object_type.is_empty() && objs.terrain_at(cx, cy).is_none() {
+                                let type_id = tool.object_type.clone();
+                                objs.terrain.push(TerrainObject { type_id, x: cx, y: cy });
+                            }
+                            settings.selected = Some((cx, cy));
+                        }
+                        Tool::Smudge => {
+                            if !tool.object_type.is_empty() && objs.smudges_at(cx, cy).next().is_none() {
+                                objs.smudges.push(Smudge::new(&tool.object_type, cx, cy));
+                            }
+                            settings.selected = Some((cx, cy));
+                        }
+                        // The cell is only selected when the object went in, so a
+                        // refused placement doesn't look like it worked.
+                        Tool::Object(kind) => {
+                            if !tool.object_type.is_empty() {
+                                let object = MapObject::new(kind, &tool.object_house, &tool.object_type, cx, cy);
+                                if objs.place_object(object) {
                                     settings.selected = Some((cx, cy));
+                                } else {
+                                    let what = &tool.object_type;
+                                    println!("[ui] Can't place {what} at ({cx}, {cy}): the cell is taken or there is no owner");
                                 }
                             }
                         }
+                        // Start locations are waypoints 0-7: place the slot, or move it here.
+                        Tool::Spawn => {
+                            if objs.waypoint_at(cx, cy).is_none_or(|w| w.number == tool.spawn_slot) {
+                                let slot = tool.spawn_slot;
+                                objs.waypoints.retain(|w| w.number != slot);
+                                objs.waypoints.push(Waypoint { number: slot, x: cx, y: cy });
+                            }
+                            settings.selected = Some((cx, cy));
+                        }
+                        // Plain waypoints take the lowest free number after the start slots.
+                        Tool::Waypoint => {
+                            if objs.waypoint_at(cx, cy).is_none() {
+                                let number = next_free_waypoint(&objs.waypoints, START_WAYPOINTS);
+                                objs.waypoints.push(Waypoint { number, x: cx, y: cy });
+                            }
+                            settings.selected = Some((cx, cy));
+                        }
+                    }
+                }
+
+                // Delete removes the selected cell's waypoint (other numbers stay as they
+                // are) and any objects, terrain and smudges on it.
+                if tool.current == Tool::Select
+                    && response.hovered()
+                    && ui.input(|i| i.key_pressed(egui::Key::Delete))
+                    && let Some((sx, sy)) = settings.selected
+                {
+                    objs.waypoints.retain(|w| (w.x, w.y) != (sx, sy));
+                    objs.objects.retain(|o| (o.x, o.y) != (sx, sy));
+                    objs.terrain.retain(|t| (t.x, t.y) != (sx, sy));
+                    objs.smudges.retain(|s| (s.x, s.y) != (sx, sy));
+                }
+
+                // Game art from the catalog's SHPs when a game directory is set;
+                // `None` falls back to the plain markers.
+                let theater = h.theater;
+                let tex_ctx = ui.ctx().clone();
+                let mut sprite = |entry: Option<&CatalogEntry>, remap: Option<egui::Color32>, frame: usize| {
+                    let (file, palette) = entry?.sprite(theater)?;
+                    let key = SpriteKey { file, palette, remap: remap.map(|c| [c.r(), c.g(), c.b()]), frame };
+                    sprites.get(&tex_ctx, &vfs, &key).cloned()
+                };
+                let mut overlay_types: Vec<Option<&CatalogEntry>> = vec![None; 256];
+                for e in catalog.of_kind(CatalogKind::Overlay).filter(|e| e.index < 256) {
+                    overlay_types[e.index] = Some(e);
+                }
+                let house_remap = |house: &str| {
+                    preview.houses.iter().find(|h| h.name.eq_ignore_ascii_case(house)).map(|h| house_color(&h.color))
+                };
+
+                // Non-resource overlays (walls, bridges, crates, ...) as small plates;
+                // ore and gems are shown through their Resource placements below.
+                if let Some(overlay) = &preview.overlay {
+                    for (ox, oy, o) in overlay.iter() {
+                        if o.resource().is_none() && grid.contains(ox, oy) {
+                            let tex = sprite(overlay_types[o.id as usize], None, o.data as usize);
+                            match tex {
+                                Some(tex) => draw_sprite(&painter, &tex, cell_to_screen(ox as f32 + 0.5, oy as f32 + 0.5, tile_w, tile_h, origin), tile_w),
+                                None => draw_overlay_plate(&painter, ox as f32 + 0.5, oy as f32 + 0.5, tile_w, tile_h, origin),
+                            }
+                        }
                     }
                 }
 
-                // Draw placed markers
+                // Flat things first: smudges lie on the ground, then placed markers.
+                for s in &objs.smudges {
+                    let tex = sprite(catalog.get(&s.type_id), None, 0);
+                    draw_smudge(&painter, s, tex.as_ref(), tile_w, tile_h, origin);
+                }
                 for p in &objs.items {
-                    draw_marker(&painter, p.kind, p.x as f32 + 0.5, p.y as f32 + 0.5, tile_w, tile_h, origin);
+                    let tex = p.overlay.and_then(|o| sprite(overlay_types[o.id as usize], None, o.data as usize));
+                    draw_marker(&painter, p, tex.as_ref(), tile_w, tile_h, origin);
+                }
+
+                // Standing things back to front, so nearer ones overlap farther ones;
+                // aircraft fly above all of them.
+                let mut standing: Vec<(i32, Standing)> = objs
+                    .terrain
+                    .iter()
+                    .map(|t| (depth(t.x, t.y, 0), Standing::Terrain(t)))
+                    .chain(
+                        objs.objects
+                            .iter()
+                            .filter(|o| o.kind != ObjectKind::Aircraft)
+                            .map(|o| {
+                                // Structures cover their foundation and sort by its front cell.
+                                let (w, h) = match o.kind {
+                                    ObjectKind::Structure => catalog.get(&o.type_id).map_or((1, 1), |e| e.foundation),
+                                    _ => (1, 1),
+                                };
+                                (depth(o.x + w - 1, o.y + h - 1, o.sub_cell), Standing::Object(o, (w, h)))
+                            }),
+                    )
+                    .collect();
+                standing.sort_by_key(|(d, _)| *d);
+                for (_, s) in &standing {
+                    match s {
+                        Standing::Terrain(t) => {
+                            let tex = sprite(catalog.get(&t.type_id), None, 0);
+                            draw_terrain(&painter, t, tex.as_ref(), tile_w, tile_h, origin);
+                        }
+                        Standing::Object(o, size) => {
+                            let entry = catalog.get(&o.type_id);
+                            let tex = sprite(entry, house_remap(&o.house), sprite_frame(o, entry));
+                            draw_object(&painter, o, *size, &preview.houses, tex.as_ref(), tile_w, tile_h, origin);
+                        }
+                    }
+                }
+                let mut aircraft: Vec<&MapObject> =
+                    objs.objects.iter().filter(|o| o.kind == ObjectKind::Aircraft).collect();
+                aircraft.sort_by_key(|o| depth(o.x, o.y, 0));
+                for o in aircraft {
+                    let entry = catalog.get(&o.type_id);
+                    let tex = sprite(entry, house_remap(&o.house), sprite_frame(o, entry));
+                    draw_object(&painter, o, (1, 1), &preview.houses, tex.as_ref(), tile_w, tile_h, origin);
+                }
+
+                for w in &objs.waypoints {
+                    draw_waypoint(&painter, w, tile_w, tile_h, origin);
                 }
 
                 // Draw selection highlight
@@ -120,6 +322,17 @@ pub fn ui_workspace(
                     ));
                 }
 
+                // Cell pointed at from another panel (trigger waypoint picker)
+                if let Some((hx, hy)) = settings.highlight {
+                    let diamond = diamond_points(hx as f32 + 0.5, hy as f32 + 0.5, tile_w, tile_h, origin);
+                    let c = cell_to_screen(hx as f32 + 0.5, hy as f32 + 0.5, tile_w, tile_h, origin);
+                    painter.add(egui::Shape::closed_line(
+                        diamond.to_vec(),
+                        egui::Stroke::new(2.5, egui::Color32::from_rgb(80, 230, 250)),
+                    ));
+                    painter.circle_stroke(c, tile_w.max(12.0), egui::Stroke::new(1.5, egui::Color32::from_rgb(80, 230, 250)));
+                }
+
                 // Overlay mini UI (top-right)
                 egui::Area::new("workspace_overlay".into())
                     .movable(false)
@@ -131,7 +344,7 @@ pub fn ui_workspace(
                                 ui.horizontal(|ui| {
                                     if ui.button("Fit").clicked() {
                                         // Fit: width always fits at zoom=1.0. Height limit:
-                                        let map_h_1: f32 = rect.width() * 0.5;
+                                        let map_h_1: f32 = rect.width() * 0.5 * h.height as f32 / h.width.max(1) as f32;
                                         let s_h: f32 = (rect.height() / map_h_1).min(1.0);
                                         let s: f32 = s_h.min(1.0);
                                         view.zoom = s.clamp(0.2, 5.0);
@@ -144,9 +357,45 @@ pub fn ui_workspace(
                                     }
                                     ui.separator();
                                     ui.toggle_value(&mut settings.show_grid, "Grid");
+                                    egui::ComboBox::from_id_source("workspace/lighting")
+                                        .selected_text(settings.lighting.label())
+                                        .width(90.0)
+                                        .show_ui(ui, |ui| {
+                                            for mode in LightingMode::ALL {
+                                                ui.selectable_value(&mut settings.lighting, mode, mode.label());
+                                            }
+                                        });
                                 });
                                 if let Some((sx, sy)) = settings.selected {
                                     ui.label(format!("Tile: {}, {}", sx, sy));
+                                    if let Some(c) = grid.get(sx, sy) {
+                                        ui.label(format!(
+                                            "Tile #{}.{}  Height {}{}",
+                                            c.tile_index(),
+                                            c.sub_tile,
+                                            c.height,
+                                            if c.is_ice() { "  (ice)" } else { "" },
+                                        ));
+                                    }
+                                    if let Some(o) = preview.overlay.as_ref().and_then(|l| l.get(sx, sy)) {
+                                        ui.label(format!("Overlay #{} (data {})", o.id, o.data));
+                                    }
+                                    if let Some(t) = objs.terrain_at(sx, sy) {
+                                        ui.label(format!("Terrain {}", t.type_id));
+                                    }
+                                    for s in objs.smudges_at(sx, sy) {
+                                        ui.label(format!("Smudge {}", s.type_id));
+                                    }
+                                    for o in objs.objects_at(sx, sy) {
+                                        ui.label(format!("{} {} ({})", o.kind.label(), o.type_id, o.house));
+                                    }
+                                    if let Some(w) = objs.waypoint_at(sx, sy) {
+                                        if w.is_start_location() {
+                                            ui.label(format!("Waypoint {} (start, player {})", w.number, w.number + 1));
+                                        } else {
+                                            ui.label(format!("Waypoint {}", w.number));
+                                        }
+                                    }
                                 } else {
                                     ui.label("Tile: —");
                                 }
@@ -179,60 +428,319 @@ pub fn ui_workspace(
 fn draw_iso_grid(
     painter: &egui::Painter,
     origin: egui::Pos2,
-    w_tiles: i32,
-    h_tiles: i32,
+    grid: &IsoMapGrid,
     tile_w: f32,
     tile_h: f32,
 ) {
-    let grid = egui::Color32::from_rgba_unmultiplied(0, 0, 0, 80);
+    let grid_color = egui::Color32::from_rgba_unmultiplied(0, 0, 0, 80);
+    let dim = grid.dim();
+
+    // Each line is clipped to the cells it borders, so the grid follows the
+    // map rectangle instead of the whole iso diamond.
+    for y in 0..=dim {
+        if let Some((x0, x1)) = span(dim, |x| grid.contains(x, y - 1) || grid.contains(x, y)) {
+            let a = cell_to_screen(x0 as f32, y as f32, tile_w, tile_h, origin);
+            let b = cell_to_screen((x1 + 1) as f32, y as f32, tile_w, tile_h, origin);
+            painter.add(egui::Shape::line_segment([a, b], egui::Stroke::new(1.0, grid_color)));
+        }
+    }
+    for x in 0..=dim {
+        if let Some((y0, y1)) = span(dim, |y| grid.contains(x - 1, y) || grid.contains(x, y)) {
+            let a = cell_to_screen(x as f32, y0 as f32, tile_w, tile_h, origin);
+            let b = cell_to_screen(x as f32, (y1 + 1) as f32, tile_w, tile_h, origin);
+            painter.add(egui::Shape::line_segment([a, b], egui::Stroke::new(1.0, grid_color)));
+        }
+    }
+}
+
+/// Map cells that can reach into `clip`, back to front (by `x + y`, then `x`)
+/// so raised tiles and cliff faces overlap the cells behind them.
+fn visible_cells(
+    grid: &IsoMapGrid,
+    clip: egui::Rect,
+    origin: egui::Pos2,
+    tile_w: f32,
+    tile_h: f32,
+) -> impl Iterator<Item = &IsoCell> {
+    // Cells below the view can be lifted into it (up to 14 levels of half a
+    // cell), and cliff faces hang a few cells above their own diamond.
+    let area = egui::Rect::from_min_max(clip.min - egui::vec2(0.0, 4.0 * tile_h), clip.max + egui::vec2(0.0, 7.5 * tile_h));
+    // Iso x grows toward the bottom right and y toward the bottom left, so
+    // those corners bound the range of each axis.
+    let cell = |p: egui::Pos2| screen_to_cell(p.x, p.y, tile_w, tile_h, origin);
+    let last = grid.dim() - 1;
+    let bound = |v: f32| (v.floor() as i32).clamp(0, last);
+    let (x0, x1) = (bound(cell(area.left_top()).0), bound(cell(area.right_bottom()).0));
+    let (y0, y1) = (bound(cell(area.right_top()).1), bound(cell(area.left_bottom()).1));
+    (x0 + y0..=x1 + y1).flat_map(move |d| {
+        ((d - y1).max(x0)..=(d - y0).min(x1)).filter_map(move |x| grid.get(x, d - x))
+    })
+}
+
+/// Index of the last mesh if it draws with `texture`, else of a new one after it.
+fn batch(batches: &mut Vec<egui::Mesh>, texture: egui::TextureId) -> usize {
+    if batches.last().is_none_or(|m| m.texture_id != texture) {
+        batches.push(egui::Mesh::with_texture(texture));
+    }
+    batches.len() - 1
+}
 
-    for y in 0..=h_tiles {
-        let a = cell_to_screen(0.0, y as f32, tile_w, tile_h, origin);
-        let b = cell_to_screen(w_tiles as f32, y as f32, tile_w, tile_h, origin);
-        painter.add(egui::Shape::line_segment([a, b], egui::Stroke::new(1.0, grid)));
+/// Append a textured quad in draw order, skipping it when off screen.
+fn add_quad(
+    batches: &mut Vec<egui::Mesh>,
+    atlas: &TileAtlas,
+    clip: egui::Rect,
+    image: AtlasRect,
+    rect: egui::Rect,
+    color: egui::Color32,
+) {
+    if !clip.intersects(rect) {
+        return;
     }
-    for x in 0..=w_tiles {
-        let a = cell_to_screen(x as f32, 0.0, tile_w, tile_h, origin);
-        let b = cell_to_screen(x as f32, h_tiles as f32, tile_w, tile_h, origin);
-        painter.add(egui::Shape::line_segment([a, b], egui::Stroke::new(1.0, grid)));
+    if let Some(texture) = atlas.texture(image.page) {
+        let i = batch(batches, texture);
+        batches[i].add_rect_with_uv(rect, image.uv, color);
     }
 }
 
+/// First and last index in `0..dim` for which `inside` holds.
+fn span(dim: i32, inside: impl Fn(i32) -> bool) -> Option<(i32, i32)> {
+    let first = (0..dim).find(|&i| inside(i))?;
+    let last = (0..dim).rev().find(|&i| inside(i))?;
+    Some((first, last))
+}
+
+/// Brighten the theater color with cell height so relief is visible before real tiles are drawn.
+fn height_shade(base: egui::Color32, height: u8) -> egui::Color32 {
+    let k = 1.0 + height as f32 * 0.06;
+    let ch = |c: u8| (c as f32 * k).min(255.0) as u8;
+    egui::Color32::from_rgb(ch(base.r()), ch(base.g()), ch(base.b()))
+}
+
+/// Placed marker; ore, gems and overlays use their overlay's `sprite` if there is one.
 fn draw_marker(
     painter: &egui::Painter,
-    kind: Tool,
-    cx_center: f32,
-    cy_center: f32,
+    p: &Placement,
+    sprite: Option<&egui::TextureHandle>,
     tile_w: f32,
     tile_h: f32,
     origin: egui::Pos2,
 ) {
     use egui::{Color32, Stroke};
 
+    let (cx_center, cy_center) = (p.x as f32 + 0.5, p.y as f32 + 0.5);
     let c = cell_to_screen(cx_center, cy_center, tile_w, tile_h, origin);
-    match kind {
+    let fill = placement_color(p);
+    if let Some(tex) = sprite {
+        draw_sprite(painter, tex, c, tile_w);
+        return;
+    }
+    match p.kind {
         Tool::Spawn => {
             // filled circle
-            painter.circle_filled(c, (tile_h * 0.4) as f32, Color32::from_rgb(60, 220, 120));
-            painter.circle_stroke(c, (tile_h * 0.4) as f32, Stroke::new(2.0, Color32::BLACK));
+            painter.circle_filled(c, tile_h * 0.4, fill);
+            painter.circle_stroke(c, tile_h * 0.4, Stroke::new(2.0, Color32::BLACK));
         }
-        Tool::Resource => {
+        Tool::Resource(_) => {
             // filled diamond
             let d = diamond_points(cx_center, cy_center, tile_w, tile_h, origin);
-            painter.add(egui::Shape::convex_polygon(d.to_vec(), Color32::from_rgb(245, 210, 60), Stroke::new(1.5, Color32::BLACK)));
+            painter.add(egui::Shape::convex_polygon(d.to_vec(), fill, Stroke::new(1.5, Color32::BLACK)));
         }
-        Tool::Unit => {
-            // triangle
-            let size = tile_h * 0.55;
-            let p1 = egui::pos2(c.x, c.y - size * 0.8);
-            let p2 = egui::pos2(c.x - size * 0.7, c.y + size * 0.5);
-            let p3 = egui::pos2(c.x + size * 0.7, c.y + size * 0.5);
-            painter.add(egui::Shape::convex_polygon(vec![p1, p2, p3], Color32::from_rgb(60, 200, 245), Stroke::new(1.5, Color32::BLACK)));
+        Tool::Overlay(_) => draw_overlay_plate(painter, cx_center, cy_center, tile_w, tile_h, origin),
+        Tool::Select | Tool::Object(_) | Tool::Terrain | Tool::Smudge | Tool::Waypoint => {}
+    }
+}
+
+/// A full SHP canvas centered on `center`, scaled from the game's cell size.
+fn draw_sprite(painter: &egui::Painter, tex: &egui::TextureHandle, center: egui::Pos2, tile_w: f32) {
+    let size = tex.size_vec2() * (tile_w / GAME_TILE_W);
+    let uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+    painter.image(tex.id(), egui::Rect::from_center_size(center, size), uv, egui::Color32::WHITE);
+}
+
+/// SHP frame for an object's facing. Infantry store eight standing frames,
+/// counter-clockwise from north; voxel models are rendered at the nearest of
+/// their facings; other sprites are drawn from their first frame.
+fn sprite_frame(o: &MapObject, entry: Option<&CatalogEntry>) -> usize {
+    if entry.is_some_and(|e| e.voxel) {
+        let facing = snap_facing(o.facing.rem_euclid(256) as u8, VOXEL_FACINGS as u32);
+        return facing as usize * VOXEL_FACINGS / 256;
+    }
+    match o.kind {
+        ObjectKind::Infantry => ((8 - ((o.facing + 16) / 32) % 8) % 8) as usize,
+        _ => 0,
+    }
+}
+
+/// Something drawn standing on a cell, sorted by `depth`.
+enum Standing<'a> {
+    Terrain(&'a TerrainObject),
+    /// With its foundation in cells.
+    Object(&'a MapObject, (i32, i32)),
+}
+
+/// Iso draw order: cells further down the screen (larger `x + y`) are nearer.
+/// Infantry in sub-cell 4 stand in the front part of their cell.
+fn depth(x: i32, y: i32, sub_cell: i32) -> i32 {
+    (x + y) * 2 + i32::from(sub_cell == 4)
+}
+
+/// Terrain `sprite`, or without one: trees (`TREE*`, `TC*`) as a canopy on a
+/// trunk and other terrain as a rock.
+fn draw_terrain(
+    painter: &egui::Painter,
+    t: &TerrainObject,
+    sprite: Option<&egui::TextureHandle>,
+    tile_w: f32,
+    tile_h: f32,
+    origin: egui::Pos2,
+) {
+    use egui::{Color32, Stroke};
+
+    let c = cell_to_screen(t.x as f32 + 0.5, t.y as f32 + 0.5, tile_w, tile_h, origin);
+    if let Some(tex) = sprite {
+        draw_sprite(painter, tex, c, tile_w);
+        return;
+    }
+    let id = t.type_id.to_ascii_uppercase();
+    let stroke = Stroke::new(1.0, Color32::BLACK);
+    if id.starts_with("TREE") || id.starts_with("TC") {
+        let top = c - egui::vec2(0.0, tile_h * 0.6);
+        painter.line_segment([c, top], Stroke::new(2.0, Color32::from_rgb(90, 60, 30)));
+        painter.circle(top, tile_h * 0.4, kind_color(Tool::Terrain), stroke);
+    } else {
+        let r = tile_h * 0.3;
+        let pts = vec![c + egui::vec2(-r * 1.2, 0.0), c + egui::vec2(-r * 0.4, -r), c + egui::vec2(r, -r * 0.6), c + egui::vec2(r * 1.2, 0.0)];
+        painter.add(egui::Shape::convex_polygon(pts, Color32::from_rgb(130, 120, 110), stroke));
+    }
+}
+
+/// Smudge `sprite`, or a dark blot flat on the cell.
+fn draw_smudge(
+    painter: &egui::Painter,
+    s: &Smudge,
+    sprite: Option<&egui::TextureHandle>,
+    tile_w: f32,
+    tile_h: f32,
+    origin: egui::Pos2,
+) {
+    let c = cell_to_screen(s.x as f32 + 0.5, s.y as f32 + 0.5, tile_w, tile_h, origin);
+    if let Some(tex) = sprite {
+        draw_sprite(painter, tex, c, tile_w);
+        return;
+    }
+    let d = diamond_points(s.x as f32 + 0.5, s.y as f32 + 0.5, tile_w, tile_h, origin).map(|p| c + (p - c) * 0.6);
+    painter.add(egui::Shape::convex_polygon(d.to_vec(), kind_color(Tool::Smudge), egui::Stroke::NONE));
+}
+
+/// Objects in their owner's color: their `sprite` when there is one, else
+/// vehicles as a triangle pointing along their facing, infantry as dots in
+/// their sub-cell, structures as a plate over their `foundation` (cells from
+/// `x, y`), aircraft as a chevron.
+#[allow(clippy::too_many_arguments)]
+fn draw_object(
+    painter: &egui::Painter,
+    o: &MapObject,
+    foundation: (i32, i32),
+    houses: &[House],
+    sprite: Option<&egui::TextureHandle>,
+    tile_w: f32,
+    tile_h: f32,
+    origin: egui::Pos2,
+) {
+    use egui::{Color32, Stroke};
+
+    let fill = houses
+        .iter()
+        .find(|h| h.name.eq_ignore_ascii_case(&o.house))
+        .map_or_else(|| kind_color(Tool::Object(o.kind)), |h| house_color(&h.color));
+    let stroke = Stroke::new(1.5, Color32::BLACK);
+    let c = cell_to_screen(o.x as f32 + 0.5, o.y as f32 + 0.5, tile_w, tile_h, origin);
+    // Facing 0 is north (up-right on screen), counting clockwise in 256 steps.
+    let angle = o.facing as f32 / 256.0 * std::f32::consts::TAU + std::f32::consts::FRAC_PI_4;
+    let dir = egui::vec2(angle.sin(), -angle.cos() * 0.5);
+    let side = egui::vec2(-dir.y * 2.0, dir.x * 0.5);
+    // Sub-cells 2-4 sit left, right and bottom of the cell center.
+    let sub_offset = match o.sub_cell {
+        2 => egui::vec2(-tile_w * 0.2, 0.0),
+        3 => egui::vec2(tile_w * 0.2, 0.0),
+        4 => egui::vec2(0.0, tile_h * 0.25),
+        _ => egui::Vec2::ZERO,
+    };
+    if let Some(tex) = sprite {
+        let center = match o.kind {
+            ObjectKind::Infantry => c + sub_offset,
+            ObjectKind::Structure => {
+                let (w, h) = (foundation.0 as f32, foundation.1 as f32);
+                cell_to_screen(o.x as f32 + w * 0.5, o.y as f32 + h * 0.5, tile_w, tile_h, origin)
+            }
+            ObjectKind::Unit | ObjectKind::Aircraft => c,
+        };
+        draw_sprite(painter, tex, center, tile_w);
+        return;
+    }
+    match o.kind {
+        ObjectKind::Unit => {
+            let s = tile_w * 0.3;
+            let pts = vec![c + dir * s * 1.2, c - dir * s * 0.8 + side * s, c - dir * s * 0.8 - side * s];
+            painter.add(egui::Shape::convex_polygon(pts, fill, stroke));
+        }
+        ObjectKind::Infantry => {
+            painter.circle(c + sub_offset, tile_h * 0.16, fill, stroke);
+        }
+        ObjectKind::Structure => {
+            let (w, h) = (foundation.0 as f32, foundation.1 as f32);
+            let (x, y) = (o.x as f32, o.y as f32);
+            let mid = cell_to_screen(x + w * 0.5, y + h * 0.5, tile_w, tile_h, origin);
+            let corners = [(x, y), (x + w, y), (x + w, y + h), (x, y + h)]
+                .map(|(cx, cy)| cell_to_screen(cx, cy, tile_w, tile_h, origin))
+                .map(|p| mid + (p - mid) * 0.9);
+            painter.add(egui::Shape::convex_polygon(corners.to_vec(), fill, stroke));
+            painter.add(egui::Shape::closed_line(corners.map(|p| mid + (p - mid) * 0.5).to_vec(), stroke));
+        }
+        ObjectKind::Aircraft => {
+            let s = tile_w * 0.3;
+            let pts = vec![c + dir * s, c - dir * s * 0.6 + side * s, c - dir * s * 0.2, c - dir * s * 0.6 - side * s];
+            painter.add(egui::Shape::line(pts.clone(), Stroke::new(2.5, Color32::BLACK)));
+            painter.add(egui::Shape::line(pts, Stroke::new(1.5, fill)));
         }
-        Tool::Select => {}
     }
 }
 
+/// Numbered flag: start locations (0-7) in the spawn color, others in the waypoint color.
+fn draw_waypoint(painter: &egui::Painter, w: &Waypoint, tile_w: f32, tile_h: f32, origin: egui::Pos2) {
+    let c = cell_to_screen(w.x as f32 + 0.5, w.y as f32 + 0.5, tile_w, tile_h, origin);
+    let fill = kind_color(if w.is_start_location() { Tool::Spawn } else { Tool::Waypoint });
+    let r = tile_h * 0.45;
+    painter.circle_filled(c, r, fill);
+    painter.circle_stroke(c, r, egui::Stroke::new(1.5, egui::Color32::BLACK));
+    painter.text(
+        c,
+        egui::Align2::CENTER_CENTER,
+        w.number.to_string(),
+        egui::FontId::proportional((r * 1.2).max(6.0)),
+        egui::Color32::BLACK,
+    );
+}
+
+fn draw_overlay_plate(
+    painter: &egui::Painter,
+    cx_center: f32,
+    cy_center: f32,
+    tile_w: f32,
+    tile_h: f32,
+    origin: egui::Pos2,
+) {
+    let c = cell_to_screen(cx_center, cy_center, tile_w, tile_h, origin);
+    let d = diamond_points(cx_center, cy_center, tile_w, tile_h, origin)
+        .map(|p| c + (p - c) * 0.45);
+    painter.add(egui::Shape::convex_polygon(
+        d.to_vec(),
+        egui::Color32::from_rgb(150, 150, 160),
+        egui::Stroke::new(1.0, egui::Color32::BLACK),
+    ));
+}
+
 fn cell_to_screen(cx: f32, cy: f32, tile_w: f32, tile_h: f32, origin: egui::Pos2) -> egui::Pos2 {
     let x = (cx - cy) * (tile_w * 0.5);
     let y = (cx + cy) * (tile_h * 0.5);